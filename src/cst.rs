@@ -0,0 +1,1165 @@
+/*
+ * This Source Code Form is subject to the terms of the
+ * Mozilla Public License, v. 2.0. If a copy of the MPL
+ * was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+//! This module exposes a lossless concrete syntax tree
+//! (CST) of a YAML stream, for making targeted edits to a
+//! document while preserving everything else about it.
+//!
+//! A [`Cst`] is built from the stream's tokens and the
+//! trivia between them -- whitespace, indentation and
+//! comments -- and retains the exact source text of every
+//! node, including any quoting, block scalar headers and
+//! node properties. The stream's text can be walked as a
+//! sequence of [`Piece`]s, which together cover every byte
+//! of the source.
+//!
+//! Any node may be edited, either by replacing its source
+//! text outright with [`Cst::replace`], or for scalars, by
+//! giving it a new value with [`Cst::set_scalar`], which
+//! keeps the scalar's style and node properties where
+//! possible. Rendering the tree with [`Cst::to_string`]
+//! reproduces the original stream byte for byte, except
+//! for the edited nodes.
+//!
+//! Edits do not change the tree itself, the nodes you
+//! inspect will always describe the original stream. Parse
+//! the rendered output again to inspect the edited stream.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use yary::cst;
+//!
+//! let yaml = "\
+//! dependencies:
+//!   foo: '1.2.0'  # pinned, see #42
+//!   bar: 0.3
+//! ";
+//!
+//! let mut tree = cst::parse(yaml)?;
+//!
+//! let foo = tree
+//!     .document(0)
+//!     .and_then(|root| root.get("dependencies"))
+//!     .and_then(|deps| deps.get("foo"))
+//!     .map(|node| node.id())
+//!     .unwrap();
+//!
+//! tree.set_scalar(foo, "1.3.0")?;
+//!
+//! assert_eq!(
+//!     tree.to_string(),
+//!     "\
+//! dependencies:
+//!   foo: '1.3.0'  # pinned, see #42
+//!   bar: 0.3
+//! "
+//! );
+//! # Ok::<(), yary::Error>(())
+//! ```
+//!
+//! [`Cst::to_string`]: ToString::to_string
+
+use std::{collections::BTreeMap, fmt, ops::Range};
+
+use crate::{
+    emit::{block_scalar, inline_scalar},
+    error::{internal::ErrorCode, mkError, Result},
+    event::{
+        from_reader,
+        types::{EventData, Mark, ScalarStyle, Span},
+    },
+    reader::{borrow::BorrowReader, Reader},
+    scanner::flag::O_COMMENTS,
+    token::Marker,
+};
+
+/// Build a [`Cst`] of the given YAML stream.
+///
+/// ## Errors
+///
+/// This function will error if .source is not a valid YAML
+/// stream.
+///
+/// ## Examples
+///
+/// ```rust
+/// use yary::cst;
+///
+/// let yaml = "--- [a, b]  # trailing\n";
+/// let tree = cst::parse(yaml)?;
+///
+/// assert_eq!(tree.document(0).unwrap().text(), "[a, b]");
+/// assert_eq!(tree.to_string(), yaml);
+/// # Ok::<(), yary::Error>(())
+/// ```
+pub fn parse(source: &str) -> Result<Cst<'_>>
+{
+    let mut builder = Builder::new(source);
+
+    let reader = BorrowReader::new(source);
+    for event in from_reader(&reader)
+    {
+        let event = event?;
+        let span = event.span();
+
+        builder.process(event.into(), span)?;
+    }
+
+    let tokens = tokens(source, &reader)?;
+
+    Ok(builder.finish(tokens))
+}
+
+/// A lossless concrete syntax tree of a YAML stream.
+///
+/// See the [module](self) documentation for more
+/// information.
+#[derive(Debug, Clone)]
+pub struct Cst<'de>
+{
+    source:    &'de str,
+    pieces:    Vec<(PieceKind, Range<usize>)>,
+    nodes:     Vec<NodeData>,
+    documents: Vec<NodeId>,
+    /// Replacement text, keyed by the (start, end) range it
+    /// replaces
+    edits:     BTreeMap<(usize, usize), String>,
+}
+
+impl<'de> Cst<'de>
+{
+    /// The source text this tree was built from
+    pub fn source(&self) -> &'de str
+    {
+        self.source
+    }
+
+    /// The number of documents in the stream
+    pub fn len(&self) -> usize
+    {
+        self.documents.len()
+    }
+
+    /// Returns true if the stream contains no documents
+    pub fn is_empty(&self) -> bool
+    {
+        self.documents.is_empty()
+    }
+
+    /// Retrieve the root node of the .index'th document
+    pub fn document(&self, index: usize) -> Option<NodeRef<'_, 'de>>
+    {
+        self.documents.get(index).map(|&id| self.node(id))
+    }
+
+    /// Iterate over the root node of each document in the
+    /// stream
+    pub fn documents(&self) -> impl Iterator<Item = NodeRef<'_, 'de>>
+    {
+        self.documents.iter().map(move |&id| self.node(id))
+    }
+
+    /// Retrieve the node with the given .id
+    ///
+    /// ## Panics
+    ///
+    /// If .id was not produced by this [`Cst`]
+    pub fn node(&self, id: NodeId) -> NodeRef<'_, 'de>
+    {
+        assert!(id.0 < self.nodes.len(), "{:?} is not part of this Cst", id);
+
+        NodeRef { cst: self, id }
+    }
+
+    /// Iterate over the [`Piece`]s making up the original
+    /// source text, in stream order.
+    ///
+    /// Concatenating every piece's text reproduces the
+    /// source exactly.
+    pub fn pieces(&self) -> impl Iterator<Item = Piece<'de>> + '_
+    {
+        let source = self.source;
+
+        self.pieces.iter().map(move |(kind, range)| Piece {
+            kind:   *kind,
+            text:   &source[range.clone()],
+            offset: range.start,
+        })
+    }
+
+    /// Replace the source text of the node .id, including
+    /// any node properties, with .text.
+    ///
+    /// The given text is inserted as is, it is the caller's
+    /// responsibility to ensure that it is valid YAML in
+    /// the node's position. Any edits to the node's
+    /// descendants are discarded.
+    ///
+    /// ## Errors
+    ///
+    /// If the node lies inside of a node which has already
+    /// been replaced.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use yary::cst;
+    ///
+    /// let mut tree = cst::parse("key: [a, b]  # list\n")?;
+    /// let list = tree.document(0).unwrap().get("key").unwrap().id();
+    ///
+    /// tree.replace(list, "{c: d}")?;
+    ///
+    /// assert_eq!(tree.to_string(), "key: {c: d}  # list\n");
+    /// # Ok::<(), yary::Error>(())
+    /// ```
+    pub fn replace<T>(&mut self, id: NodeId, text: T) -> Result<()>
+    where
+        T: Into<String>,
+    {
+        let range = self.node(id).data().range.clone();
+
+        self.edit(range, text.into())
+    }
+
+    /// Set the value of the scalar node .id, keeping its
+    /// node properties and surrounding text intact.
+    ///
+    /// The scalar's existing style is kept if it can
+    /// represent .value, otherwise a quoted style is
+    /// chosen. Block scalars also keep their content's
+    /// indentation, but are rewritten as double quoted
+    /// scalars if .value ends with more than one line
+    /// break, as any trailing empty lines belong to the
+    /// surrounding text.
+    ///
+    /// ## Errors
+    ///
+    /// If the node is not a scalar, or lies inside of a
+    /// node which has already been replaced.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use yary::cst;
+    ///
+    /// let mut tree = cst::parse("name: &n plain\n")?;
+    /// let name = tree.document(0).unwrap().get("name").unwrap().id();
+    ///
+    /// tree.set_scalar(name, "needs: quoting")?;
+    ///
+    /// assert_eq!(tree.to_string(), "name: &n 'needs: quoting'\n");
+    /// # Ok::<(), yary::Error>(())
+    /// ```
+    pub fn set_scalar(&mut self, id: NodeId, value: &str) -> Result<()>
+    {
+        let node = self.node(id).data();
+
+        let (range, text) = match (node.kind, node.style)
+        {
+            (NodeKind::Scalar, Some(style @ ScalarStyle::Literal))
+            | (NodeKind::Scalar, Some(style @ ScalarStyle::Folded)) =>
+            {
+                let text = self
+                    .block_scalar(&node.content, value, style)
+                    .unwrap_or_else(|| inline_scalar(value, style, node.flow));
+
+                (node.content.clone(), text)
+            },
+            (NodeKind::Scalar, Some(style)) =>
+            {
+                let mut text = inline_scalar(value, style, node.flow);
+
+                // Empty scalars may directly follow an indicator
+                if node.content.is_empty()
+                    && !self.source[..node.content.start].ends_with(&[' ', '\t'][..])
+                {
+                    text.insert(0, ' ');
+                }
+
+                (node.content.clone(), text)
+            },
+            _ => return Err(mkError!(ErrorCode::NotScalar, CODE)),
+        };
+
+        self.edit(range, text)
+    }
+
+    /// Render .value as a replacement for the block scalar
+    /// at .content, if it can be represented with .style
+    fn block_scalar(
+        &self,
+        content: &Range<usize>,
+        value: &str,
+        style: ScalarStyle,
+    ) -> Option<String>
+    {
+        let trailing = value.len() - value.trim_end_matches('\n').len();
+        if trailing > 1 || (trailing == 1 && value.len() == 1)
+        {
+            return None;
+        }
+
+        let indentation = |line: &str| line.len() - line.trim_start_matches(' ').len();
+
+        // The parent node's indentation is that of the line
+        // holding the block header
+        let line_start = self.source[..content.start]
+            .rfind('\n')
+            .map_or(0, |i| i + 1);
+        let parent = indentation(&self.source[line_start..content.start]);
+
+        let indent = self.source[content.clone()]
+            .lines()
+            .skip(1)
+            .find(|line| !line.trim().is_empty())
+            .map_or(parent + 2, indentation);
+
+        block_scalar(value, style, indent, parent)
+    }
+
+    /// Discard all edits made to this tree
+    pub fn reset(&mut self)
+    {
+        self.edits.clear()
+    }
+
+    /// Returns true if any edits have been made to this
+    /// tree
+    pub fn is_modified(&self) -> bool
+    {
+        !self.edits.is_empty()
+    }
+
+    fn edit(&mut self, range: Range<usize>, text: String) -> Result<()>
+    {
+        let mut contained = Vec::new();
+
+        for &(start, end) in self.edits.keys()
+        {
+            // Exact matches are simply overwritten
+            if (start, end) == (range.start, range.end)
+            {
+                continue;
+            }
+
+            if range.start <= start && end <= range.end
+            {
+                contained.push((start, end));
+            }
+            else if start < range.end && range.start < end
+            {
+                return Err(mkError!(ErrorCode::EditConflict, CODE));
+            }
+        }
+
+        for key in contained
+        {
+            self.edits.remove(&key);
+        }
+
+        self.edits.insert((range.start, range.end), text);
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Cst<'_>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        let mut cursor = 0;
+
+        for (&(start, end), text) in self.edits.iter()
+        {
+            f.write_str(&self.source[cursor..start])?;
+            f.write_str(text)?;
+
+            cursor = end;
+        }
+
+        f.write_str(&self.source[cursor..])
+    }
+}
+
+/// An opaque handle to a node in a [`Cst`], which remains
+/// valid across edits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(usize);
+
+/// The kind of a [`Cst`] node
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NodeKind
+{
+    /// A scalar, in any style
+    Scalar,
+    /// A block or flow sequence
+    Sequence,
+    /// A block or flow mapping
+    Mapping,
+    /// An alias to an anchored node
+    Alias,
+}
+
+/// A reference to a node in a [`Cst`], through which the
+/// node and its children can be inspected.
+#[derive(Clone, Copy)]
+pub struct NodeRef<'a, 'de>
+{
+    cst: &'a Cst<'de>,
+    id:  NodeId,
+}
+
+impl<'a, 'de> NodeRef<'a, 'de>
+{
+    /// This node's handle, for use with the editing methods
+    /// of [`Cst`]
+    pub fn id(&self) -> NodeId
+    {
+        self.id
+    }
+
+    /// The kind of node this is
+    pub fn kind(&self) -> NodeKind
+    {
+        self.data().kind
+    }
+
+    /// The node's position in the source, including any
+    /// node properties
+    pub fn span(&self) -> Span
+    {
+        self.data().span
+    }
+
+    /// The node's source text, including any node
+    /// properties, quotes or block scalar header
+    pub fn text(&self) -> &'de str
+    {
+        &self.cst.source[self.data().range.clone()]
+    }
+
+    /// The value of this node if it is a scalar, with any
+    /// quoting or escapes processed
+    pub fn value(&self) -> Option<&'a str>
+    {
+        self.data().value.as_deref()
+    }
+
+    /// The style of this node if it is a scalar
+    pub fn style(&self) -> Option<ScalarStyle>
+    {
+        self.data().style
+    }
+
+    /// Returns true if this node is, or is contained
+    /// within, a flow collection
+    pub fn is_flow(&self) -> bool
+    {
+        self.data().flow
+    }
+
+    /// This node's parent collection, if any
+    pub fn parent(&self) -> Option<NodeRef<'a, 'de>>
+    {
+        self.data().parent.map(|id| self.cst.node(id))
+    }
+
+    /// The number of entries in this node, if it is a
+    /// collection, zero otherwise
+    pub fn len(&self) -> usize
+    {
+        match self.kind()
+        {
+            NodeKind::Mapping => self.data().children.len() / 2,
+            _ => self.data().children.len(),
+        }
+    }
+
+    /// Returns true if this node has no entries
+    pub fn is_empty(&self) -> bool
+    {
+        self.len() == 0
+    }
+
+    /// Retrieve the value of the given scalar .key, if this
+    /// node is a mapping
+    pub fn get(&self, key: &str) -> Option<NodeRef<'a, 'de>>
+    {
+        self.entries()
+            .find(|(k, _)| k.value() == Some(key))
+            .map(|(_, v)| v)
+    }
+
+    /// Retrieve the .index'th entry, if this node is a
+    /// sequence
+    pub fn item(&self, index: usize) -> Option<NodeRef<'a, 'de>>
+    {
+        self.items().nth(index)
+    }
+
+    /// Iterate over the (key, value) pairs of this node, if
+    /// it is a mapping
+    pub fn entries(&self) -> impl Iterator<Item = (NodeRef<'a, 'de>, NodeRef<'a, 'de>)>
+    {
+        let cst = self.cst;
+        let children: &'a [NodeId] = match self.kind()
+        {
+            NodeKind::Mapping => &self.data().children,
+            _ => &[],
+        };
+
+        children
+            .chunks_exact(2)
+            .map(move |pair| (cst.node(pair[0]), cst.node(pair[1])))
+    }
+
+    /// Iterate over the entries of this node, if it is a
+    /// sequence
+    pub fn items(&self) -> impl Iterator<Item = NodeRef<'a, 'de>>
+    {
+        let cst = self.cst;
+        let children: &'a [NodeId] = match self.kind()
+        {
+            NodeKind::Sequence => &self.data().children,
+            _ => &[],
+        };
+
+        children.iter().map(move |&id| cst.node(id))
+    }
+
+    fn data(&self) -> &'a NodeData
+    {
+        &self.cst.nodes[self.id.0]
+    }
+}
+
+impl fmt::Debug for NodeRef<'_, '_>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        f.debug_struct("NodeRef")
+            .field("id", &self.id)
+            .field("kind", &self.kind())
+            .field("text", &self.text())
+            .finish()
+    }
+}
+
+/// A contiguous piece of a YAML stream's source text, see
+/// [`Cst::pieces`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Piece<'de>
+{
+    /// What this piece of text is
+    pub kind:   PieceKind,
+    /// The piece's source text
+    pub text:   &'de str,
+    /// Byte offset of the piece in the source
+    pub offset: usize,
+}
+
+/// The kinds of [`Piece`] a YAML stream is made of
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PieceKind
+{
+    /// Blanks at the start of a line
+    Indent,
+    /// Blanks between tokens on the same line
+    Whitespace,
+    /// One or more line breaks
+    LineBreak,
+    /// A comment, including the leading `#`
+    Comment,
+    /// A scalar, including any quotes or block scalar
+    /// header
+    Scalar,
+    /// Any other token, e.g indicators, directives,
+    /// anchors, tags and aliases
+    Syntax,
+}
+
+#[derive(Debug, Clone)]
+struct NodeData
+{
+    kind:     NodeKind,
+    span:     Span,
+    range:    Range<usize>,
+    /// Range of the scalar's content, excluding node
+    /// properties
+    content:  Range<usize>,
+    value:    Option<String>,
+    style:    Option<ScalarStyle>,
+    flow:     bool,
+    parent:   Option<NodeId>,
+    children: Vec<NodeId>,
+}
+
+/// Incremental tree builder, which consumes events one at
+/// a time
+#[derive(Debug)]
+struct Builder<'de>
+{
+    source:    &'de str,
+    nodes:     Vec<NodeData>,
+    documents: Vec<NodeId>,
+    stack:     Vec<NodeId>,
+    root:      Option<NodeId>,
+}
+
+impl<'de> Builder<'de>
+{
+    fn new(source: &'de str) -> Self
+    {
+        Self {
+            source,
+            nodes: Vec::new(),
+            documents: Vec::new(),
+            stack: Vec::new(),
+            root: None,
+        }
+    }
+
+    fn process(&mut self, event: EventData<'_>, span: Span) -> Result<()>
+    {
+        match event
+        {
+            EventData::DocumentEnd(_) => self.documents.extend(self.root.take()),
+            EventData::Scalar(node) =>
+            {
+                let scalar = node.content.evaluate()?;
+                let span = trim_block(self.source, span);
+
+                let id = self.push(NodeKind::Scalar, span, false);
+                let data = &mut self.nodes[id.0];
+
+                data.value = Some(scalar.data().to_string());
+                data.style = Some(scalar.style());
+            },
+            EventData::Alias(_) =>
+            {
+                self.push(NodeKind::Alias, span, false);
+            },
+            EventData::SequenceStart(_) | EventData::MappingStart(_) =>
+            {
+                let kind = match event
+                {
+                    EventData::SequenceStart(_) => NodeKind::Sequence,
+                    _ => NodeKind::Mapping,
+                };
+                let flow = self.source[..span.end.offset].ends_with(&['[', '{'][..]);
+
+                let id = self.push(kind, span, flow);
+                self.stack.push(id);
+            },
+            EventData::SequenceEnd | EventData::MappingEnd =>
+            {
+                if let Some(id) = self.stack.pop()
+                {
+                    let node = &self.nodes[id.0];
+
+                    // Flow collections end with their closing indicator,
+                    // block collections with their last entry
+                    let end = match node.flow
+                    {
+                        true => span.end,
+                        false => node
+                            .children
+                            .last()
+                            .map_or(node.span.end, |child| self.nodes[child.0].span.end),
+                    };
+
+                    let node = &mut self.nodes[id.0];
+                    node.span.end = end;
+                    node.range.end = end.offset;
+                    node.content.end = end.offset;
+                }
+            },
+            _ =>
+            {},
+        }
+
+        Ok(())
+    }
+
+    /// Add a new node, attaching it to its parent
+    fn push(&mut self, kind: NodeKind, span: Span, flow: bool) -> NodeId
+    {
+        let id = NodeId(self.nodes.len());
+        let parent = self.stack.last().copied();
+        let range = span.start.offset..span.end.offset;
+
+        let flow = flow || parent.map_or(false, |p| self.nodes[p.0].flow);
+
+        self.nodes.push(NodeData {
+            kind,
+            span,
+            content: range.clone(),
+            range,
+            value: None,
+            style: None,
+            flow,
+            parent,
+            children: Vec::new(),
+        });
+
+        match parent
+        {
+            Some(parent) => self.nodes[parent.0].children.push(id),
+            None => self.root = Some(id),
+        }
+
+        id
+    }
+
+    fn finish(mut self, tokens: Vec<(PieceKind, Span)>) -> Cst<'de>
+    {
+        let scalars: BTreeMap<usize, usize> = tokens
+            .iter()
+            .filter(|(kind, _)| *kind == PieceKind::Scalar)
+            .map(|(_, span)| (span.end.offset, span.start.offset))
+            .collect();
+        let ends: BTreeMap<usize, Mark> = tokens
+            .iter()
+            .filter(|(kind, _)| *kind != PieceKind::Comment)
+            .map(|(_, span)| (span.end.offset, span.end))
+            .collect();
+
+        for node in self.nodes.iter_mut().filter(|n| n.kind == NodeKind::Scalar)
+        {
+            // Empty scalars are positioned at the next token, move
+            // them back to directly after the previous token
+            if node.range.is_empty()
+            {
+                if let Some((_, &mark)) = ends.range(..=node.range.start).next_back()
+                {
+                    node.span = Span::at(mark);
+                    node.range = mark.offset..mark.offset;
+                }
+            }
+
+            // A scalar's content starts after its node properties,
+            // which is where its scalar token starts. Scalars
+            // without a token are empty, and their content is
+            // inserted after any properties
+            node.content = match scalars.get(&node.range.end)
+            {
+                Some(&start) if node.range.start <= start => start..node.range.end,
+                _ => node.range.end..node.range.end,
+            };
+        }
+
+        Cst {
+            source:    self.source,
+            pieces:    pieces(self.source, &tokens),
+            nodes:     self.nodes,
+            documents: self.documents,
+            edits:     BTreeMap::new(),
+        }
+    }
+}
+
+/// Retrieve the (non virtual) tokens of .source, in
+/// stream order
+fn tokens(source: &str, reader: &BorrowReader<'_>) -> Result<Vec<(PieceKind, Span)>>
+{
+    let mut reader = Reader::new(reader, O_COMMENTS);
+    let mut tokens = Vec::new();
+
+    loop
+    {
+        if reader.queue().is_empty()
+        {
+            reader.scan_tokens()?;
+        }
+
+        let entry = match reader.queue_mut().pop()
+        {
+            Some(entry) => entry,
+            None => break,
+        };
+
+        let kind = match entry.marker()
+        {
+            Marker::Comment => PieceKind::Comment,
+            Marker::Scalar => PieceKind::Scalar,
+            _ => PieceKind::Syntax,
+        };
+        let span = trim_block(source, entry.span());
+
+        if span.start.offset != span.end.offset
+        {
+            tokens.push((kind, span));
+        }
+    }
+
+    tokens.sort_by_key(|(_, span)| (span.start.offset, span.end.offset));
+
+    Ok(tokens)
+}
+
+/// Split .source into pieces, using the given .tokens
+fn pieces(source: &str, tokens: &[(PieceKind, Span)]) -> Vec<(PieceKind, Range<usize>)>
+{
+    let mut pieces = Vec::new();
+    let mut cursor = 0;
+
+    for (kind, span) in tokens
+    {
+        let range = span.start.offset..span.end.offset;
+
+        // Skip any tokens overlapping the previous token
+        if range.start < cursor
+        {
+            continue;
+        }
+
+        trivia(source, cursor..range.start, &mut pieces);
+        cursor = range.end;
+        pieces.push((*kind, range));
+    }
+
+    trivia(source, cursor..source.len(), &mut pieces);
+
+    pieces
+}
+
+/// Split the trivia found in .range into pieces
+fn trivia(source: &str, range: Range<usize>, pieces: &mut Vec<(PieceKind, Range<usize>)>)
+{
+    let mut start = range.start;
+    let mut current: Option<PieceKind> = None;
+
+    for (offset, ch) in source[range.clone()].char_indices()
+    {
+        let offset = range.start + offset;
+        let kind = match ch
+        {
+            '\r' | '\n' => PieceKind::LineBreak,
+            _ if current == Some(PieceKind::Indent) => PieceKind::Indent,
+            _ if offset == 0 || source[..offset].ends_with(&['\r', '\n'][..]) => PieceKind::Indent,
+            _ => PieceKind::Whitespace,
+        };
+
+        if let Some(prev) = current.filter(|&prev| prev != kind)
+        {
+            pieces.push((prev, start..offset));
+            start = offset;
+        }
+
+        current = Some(kind);
+    }
+
+    if let Some(kind) = current
+    {
+        pieces.push((kind, start..range.end));
+    }
+}
+
+/// Block scalars include any trailing blank lines and the
+/// indentation of the line after their content, which we
+/// consider trivia
+fn trim_block(source: &str, span: Span) -> Span
+{
+    let text = &source[span.start.offset..span.end.offset];
+    let content = text.trim_end_matches(|c| matches!(c, ' ' | '\t' | '\r' | '\n'));
+
+    // Only cut at a line break, anything before it on the
+    // same line is content
+    let cut = match text[content.len()..].find(|c| matches!(c, '\r' | '\n'))
+    {
+        Some(cut) => content.len() + cut,
+        None => return span,
+    };
+
+    let mut end = span.start;
+    for c in text[..cut].chars()
+    {
+        end.offset += c.len_utf8();
+        end.column += 1;
+
+        if c == '\n' || (c == '\r' && !text[end.offset - span.start.offset..].starts_with('\n'))
+        {
+            end.line += 1;
+            end.column = 0;
+        }
+    }
+
+    Span::new(span.start, end)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const YAML: &str = "%YAML 1.2
+--- # document
+# leading comment
+name: &name 'yary'   # trailing
+version: \"0.1.3\"
+description: |
+  A YAML parser
+  # not a comment
+
+tags: !!seq
+  - yaml
+  -   parser # indented
+flow: {a: [1, 2], b: *name}
+empty:
+...
+--- second
+";
+
+    fn lookup<'a, 'de>(cst: &'a Cst<'de>, path: &[&str]) -> NodeRef<'a, 'de>
+    {
+        path.iter()
+            .try_fold(cst.document(0).unwrap(), |node, key| node.get(key))
+            .expect("a node at path")
+    }
+
+    #[test]
+    fn round_trip()
+    {
+        let cst = parse(YAML).unwrap();
+
+        assert_eq!(cst.to_string(), YAML);
+        assert!(!cst.is_modified());
+        assert_eq!(cst.len(), 2);
+    }
+
+    #[test]
+    fn pieces_lossless()
+    {
+        let cst = parse(YAML).unwrap();
+
+        let joined: String = cst.pieces().map(|p| p.text).collect();
+        assert_eq!(joined, YAML);
+
+        let comments: Vec<_> = cst
+            .pieces()
+            .filter(|p| p.kind == PieceKind::Comment)
+            .map(|p| p.text)
+            .collect();
+        assert_eq!(
+            comments,
+            vec![
+                "# document",
+                "# leading comment",
+                "# trailing",
+                "# indented"
+            ]
+        );
+
+        let indents: Vec<_> = cst
+            .pieces()
+            .filter(|p| p.kind == PieceKind::Indent)
+            .map(|p| p.text)
+            .collect();
+        assert_eq!(indents, vec!["  ", "  "]);
+    }
+
+    #[test]
+    fn node_text()
+    {
+        let cst = parse(YAML).unwrap();
+
+        assert_eq!(lookup(&cst, &["name"]).text(), "&name 'yary'");
+        assert_eq!(lookup(&cst, &["name"]).value(), Some("yary"));
+        assert_eq!(
+            lookup(&cst, &["description"]).text(),
+            "|\n  A YAML parser\n  # not a comment"
+        );
+        assert_eq!(
+            lookup(&cst, &["description"]).value(),
+            Some("A YAML parser\n# not a comment\n")
+        );
+        assert_eq!(
+            lookup(&cst, &["tags"]).text(),
+            "!!seq\n  - yaml\n  -   parser"
+        );
+        assert_eq!(lookup(&cst, &["flow"]).text(), "{a: [1, 2], b: *name}");
+        assert_eq!(lookup(&cst, &["flow", "b"]).kind(), NodeKind::Alias);
+        assert_eq!(
+            lookup(&cst, &["tags"]).item(1).unwrap().value(),
+            Some("parser")
+        );
+        assert_eq!(lookup(&cst, &["empty"]).value(), Some(""));
+
+        let flow = lookup(&cst, &["flow", "a"]);
+        assert!(flow.is_flow());
+        assert_eq!(flow.len(), 2);
+        assert_eq!(flow.parent().unwrap().text(), "{a: [1, 2], b: *name}");
+
+        assert_eq!(cst.document(1).unwrap().text(), "second");
+    }
+
+    #[test]
+    fn node_span()
+    {
+        let cst = parse(YAML).unwrap();
+        let span = lookup(&cst, &["version"]).span();
+        let offset = YAML.find("\"0.1.3\"").unwrap();
+
+        assert_eq!(span.start, Mark::new(offset, 4, 9));
+        assert_eq!(span.end, Mark::new(offset + 7, 4, 16));
+        assert_eq!(&YAML[span.start.offset..span.end.offset], "\"0.1.3\"");
+    }
+
+    #[test]
+    fn edit_preserves_surroundings()
+    {
+        let mut cst = parse(YAML).unwrap();
+
+        let version = lookup(&cst, &["version"]).id();
+        cst.set_scalar(version, "0.2.0").unwrap();
+
+        let name = lookup(&cst, &["name"]).id();
+        cst.set_scalar(name, "it's").unwrap();
+
+        let expected = YAML
+            .replace("\"0.1.3\"", "\"0.2.0\"")
+            .replace("'yary'", "'it''s'");
+
+        assert!(cst.is_modified());
+        assert_eq!(cst.to_string(), expected);
+
+        cst.reset();
+        assert_eq!(cst.to_string(), YAML);
+    }
+
+    #[test]
+    fn edit_styles()
+    {
+        let mut cst = parse(YAML).unwrap();
+
+        // Block scalars keep their style
+        let description = lookup(&cst, &["description"]).id();
+        cst.set_scalar(description, "multi\nline\n").unwrap();
+
+        // Flow context plain scalars cannot contain flow
+        // indicators
+        let one = lookup(&cst, &["flow", "a"]).item(0).unwrap().id();
+        cst.set_scalar(one, "x, y").unwrap();
+
+        // Plain scalars stay plain where possible
+        let parser = lookup(&cst, &["tags"]).item(1).unwrap().id();
+        cst.set_scalar(parser, "x, y").unwrap();
+
+        // Empty scalars are given a value
+        let empty = lookup(&cst, &["empty"]).id();
+        cst.set_scalar(empty, "filled").unwrap();
+
+        let expected = YAML
+            .replace(
+                "|\n  A YAML parser\n  # not a comment",
+                "|\n  multi\n  line",
+            )
+            .replace("[1, 2]", "['x, y', 2]")
+            .replace("  parser", "  x, y")
+            .replace("empty:", "empty: filled");
+
+        assert_eq!(cst.to_string(), expected);
+
+        // And the edited stream has the expected values
+        let edited = parse(&expected).unwrap();
+
+        assert_eq!(
+            lookup(&edited, &["description"]).value(),
+            Some("multi\nline\n")
+        );
+        assert_eq!(
+            lookup(&edited, &["flow", "a"]).item(0).unwrap().value(),
+            Some("x, y")
+        );
+        assert_eq!(
+            lookup(&edited, &["tags"]).item(1).unwrap().value(),
+            Some("x, y")
+        );
+        assert_eq!(lookup(&edited, &["empty"]).value(), Some("filled"));
+    }
+
+    #[test]
+    fn edit_block_styles()
+    {
+        let yaml = "a: |\n    text\nb:\n- >-\n  folded\nc: |\n  text\n";
+        let mut cst = parse(yaml).unwrap();
+
+        let values = [
+            (vec!["a"], "  indented\nlines\n"),
+            (vec!["b"], "several\nfolded lines"),
+            (vec!["c"], "kept\n\n"),
+        ];
+
+        for (path, value) in values.iter()
+        {
+            let id = match path[0]
+            {
+                "b" => lookup(&cst, path).item(0).unwrap().id(),
+                _ => lookup(&cst, path).id(),
+            };
+
+            cst.set_scalar(id, value).unwrap();
+        }
+
+        // Trailing empty lines can't be kept by a block scalar,
+        // as they are part of the surrounding text
+        let expected = concat!(
+            "a: |4\n      indented\n    lines\n",
+            "b:\n- >-\n  several\n\n  folded lines\n",
+            "c: \"kept\\n\\n\"\n"
+        );
+        assert_eq!(cst.to_string(), expected);
+
+        let edited = parse(expected).unwrap();
+        assert_eq!(lookup(&edited, &["a"]).value(), Some(values[0].1));
+        assert_eq!(
+            lookup(&edited, &["b"]).item(0).unwrap().value(),
+            Some(values[1].1)
+        );
+        assert_eq!(lookup(&edited, &["c"]).value(), Some(values[2].1));
+    }
+
+    #[test]
+    fn edit_replace()
+    {
+        let mut cst = parse(YAML).unwrap();
+
+        let flow = lookup(&cst, &["flow"]).id();
+        let nested = lookup(&cst, &["flow", "a"]).item(0).unwrap().id();
+
+        // Edits to descendants are discarded
+        cst.set_scalar(nested, "3").unwrap();
+        cst.replace(flow, "[]").unwrap();
+
+        assert_eq!(cst.to_string(), YAML.replace("{a: [1, 2], b: *name}", "[]"));
+
+        // But cannot be made inside a replaced node
+        assert_eq!(
+            cst.set_scalar(nested, "4").unwrap_err().classify(),
+            crate::error::Category::Data
+        );
+    }
+
+    #[test]
+    fn edit_not_scalar()
+    {
+        let mut cst = parse(YAML).unwrap();
+        let tags = lookup(&cst, &["tags"]).id();
+
+        assert!(cst.set_scalar(tags, "scalar").is_err());
+        assert!(!cst.is_modified());
+    }
+
+    #[test]
+    fn invalid_yaml()
+    {
+        assert!(parse("key: [unterminated\n").is_err());
+    }
+}
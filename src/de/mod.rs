@@ -0,0 +1,1857 @@
+/*
+ * This Source Code Form is subject to the terms of the
+ * Mozilla Public License, v. 2.0. If a copy of the MPL
+ * was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+//! This module exposes a [`serde`] Deserializer for YAML
+//! documents, available with the `serde` feature.
+//!
+//! String data is borrowed from the underlying read source
+//! where possible, so types that contain `&'de str` can be
+//! deserialized without copying.
+//!
+//! Untagged plain scalars are resolved as null, booleans,
+//! integers or floats when the target type asks for them,
+//! or when the target type is self describing. Resolution
+//! follows the YAML 1.2 core schema, or the YAML 1.1 types
+//! for documents with a `%YAML 1.1` directive. See
+//! [`schema`](crate::schema) for more. Enums can be
+//! represented as a plain scalar (unit variants), a local
+//! tag (`!Variant value`) or a single key mapping
+//! (`Variant: value`).
+//!
+//! Application specific tags can be given meaning with a
+//! [`Registry`] of tag constructors, see
+//! [`StreamDeserializer::with_registry`].
+//!
+//! Events are pulled from the event stream as each document
+//! is deserialized, rather than buffering the document in
+//! advance. Only the events of anchored nodes are recorded,
+//! and replayed for each alias to them, subject to the
+//! expansion [`Limits`] set with
+//! [`StreamDeserializer::set_limits`]. Nodes passed to a
+//! tag constructor are built in full.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use serde::Deserialize;
+//! use yary::{de, reader::borrow::BorrowReader};
+//!
+//! #[derive(Debug, PartialEq, Deserialize)]
+//! struct Config<'a>
+//! {
+//!     name:    &'a str,
+//!     retries: u8,
+//!     verbose: Option<bool>,
+//! }
+//!
+//! let yaml = BorrowReader::new("name: web\nretries: 3\nverbose: ~\n");
+//! let config: Config = de::from_reader(&yaml)?;
+//!
+//! assert_eq!(
+//!     config,
+//!     Config {
+//!         name:    "web",
+//!         retries: 3,
+//!         verbose: None,
+//!     }
+//! );
+//! # Ok::<(), yary::Error>(())
+//! ```
+
+use std::{
+    cell::RefCell,
+    collections::{HashMap, VecDeque},
+    rc::Rc,
+};
+
+use serde::de::{
+    self,
+    value::{BorrowedStrDeserializer, StringDeserializer},
+    DeserializeOwned, DeserializeSeed, Error as _, IntoDeserializer, Visitor,
+};
+
+use crate::{
+    error::{internal::ErrorCode, mkError, Error, Result},
+    event::{
+        expand::Limits,
+        from_reader as events_from_reader,
+        types::{
+            Event, EventData, Node, ResolvedTag, Scalar, ScalarStyle, Slice, Span, TagDirectives,
+            VersionDirective,
+        },
+        EventIter,
+    },
+    reader::{borrow::BorrowReader, Read},
+    schema::{self, Resolved},
+    tree::{
+        self,
+        tag::{Action, Registry},
+        Builder, Yaml,
+    },
+};
+
+/// Deserialize an instance of `T` from the single document
+/// contained in the given read source.
+///
+/// Returns an error if the stream contains more than one
+/// document; use [`StreamDeserializer`] for multi document
+/// streams.
+///
+/// ## Examples
+///
+/// ```rust
+/// use yary::{de, reader::borrow::BorrowReader};
+///
+/// let yaml = BorrowReader::new("[1, 2, 3]");
+/// let list: Vec<u32> = de::from_reader(&yaml)?;
+///
+/// assert_eq!(list, vec![1, 2, 3]);
+/// # Ok::<(), yary::Error>(())
+/// ```
+pub fn from_reader<'de, R, T>(src: &'de R) -> Result<T>
+where
+    R: Read,
+    T: de::Deserialize<'de>,
+{
+    single(StreamDeserializer::new(src))
+}
+
+/// Deserialize an instance of `T` from the single document
+/// contained in the given read source, passing tagged nodes
+/// through the constructors of the given [`Registry`].
+///
+/// See [`StreamDeserializer::with_registry`] for more
+/// information.
+pub fn from_reader_with<'de, R, T>(src: &'de R, registry: &Registry) -> Result<T>
+where
+    R: Read,
+    T: de::Deserialize<'de>,
+{
+    single(StreamDeserializer::with_registry(src, registry))
+}
+
+/// Deserialize the only document in .stream
+fn single<'de, R, T>(mut stream: StreamDeserializer<'de, R>) -> Result<T>
+where
+    R: Read,
+    T: de::Deserialize<'de>,
+{
+    let mut de = match stream.next()
+    {
+        Some(de) => de?,
+        None => return Err(mkError!(ErrorCode::UnexpectedEOF, CODE)),
+    };
+
+    let value = T::deserialize(&mut de)?;
+
+    match stream.next()
+    {
+        None => Ok(value),
+        Some(Err(e)) => Err(e),
+        Some(Ok(_)) => Err(Error::custom("expected a single document, found more")),
+    }
+}
+
+/// Deserialize an instance of `T` from the single document
+/// contained in the given string.
+///
+/// See [`from_reader`] for more information.
+pub fn from_str<T>(s: &str) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    let src = BorrowReader::new(s);
+
+    from_reader(&src)
+}
+
+/// Iterator over the documents in a YAML stream, yielding
+/// a [`Deserializer`] for each.
+///
+/// Each [`Deserializer`] reads its document directly from
+/// the underlying event stream. Requesting the next
+/// document skips whatever remains of the current one,
+/// after which the previous [`Deserializer`] returns an
+/// error for any further reads.
+///
+/// ## Examples
+///
+/// ```rust
+/// use serde::Deserialize;
+/// use yary::{de::StreamDeserializer, reader::borrow::BorrowReader};
+///
+/// let yaml = BorrowReader::new("--- 1\n--- 2\n--- 3\n");
+///
+/// let values = StreamDeserializer::new(&yaml)
+///     .map(|de| de.and_then(|mut de| u8::deserialize(&mut de)))
+///     .collect::<Result<Vec<_>, _>>()?;
+///
+/// assert_eq!(values, vec![1, 2, 3]);
+/// # Ok::<(), yary::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct StreamDeserializer<'de, R>
+{
+    loader: Rc<RefCell<Loader<'de, R>>>,
+    done:   bool,
+}
+
+impl<'de, R> StreamDeserializer<'de, R>
+where
+    R: Read,
+{
+    /// Instantiate a new [`StreamDeserializer`] over the
+    /// given read source
+    pub fn new(src: &'de R) -> Self
+    {
+        let loader = Loader::new(events_from_reader(src).into_iter());
+
+        Self {
+            loader: Rc::new(RefCell::new(loader)),
+            done:   false,
+        }
+    }
+
+    /// Instantiate a new [`StreamDeserializer`] over the
+    /// given read source, passing tagged nodes through the
+    /// constructors of the given [`Registry`].
+    ///
+    /// Each node with a registered tag is built into a
+    /// [`tree::Node`] and replaced by its constructor's
+    /// output, which is then deserialized as though it had
+    /// been written in the document. Constructors should
+    /// therefore produce YAML data, a
+    /// [`Custom`](tree::Custom) value can not be
+    /// deserialized. Aliases inside a constructed node may
+    /// only refer to anchors defined within it.
+    ///
+    /// Unknown tags are handled according to the registry's
+    /// [`Unknown`](tree::tag::Unknown) policy. Note that
+    /// this includes the local tags used to select enum
+    /// variants.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use serde::Deserialize;
+    /// use yary::{
+    ///     de::StreamDeserializer,
+    ///     event::types::{Scalar, ScalarStyle},
+    ///     reader::borrow::BorrowReader,
+    ///     tree::{tag::Registry, Yaml},
+    /// };
+    ///
+    /// let mut registry = Registry::new();
+    /// registry.insert("!minutes", |node| {
+    ///     let minutes: u64 = node.as_str().unwrap_or_default().parse()?;
+    ///     let secs = (minutes * 60).to_string();
+    ///
+    ///     Ok(Yaml::Scalar(Scalar::new(secs.into(), ScalarStyle::Plain)))
+    /// });
+    ///
+    /// let yaml = BorrowReader::new("[!minutes 2, 30]");
+    ///
+    /// let values = StreamDeserializer::with_registry(&yaml, &registry)
+    ///     .map(|de| de.and_then(|mut de| Vec::<u64>::deserialize(&mut de)))
+    ///     .collect::<Result<Vec<_>, _>>()?;
+    ///
+    /// assert_eq!(values, vec![vec![120, 30]]);
+    /// # Ok::<(), yary::Error>(())
+    /// ```
+    pub fn with_registry(src: &'de R, registry: &Registry) -> Self
+    {
+        let this = Self::new(src);
+        this.loader.borrow_mut().registry = Some(registry.clone());
+
+        this
+    }
+
+    /// Set the [`Limits`] applied while replaying aliases
+    /// in each document, which default to
+    /// [`Limits::default`].
+    ///
+    /// Deserializing a document returns an error of
+    /// [`Category::Limit`](crate::error::Category) once any
+    /// limit is exceeded.
+    pub fn set_limits(&mut self, limits: Limits) -> &mut Self
+    {
+        self.loader.borrow_mut().limits = limits;
+
+        self
+    }
+}
+
+impl<'de, R> Iterator for StreamDeserializer<'de, R>
+where
+    R: Read,
+{
+    type Item = Result<Deserializer<'de, R>>;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        if self.done
+        {
+            return None;
+        }
+
+        let document = self.loader.borrow_mut().start_document().transpose();
+
+        if !matches!(document, Some(Ok(_)))
+        {
+            self.done = true;
+        }
+
+        document.map(|res| res.map(|version| Deserializer::new(Rc::clone(&self.loader), version)))
+    }
+}
+
+/// A YAML Deserializer for a single document.
+///
+/// Typically retrieved from a [`StreamDeserializer`], or
+/// used implicitly via [`from_reader`].
+#[derive(Debug)]
+pub struct Deserializer<'de, R>
+{
+    loader:   Rc<RefCell<Loader<'de, R>>>,
+    document: usize,
+    peeked:   Option<Entry<'de>>,
+    untag:    bool,
+    version:  VersionDirective,
+}
+
+impl<'de, R> Deserializer<'de, R>
+where
+    R: Read,
+{
+    fn new(loader: Rc<RefCell<Loader<'de, R>>>, version: VersionDirective) -> Self
+    {
+        let document = loader.borrow().document;
+
+        Self {
+            loader,
+            document,
+            peeked: None,
+            untag: false,
+            version,
+        }
+    }
+
+    /// Look at the next entry, following any aliases
+    fn peek(&mut self) -> Result<&Entry<'de>>
+    {
+        let entry = match self.peeked.take()
+        {
+            Some(entry) => entry,
+            None => self.loader.borrow_mut().next_entry(self.document)?,
+        };
+
+        Ok(self.peeked.get_or_insert(entry))
+    }
+
+    /// Consume the next entry, following any aliases
+    fn next(&mut self) -> Result<Entry<'de>>
+    {
+        match self.peeked.take()
+        {
+            Some(entry) => Ok(entry),
+            None => self.loader.borrow_mut().next_entry(self.document),
+        }
+    }
+
+    /// Consume the next node, including any children
+    fn skip_node(&mut self) -> Result<()>
+    {
+        let mut depth = 0usize;
+
+        loop
+        {
+            match self.next()?
+            {
+                Entry::SequenceStart(_) | Entry::MappingStart(_) => depth += 1,
+                Entry::SequenceEnd | Entry::MappingEnd => depth -= 1,
+                Entry::Scalar(_, _) | Entry::Alias(_, _) =>
+                {},
+            }
+
+            if depth == 0
+            {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Consume the next scalar, returning its resolved tag
+    /// and content
+    fn next_scalar(&mut self) -> Result<(ResolvedTag<'de>, Scalar<'de>)>
+    {
+        let untag = std::mem::take(&mut self.untag);
+
+        match self.next()?
+        {
+            Entry::Scalar(_, scalar) if untag => Ok((non_specific(&scalar), scalar)),
+            Entry::Scalar(tag, scalar) => Ok((tag, scalar)),
+            entry => Err(entry.invalid_type(&"a scalar")),
+        }
+    }
+
+    fn end_sequence(&mut self) -> Result<()>
+    {
+        match self.next()?
+        {
+            Entry::SequenceEnd => Ok(()),
+            _ => Err(Error::custom("expected end of sequence")),
+        }
+    }
+
+    fn end_mapping(&mut self) -> Result<()>
+    {
+        match self.next()?
+        {
+            Entry::MappingEnd => Ok(()),
+            _ => Err(Error::custom("expected end of mapping")),
+        }
+    }
+}
+
+impl<'de, R> de::Deserializer<'de> for &mut Deserializer<'de, R>
+where
+    R: Read,
+{
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.peek()?
+        {
+            Entry::SequenceStart(_) => self.deserialize_seq(visitor),
+            Entry::MappingStart(_) => self.deserialize_map(visitor),
+            _ =>
+            {
+                let (tag, scalar) = self.next_scalar()?;
+
+                match resolve(self.version, &tag, &scalar)?
+                {
+                    Resolved::Null => visitor.visit_unit(),
+                    Resolved::Bool(b) => visitor.visit_bool(b),
+                    Resolved::Int(i) => visitor.visit_i64(i),
+                    Resolved::UInt(u) => visitor.visit_u64(u),
+                    Resolved::Float(f) => visitor.visit_f64(f),
+                    Resolved::Str => visit_slice(visitor, scalar.into_data()),
+                }
+            },
+        }
+    }
+
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let (_, scalar) = self.next_scalar()?;
+
+        visit_slice(visitor, scalar.into_data())
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_char<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_identifier<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let (_, scalar) = self.next_scalar()?;
+
+        match scalar.into_data()
+        {
+            Slice::Borrowed(s) => visitor.visit_borrowed_bytes(s.as_bytes()),
+            Slice::Owned(s) => visitor.visit_byte_buf(s.into_bytes()),
+        }
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let untag = self.untag;
+        let version = self.version;
+        let is_null = match self.peek()?
+        {
+            Entry::Scalar(tag, scalar) =>
+            {
+                let tag = match untag
+                {
+                    true => non_specific(scalar),
+                    false => tag.clone(),
+                };
+
+                matches!(resolve(version, &tag, scalar), Ok(Resolved::Null))
+            },
+            _ => false,
+        };
+
+        if is_null
+        {
+            self.next_scalar()?;
+
+            visitor.visit_none()
+        }
+        else
+        {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let (tag, scalar) = self.next_scalar()?;
+
+        match resolve(self.version, &tag, &scalar)?
+        {
+            Resolved::Null => visitor.visit_unit(),
+            _ => Err(Error::invalid_type(de::Unexpected::Str(&scalar), &"null")),
+        }
+    }
+
+    fn deserialize_unit_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.untag = false;
+
+        match self.next()?
+        {
+            Entry::SequenceStart(_) =>
+            {},
+            entry => return Err(entry.invalid_type(&"a sequence")),
+        }
+
+        let value = visitor.visit_seq(SeqAccess { de: self })?;
+        self.end_sequence()?;
+
+        Ok(value)
+    }
+
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.untag = false;
+
+        match self.next()?
+        {
+            Entry::MappingStart(_) =>
+            {},
+            entry => return Err(entry.invalid_type(&"a mapping")),
+        }
+
+        let value = visitor.visit_map(MapAccess { de: self })?;
+        self.end_mapping()?;
+
+        Ok(value)
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let kind = match self.peek()?
+        {
+            Entry::Scalar(tag, _) | Entry::SequenceStart(tag) | Entry::MappingStart(tag)
+                if is_local(tag) =>
+            {
+                EnumKind::Tagged
+            },
+            Entry::Scalar(_, _) => EnumKind::Scalar,
+            Entry::MappingStart(_) => EnumKind::Mapping,
+            entry => return Err(entry.invalid_type(&"an enum")),
+        };
+
+        visitor.visit_enum(EnumAccess { de: self, kind })
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.untag = false;
+        self.skip_node()?;
+
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64
+    }
+}
+
+struct SeqAccess<'a, 'de, R>
+{
+    de: &'a mut Deserializer<'de, R>,
+}
+
+impl<'de, 'a, R> de::SeqAccess<'de> for SeqAccess<'a, 'de, R>
+where
+    R: Read,
+{
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.de.peek()?
+        {
+            Entry::SequenceEnd => Ok(None),
+            _ => seed.deserialize(&mut *self.de).map(Some),
+        }
+    }
+}
+
+struct MapAccess<'a, 'de, R>
+{
+    de: &'a mut Deserializer<'de, R>,
+}
+
+impl<'de, 'a, R> de::MapAccess<'de> for MapAccess<'a, 'de, R>
+where
+    R: Read,
+{
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match self.de.peek()?
+        {
+            Entry::MappingEnd => Ok(None),
+            _ => seed.deserialize(&mut *self.de).map(Some),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        seed.deserialize(&mut *self.de)
+    }
+}
+
+/// The representation of an enum in the document
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum EnumKind
+{
+    /// A plain scalar naming a unit variant, `Variant`
+    Scalar,
+    /// A local tag naming the variant, `!Variant value`
+    Tagged,
+    /// A single key mapping, `Variant: value`
+    Mapping,
+}
+
+struct EnumAccess<'a, 'de, R>
+{
+    de:   &'a mut Deserializer<'de, R>,
+    kind: EnumKind,
+}
+
+impl<'de, 'a, R> de::EnumAccess<'de> for EnumAccess<'a, 'de, R>
+where
+    R: Read,
+{
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant)>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant = match self.kind
+        {
+            EnumKind::Scalar => seed.deserialize(&mut *self.de)?,
+            EnumKind::Tagged =>
+            {
+                let uri = match self.de.peek()?
+                {
+                    Entry::Scalar(ResolvedTag::Specific(uri), _)
+                    | Entry::SequenceStart(ResolvedTag::Specific(uri))
+                    | Entry::MappingStart(ResolvedTag::Specific(uri)) => uri.clone(),
+                    _ => unreachable!("enum tag checked in deserialize_enum"),
+                };
+                self.de.untag = true;
+
+                // Local tags keep their leading '!'
+                match uri
+                {
+                    Slice::Borrowed(s) =>
+                    {
+                        seed.deserialize(BorrowedStrDeserializer::<Error>::new(&s[1..]))?
+                    },
+                    Slice::Owned(s) =>
+                    {
+                        let de: StringDeserializer<Error> = s[1..].to_string().into_deserializer();
+                        seed.deserialize(de)?
+                    },
+                }
+            },
+            EnumKind::Mapping =>
+            {
+                self.de.next()?;
+                seed.deserialize(&mut *self.de)?
+            },
+        };
+
+        Ok((variant, self))
+    }
+}
+
+impl<'de, 'a, R> de::VariantAccess<'de> for EnumAccess<'a, 'de, R>
+where
+    R: Read,
+{
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()>
+    {
+        match self.kind
+        {
+            EnumKind::Scalar => Ok(()),
+            EnumKind::Tagged => self.de.skip_node(),
+            EnumKind::Mapping =>
+            {
+                self.de.skip_node()?;
+                self.de.end_mapping()
+            },
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let value = seed.deserialize(&mut *self.de)?;
+        self.end()?;
+
+        Ok(value)
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let value = de::Deserializer::deserialize_seq(&mut *self.de, visitor)?;
+        self.end()?;
+
+        Ok(value)
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let value = de::Deserializer::deserialize_map(&mut *self.de, visitor)?;
+        self.end()?;
+
+        Ok(value)
+    }
+}
+
+impl<'de, 'a, R> EnumAccess<'a, 'de, R>
+where
+    R: Read,
+{
+    fn end(self) -> Result<()>
+    {
+        match self.kind
+        {
+            EnumKind::Mapping => self.de.end_mapping(),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A tag, as a (handle, suffix) pair
+type Tag<'de> = (Slice<'de>, Slice<'de>);
+
+/// A document entry, nodes carrying their resolved tag
+#[derive(Debug, Clone)]
+enum Entry<'de>
+{
+    Scalar(ResolvedTag<'de>, Scalar<'de>),
+    SequenceStart(ResolvedTag<'de>),
+    SequenceEnd,
+    MappingStart(ResolvedTag<'de>),
+    MappingEnd,
+    /// A previously completed node, as a [start, end) range
+    /// of recorded entries
+    Alias(usize, usize),
+}
+
+impl<'de> Entry<'de>
+{
+    /// Is this entry the start of a node
+    fn is_node(&self) -> bool
+    {
+        matches!(
+            self,
+            Self::Scalar(_, _) | Self::SequenceStart(_) | Self::MappingStart(_)
+        )
+    }
+
+    fn invalid_type(&self, expected: &dyn de::Expected) -> Error
+    {
+        let unexpected = match self
+        {
+            Self::Scalar(_, scalar) => de::Unexpected::Str(scalar),
+            Self::SequenceStart(_) => de::Unexpected::Seq,
+            Self::MappingStart(_) => de::Unexpected::Map,
+            _ => de::Unexpected::Other("end of collection"),
+        };
+
+        Error::invalid_type(unexpected, expected)
+    }
+}
+
+/// The event stream of a [`StreamDeserializer`], shared
+/// with each [`Deserializer`] it produces
+#[derive(Debug)]
+struct Loader<'de, R>
+{
+    events:   EventIter<'de, R>,
+    registry: Option<Registry>,
+    limits:   Limits,
+
+    /// Number of documents started
+    document: usize,
+    /// Tag directives of the current document
+    tags:     TagDirectives<'de>,
+    /// Has the current document produced any entries
+    started:  bool,
+    /// Has the current document's end been reached
+    ended:    bool,
+
+    /// Entries recorded from anchored nodes in the current
+    /// document
+    log:     Vec<Entry<'de>>,
+    /// Map of anchor names to their node's range in .log
+    anchors: HashMap<Slice<'de>, (usize, usize)>,
+    /// Stack of open collections, and the anchor and .log
+    /// position of each
+    open:    Vec<(Option<Slice<'de>>, usize)>,
+    /// Number of collections in .open which are anchored,
+    /// and thus being recorded
+    record:  usize,
+
+    /// Stack of .log ranges currently being replayed
+    replay: Vec<(usize, usize)>,
+    /// Span of the alias being replayed
+    span:   Span,
+    /// Remaining entries of a constructed node
+    queue:  VecDeque<Entry<'de>>,
+
+    /// Nodes (and aliases) seen in the current document
+    nodes:    usize,
+    /// Nodes replayed by aliases in the current document
+    expanded: usize,
+}
+
+impl<'de, R> Loader<'de, R>
+where
+    R: Read,
+{
+    fn new(events: EventIter<'de, R>) -> Self
+    {
+        Self {
+            events,
+            registry: None,
+            limits: Limits::default(),
+            document: 0,
+            tags: TagDirectives::new(),
+            started: false,
+            ended: true,
+            log: Vec::new(),
+            anchors: HashMap::new(),
+            open: Vec::new(),
+            record: 0,
+            replay: Vec::new(),
+            span: Span::default(),
+            queue: VecDeque::new(),
+            nodes: 0,
+            expanded: 0,
+        }
+    }
+
+    /// Skip the remainder of the current document, and
+    /// start the next, returning its version or None if the
+    /// stream is finished
+    fn start_document(&mut self) -> Result<Option<VersionDirective>>
+    {
+        while !self.ended
+        {
+            match self.events.next().transpose()?
+            {
+                Some(event) => self.ended = matches!(event.data(), EventData::DocumentEnd(_)),
+                None => return Ok(None),
+            }
+        }
+
+        self.log.clear();
+        self.anchors.clear();
+        self.open.clear();
+        self.record = 0;
+        self.replay.clear();
+        self.queue.clear();
+        self.nodes = 0;
+        self.expanded = 0;
+
+        loop
+        {
+            let event = match self.events.next()
+            {
+                Some(event) => event?,
+                None => return Ok(None),
+            };
+
+            match EventData::from(event)
+            {
+                EventData::DocumentStart(doc) =>
+                {
+                    self.document += 1;
+                    self.tags = doc.directives.tags;
+                    self.started = false;
+                    self.ended = false;
+
+                    return Ok(Some(doc.directives.version));
+                },
+                EventData::StreamEnd => return Ok(None),
+                _ => continue,
+            }
+        }
+    }
+
+    /// Fetch the next entry of the given .document,
+    /// following any aliases
+    fn next_entry(&mut self, document: usize) -> Result<Entry<'de>>
+    {
+        loop
+        {
+            if document != self.document || self.ended
+            {
+                return Err(mkError!(ErrorCode::UnexpectedEOF, CODE));
+            }
+
+            if let Some(entry) = self.next_replayed()?
+            {
+                return Ok(entry);
+            }
+
+            if let Some(entry) = self.queue.pop_front()
+            {
+                return Ok(entry);
+            }
+
+            if let Some(entry) = self.next_event()?
+            {
+                self.started = true;
+
+                return Ok(entry);
+            }
+        }
+    }
+
+    /// Process the next event from the underlying stream,
+    /// returning its entry if it has one
+    fn next_event(&mut self) -> Result<Option<Entry<'de>>>
+    {
+        let event = match self.events.next()
+        {
+            Some(event) => event?,
+            None => return Err(mkError!(ErrorCode::UnexpectedEOF, CODE)),
+        };
+        let span = event.span();
+        let mut data = EventData::from(event);
+
+        let (construct, anchor) = match data
+        {
+            EventData::Scalar(ref mut node) =>
+            {
+                let plain = node.content.is_plain();
+
+                (
+                    apply(self.registry.as_ref(), node, plain)?,
+                    node.anchor.clone(),
+                )
+            },
+            EventData::SequenceStart(ref mut node) => (
+                apply(self.registry.as_ref(), node, true)?,
+                node.anchor.clone(),
+            ),
+            EventData::MappingStart(ref mut node) => (
+                apply(self.registry.as_ref(), node, true)?,
+                node.anchor.clone(),
+            ),
+            _ => (false, None),
+        };
+
+        if let (true, Some(registry)) = (construct, self.registry.as_ref())
+        {
+            let node = construct_node(data, &mut self.events, registry)?;
+
+            let mut entries = Vec::new();
+            let tag = resolve_tag(&self.tags, None, node.value());
+            flatten(node.value(), tag, &self.tags, &mut entries)?;
+
+            self.nodes += entries.iter().filter(|entry| entry.is_node()).count();
+            self.started = true;
+
+            if self.record > 0 || anchor.is_some()
+            {
+                let start = self.log.len();
+                self.log.extend(entries.iter().cloned());
+
+                if let Some(anchor) = anchor
+                {
+                    self.anchors.insert(anchor, (start, self.log.len()));
+                }
+            }
+
+            self.queue.extend(entries);
+
+            return Ok(None);
+        }
+
+        let entry = match data
+        {
+            EventData::StreamStart(_) | EventData::Comment(_) => return Ok(None),
+            EventData::DocumentEnd(_) =>
+            {
+                self.ended = true;
+
+                // Documents without content are an empty plain
+                // scalar
+                match self.started
+                {
+                    true => return Err(mkError!(ErrorCode::UnexpectedEOF, CODE)),
+                    false => Entry::Scalar(
+                        ResolvedTag::Question,
+                        Scalar::new(Slice::Borrowed(""), ScalarStyle::Plain),
+                    ),
+                }
+            },
+            EventData::DocumentStart(_) | EventData::StreamEnd =>
+            {
+                return Err(mkError!(ErrorCode::UnexpectedEOF, CODE))
+            },
+            EventData::Alias(alias) =>
+            {
+                self.nodes += 1;
+
+                let range = self.anchors.get(&alias.name).copied().ok_or_else(|| {
+                    mkError!(ErrorCode::UndefinedAlias, CODE).with_mark(span.start)
+                })?;
+
+                if self.record > 0
+                {
+                    self.log.push(Entry::Alias(range.0, range.1));
+                }
+
+                self.span = span;
+                self.push_replay(range)?;
+
+                return Ok(None);
+            },
+            EventData::Scalar(node) =>
+            {
+                self.nodes += 1;
+
+                let start = self.log.len();
+                let entry = Entry::Scalar(node.resolved, node.content.evaluate()?);
+
+                if self.record > 0 || node.anchor.is_some()
+                {
+                    self.log.push(entry.clone());
+                }
+
+                if let Some(anchor) = node.anchor
+                {
+                    self.anchors.insert(anchor, (start, start + 1));
+                }
+
+                entry
+            },
+            EventData::SequenceStart(node) =>
+            {
+                self.record_open(Entry::SequenceStart(node.resolved), node.anchor)
+            },
+            EventData::MappingStart(node) =>
+            {
+                self.record_open(Entry::MappingStart(node.resolved), node.anchor)
+            },
+            EventData::SequenceEnd => self.record_close(Entry::SequenceEnd),
+            EventData::MappingEnd => self.record_close(Entry::MappingEnd),
+        };
+
+        Ok(Some(entry))
+    }
+
+    fn record_open(&mut self, entry: Entry<'de>, anchor: Option<Slice<'de>>) -> Entry<'de>
+    {
+        self.nodes += 1;
+
+        if anchor.is_some()
+        {
+            self.record += 1;
+        }
+
+        self.open.push((anchor, self.log.len()));
+
+        if self.record > 0
+        {
+            self.log.push(entry.clone());
+        }
+
+        entry
+    }
+
+    fn record_close(&mut self, entry: Entry<'de>) -> Entry<'de>
+    {
+        if self.record > 0
+        {
+            self.log.push(entry.clone());
+        }
+
+        if let Some((Some(anchor), start)) = self.open.pop()
+        {
+            self.record -= 1;
+            self.anchors.insert(anchor, (start, self.log.len()));
+        }
+
+        entry
+    }
+
+    /// Fetch the next entry of the current replay, if one
+    /// is in progress
+    fn next_replayed(&mut self) -> Result<Option<Entry<'de>>>
+    {
+        while let Some((pos, end)) = self.replay.last_mut()
+        {
+            if pos >= end
+            {
+                self.replay.pop();
+                continue;
+            }
+
+            let entry = self.log[*pos].clone();
+            *pos += 1;
+
+            if let Entry::Alias(start, end) = entry
+            {
+                self.push_replay((start, end))?;
+                continue;
+            }
+
+            if entry.is_node()
+            {
+                self.count_expanded()?;
+            }
+
+            return Ok(Some(entry));
+        }
+
+        Ok(None)
+    }
+
+    fn push_replay(&mut self, range: (usize, usize)) -> Result<()>
+    {
+        if self.replay.len() >= self.limits.max_depth
+        {
+            return Err(mkError!(ErrorCode::AliasDepthLimit, CODE).with_mark(self.span.start));
+        }
+
+        self.replay.push(range);
+
+        Ok(())
+    }
+
+    fn count_expanded(&mut self) -> Result<()>
+    {
+        self.expanded += 1;
+
+        if self.expanded > self.limits.max_nodes
+            || self.expanded > self.nodes.saturating_mul(self.limits.max_ratio)
+        {
+            return Err(mkError!(ErrorCode::ExpansionLimit, CODE).with_mark(self.span.start));
+        }
+
+        Ok(())
+    }
+}
+
+/// Apply the .registry's action for .node's tag, returning
+/// whether the node should be constructed. .plain is false
+/// for non plain scalars
+fn apply<T>(registry: Option<&Registry>, node: &mut Node<'_, T>, plain: bool) -> Result<bool>
+{
+    match registry.map(|registry| registry.action(&node.resolved))
+    {
+        None | Some(Action::Keep) => Ok(false),
+        Some(Action::Strip) =>
+        {
+            node.tag = None;
+            node.resolved = match plain
+            {
+                true => ResolvedTag::Question,
+                false => ResolvedTag::Exclamation,
+            };
+
+            Ok(false)
+        },
+        Some(Action::Reject) => Err(mkError!(ErrorCode::UnknownTag, CODE)),
+        Some(Action::Construct(_)) => Ok(true),
+    }
+}
+
+/// Build the node starting with .first into a tree, passing
+/// it (and any of its children) through the .registry's
+/// constructors
+fn construct_node<'de, I>(
+    first: EventData<'de>,
+    events: &mut I,
+    registry: &Registry,
+) -> Result<tree::Node<'de>>
+where
+    I: Iterator<Item = Result<Event<'de>>>,
+{
+    let mut builder = Builder::with_registry(registry.clone());
+    let mut data = first;
+
+    loop
+    {
+        builder.process(data)?;
+
+        if let Some(node) = builder.take_node()
+        {
+            return Ok(node);
+        }
+
+        data = match events.next()
+        {
+            Some(event) => EventData::from(event?),
+            None => return Err(mkError!(ErrorCode::UnexpectedEOF, CODE)),
+        };
+    }
+}
+
+/// Append the entries of .value, and its children, to
+/// .entries. .tags are the document's tag directives
+fn flatten<'de>(
+    value: &Yaml<'de>,
+    tag: ResolvedTag<'de>,
+    tags: &TagDirectives<'de>,
+    entries: &mut Vec<Entry<'de>>,
+) -> Result<()>
+{
+    let child = |node: &tree::Node<'de>, entries: &mut Vec<Entry<'de>>| {
+        let tag = resolve_tag(tags, node.tag(), node.value());
+
+        flatten(node.value(), tag, tags, entries)
+    };
+
+    match value
+    {
+        Yaml::Scalar(scalar) => entries.push(Entry::Scalar(tag, scalar.clone())),
+        Yaml::Sequence(seq) =>
+        {
+            entries.push(Entry::SequenceStart(tag));
+
+            for node in seq
+            {
+                child(node, entries)?;
+            }
+
+            entries.push(Entry::SequenceEnd);
+        },
+        Yaml::Mapping(map) =>
+        {
+            entries.push(Entry::MappingStart(tag));
+
+            for (key, value) in map.iter()
+            {
+                child(key, entries)?;
+                child(value, entries)?;
+            }
+
+            entries.push(Entry::MappingEnd);
+        },
+        Yaml::Custom(_) => return Err(Error::custom("a custom tag value cannot be deserialized")),
+    }
+
+    Ok(())
+}
+
+/// Resolve a tree node's .tag against the document's .tags
+/// directives, in the same manner as the parser resolves
+/// [`Node::resolved`]
+fn resolve_tag<'de>(
+    tags: &TagDirectives<'de>,
+    tag: Option<&Tag<'de>>,
+    value: &Yaml<'de>,
+) -> ResolvedTag<'de>
+{
+    match tag
+    {
+        None => match value
+        {
+            Yaml::Scalar(scalar) => non_specific(scalar),
+            _ => ResolvedTag::Question,
+        },
+        Some((handle, suffix)) if handle == "!" && suffix.is_empty() => ResolvedTag::Exclamation,
+        // Verbatim tags have no handle
+        Some((handle, suffix)) if handle.is_empty() => ResolvedTag::Specific(suffix.clone()),
+        Some((handle, suffix)) =>
+        {
+            let prefix = tags.get(handle).unwrap_or(handle);
+
+            ResolvedTag::Specific(Slice::Owned(format!("{}{}", prefix, suffix)))
+        },
+    }
+}
+
+/// The non-specific tag of an untagged .scalar
+fn non_specific(scalar: &Scalar<'_>) -> ResolvedTag<'static>
+{
+    match scalar.style()
+    {
+        ScalarStyle::Plain => ResolvedTag::Question,
+        _ => ResolvedTag::Exclamation,
+    }
+}
+
+fn visit_slice<'de, V>(visitor: V, data: Slice<'de>) -> Result<V::Value>
+where
+    V: Visitor<'de>,
+{
+    match data
+    {
+        Slice::Borrowed(s) => visitor.visit_borrowed_str(s),
+        Slice::Owned(s) => visitor.visit_string(s),
+    }
+}
+
+/// Is this a local tag, e.g `!Variant`
+fn is_local(tag: &ResolvedTag<'_>) -> bool
+{
+    matches!(tag, ResolvedTag::Specific(uri) if uri.len() > 1 && uri.starts_with('!'))
+}
+
+/// Resolve a scalar's value using the schema for the
+/// document's .version, typically the YAML 1.2 core schema.
+///
+/// Scalars tagged in the YAML namespace (e.g `!!int`) are
+/// resolved to the tagged type, erroring if they do not
+/// match. Non plain scalars, and those with any other tag
+/// are strings.
+fn resolve(
+    version: VersionDirective,
+    tag: &ResolvedTag<'_>,
+    scalar: &Scalar<'_>,
+) -> Result<Resolved>
+{
+    schema::for_version(version).resolve(tag, scalar)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use std::collections::BTreeMap;
+
+    use pretty_assertions::assert_eq;
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::{
+        error::{Category, ErrorKind},
+        tree::tag::Unknown,
+    };
+
+    #[test]
+    fn scalars()
+    {
+        assert_eq!(from_str::<bool>("true").unwrap(), true);
+        assert_eq!(from_str::<i32>("-42").unwrap(), -42);
+        assert_eq!(from_str::<u8>("0x1F").unwrap(), 31);
+        assert_eq!(from_str::<u16>("0o17").unwrap(), 15);
+        assert_eq!(from_str::<u64>("18446744073709551615").unwrap(), u64::MAX);
+        assert_eq!(from_str::<f64>("1.5e3").unwrap(), 1500.0);
+        assert_eq!(from_str::<f64>("-.inf").unwrap(), f64::NEG_INFINITY);
+        assert!(from_str::<f64>(".nan").unwrap().is_nan());
+        assert_eq!(from_str::<char>("c").unwrap(), 'c');
+        assert_eq!(from_str::<String>("yes").unwrap(), "yes");
+        assert_eq!(from_str::<()>("~").unwrap(), ());
+    }
+
+    #[test]
+    fn quoted_is_string()
+    {
+        assert!(from_str::<i32>("'42'").is_err());
+        assert_eq!(from_str::<String>("'42'").unwrap(), "42");
+    }
+
+    #[test]
+    fn int_overflow()
+    {
+        let err = from_str::<i64>("-9223372036854775809").unwrap_err();
+
+        assert_eq!(err.classify(), Category::Data);
+    }
+
+    #[test]
+    fn yaml11_document()
+    {
+        let yaml = "%YAML 1.1\n---\n[yes, Off, 0755, 1:30, 1_000.5]\n";
+        let value = from_str::<(bool, bool, u32, u32, f64)>(yaml).unwrap();
+
+        assert_eq!(value, (true, false, 0o755, 90, 1000.5));
+
+        // Directives only apply to the following document
+        let yaml = "%YAML 1.1\n--- yes\n--- yes\n";
+        let src = BorrowReader::new(yaml);
+        let values = StreamDeserializer::new(&src)
+            .map(|de| de.and_then(|mut de| Value::deserialize(&mut de)))
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(values, vec![Value::Bool(true), Value::Str("yes".into())]);
+    }
+
+    #[test]
+    fn explicit_tags()
+    {
+        assert_eq!(from_str::<String>("!!str 42").unwrap(), "42");
+        assert_eq!(from_str::<f64>("!!float 42").unwrap(), 42.0);
+        assert!(from_str::<i32>("!!str 42").is_err());
+        assert!(from_str::<bool>("!!bool 42").is_err());
+    }
+
+    #[test]
+    fn explicit_tags_resolved()
+    {
+        // Tags are matched by their resolved URI, not how they
+        // are written
+        let verbatim = "- !<tag:yaml.org,2002:int> 42\n";
+        assert_eq!(from_str::<Vec<u64>>(verbatim).unwrap(), vec![42]);
+
+        let named = "%TAG !yaml! tag:yaml.org:2002:\n--- !yaml!str 42\n";
+        assert_eq!(from_str::<String>(named).unwrap(), "42");
+
+        let redefined = "%TAG !! tag:example.com:2000:\n--- !!int 42\n";
+        assert_eq!(
+            from_str::<Value>(redefined).unwrap(),
+            Value::Str("42".into())
+        );
+        assert!(from_str::<u64>(redefined).is_err());
+    }
+
+    #[test]
+    fn borrowed_str()
+    {
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Borrowed<'a>
+        {
+            key: &'a str,
+        }
+
+        let yaml = BorrowReader::new("key: value\n");
+        let value: Borrowed = from_reader(&yaml).unwrap();
+
+        assert_eq!(value, Borrowed { key: "value" });
+    }
+
+    #[test]
+    fn structs()
+    {
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Inner
+        {
+            a: Vec<u32>,
+            b: (bool, String),
+        }
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Outer
+        {
+            inner:   Inner,
+            missing: Option<u32>,
+            null:    Option<u32>,
+            some:    Option<u32>,
+        }
+
+        let yaml = r#"
+inner:
+  a: [1, 2, 3]
+  b:
+    - false
+    - "text"
+  ignored: {x: [y]}
+null: ~
+some: 7
+"#;
+
+        let expected = Outer {
+            inner:   Inner {
+                a: vec![1, 2, 3],
+                b: (false, "text".into()),
+            },
+            missing: None,
+            null:    None,
+            some:    Some(7),
+        };
+
+        assert_eq!(from_str::<Outer>(yaml).unwrap(), expected);
+    }
+
+    #[test]
+    fn newtype_and_unit_structs()
+    {
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Meters(f32);
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Unit;
+
+        assert_eq!(from_str::<Meters>("1.5").unwrap(), Meters(1.5));
+        assert_eq!(from_str::<Unit>("null").unwrap(), Unit);
+    }
+
+    #[test]
+    fn enums()
+    {
+        #[derive(Debug, PartialEq, Deserialize)]
+        enum Shape
+        {
+            Empty,
+            Circle(f64),
+            Point(i32, i32),
+            Rect
+            {
+                w: u32,
+                h: u32,
+            },
+        }
+
+        let yaml = r#"
+- Empty
+- !Empty
+- Circle: 1.5
+- !Circle 2.5
+- Point: [1, 2]
+- !Point [3, 4]
+- Rect: {w: 1, h: 2}
+- !Rect {w: 3, h: 4}
+"#;
+
+        let expected = vec![
+            Shape::Empty,
+            Shape::Empty,
+            Shape::Circle(1.5),
+            Shape::Circle(2.5),
+            Shape::Point(1, 2),
+            Shape::Point(3, 4),
+            Shape::Rect { w: 1, h: 2 },
+            Shape::Rect { w: 3, h: 4 },
+        ];
+
+        assert_eq!(from_str::<Vec<Shape>>(yaml).unwrap(), expected);
+    }
+
+    #[test]
+    fn aliases()
+    {
+        let yaml = "a: &x [1, 2]\nb: *x\nc: &y 3\nd: *y\n";
+        let map: BTreeMap<String, Value> = from_str(yaml).unwrap();
+
+        assert_eq!(map["b"], Value::Seq(vec![Value::Int(1), Value::Int(2)]));
+        assert_eq!(map["d"], Value::Int(3));
+    }
+
+    #[test]
+    fn alias_limits()
+    {
+        // Each level of aliases replays the last ten times
+        let mut yaml = String::from("a0: &a0 [x, x, x, x, x, x, x, x, x, x]\n");
+        for i in 1..8
+        {
+            let aliases = vec![format!("*a{}", i - 1); 10].join(", ");
+
+            yaml.push_str(&format!("a{}: &a{} [{}]\n", i, i, aliases));
+        }
+
+        let err = from_str::<BTreeMap<String, de::IgnoredAny>>(&yaml).unwrap_err();
+        assert_eq!(err.classify(), Category::Limit);
+        assert_eq!(err.kind(), ErrorKind::ExpansionLimit);
+
+        let yaml = BorrowReader::new(&yaml);
+        let mut stream = StreamDeserializer::new(&yaml);
+        stream.set_limits(Limits::new(usize::MAX, usize::MAX, 2));
+
+        let err =
+            BTreeMap::<String, de::IgnoredAny>::deserialize(&mut stream.next().unwrap().unwrap())
+                .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::AliasDepthLimit);
+
+        let yaml = "a: &a [x, x]\nb: &b [*a, *a]\nc: [*b, *b]\n";
+        let map: BTreeMap<String, de::IgnoredAny> = from_str(yaml).unwrap();
+        assert_eq!(map.len(), 3);
+    }
+
+    #[test]
+    fn undefined_alias()
+    {
+        let err = from_str::<BTreeMap<String, u32>>("a: *missing\n").unwrap_err();
+
+        assert_eq!(err.classify(), Category::Data);
+    }
+
+    #[test]
+    fn self_describing()
+    {
+        let yaml = "[~, true, -1, 2, 1.5, text, '3']";
+
+        let expected = vec![
+            Value::Null,
+            Value::Bool(true),
+            Value::Int(-1),
+            Value::Int(2),
+            Value::Float(1.5),
+            Value::Str("text".into()),
+            Value::Str("3".into()),
+        ];
+
+        assert_eq!(from_str::<Vec<Value>>(yaml).unwrap(), expected);
+    }
+
+    #[test]
+    fn empty_document()
+    {
+        assert_eq!(from_str::<Option<u32>>("").unwrap(), None);
+    }
+
+    #[test]
+    fn multiple_documents()
+    {
+        let yaml = BorrowReader::new("--- 1\n--- [2]\n");
+
+        assert!(from_reader::<_, u32>(&yaml).is_err());
+
+        let mut stream = StreamDeserializer::new(&yaml);
+
+        let first = u32::deserialize(&mut stream.next().unwrap().unwrap()).unwrap();
+        let second = Vec::<u32>::deserialize(&mut stream.next().unwrap().unwrap()).unwrap();
+
+        assert_eq!(first, 1);
+        assert_eq!(second, vec![2]);
+        assert!(stream.next().is_none());
+    }
+
+    #[test]
+    fn partial_documents()
+    {
+        let yaml = BorrowReader::new("--- [1, [2, 3], 4]\n--- 5\n");
+        let mut stream = StreamDeserializer::new(&yaml);
+
+        // Stops after the first element
+        let mut first = stream.next().unwrap().unwrap();
+        assert!(<(u32,)>::deserialize(&mut first).is_err());
+
+        let second = u32::deserialize(&mut stream.next().unwrap().unwrap()).unwrap();
+
+        assert_eq!(second, 5);
+        assert!(stream.next().is_none());
+
+        // The rest of the first document was skipped
+        assert!(de::IgnoredAny::deserialize(&mut first).is_err());
+    }
+
+    fn registry() -> Registry
+    {
+        let mut registry = Registry::new();
+        registry
+            .insert("!Ref", |node| {
+                let mut map = tree::Mapping::new();
+                let key = Scalar::new("Ref".into(), ScalarStyle::Plain);
+                let value = node.as_scalar().ok_or("expected a scalar")?;
+
+                map.insert(
+                    tree::Node::new(Yaml::Scalar(key), None, None),
+                    tree::Node::new(
+                        Yaml::Scalar(Scalar::new(value.as_ref().to_owned().into(), value.style())),
+                        None,
+                        None,
+                    ),
+                );
+
+                Ok(Yaml::Mapping(map))
+            })
+            .insert("!custom", |_| Ok(Yaml::Custom(tree::Custom::new(()))));
+
+        registry
+    }
+
+    #[test]
+    fn tag_constructors()
+    {
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Ref
+        {
+            #[serde(rename = "Ref")]
+            name: String,
+        }
+
+        let yaml = BorrowReader::new(
+            "a: &a !Ref db
+b: [*a, !Ref cache]
+",
+        );
+        let refs: BTreeMap<String, Value> = from_reader_with(&yaml, &registry()).unwrap();
+
+        let db = Value::Map(
+            vec![("Ref".into(), Value::Str("db".into()))]
+                .into_iter()
+                .collect(),
+        );
+
+        assert_eq!(refs["a"], db);
+        assert!(matches!(&refs["b"], Value::Seq(seq) if seq[0] == db));
+
+        let yaml = BorrowReader::new("[!Ref db, !Ref cache]");
+        let refs: Vec<Ref> = from_reader_with(&yaml, &registry()).unwrap();
+
+        assert_eq!(
+            refs,
+            vec![
+                Ref { name: "db".into() },
+                Ref {
+                    name: "cache".into(),
+                }
+            ]
+        );
+        assert!(from_reader_with::<_, ()>(&BorrowReader::new("!custom a"), &registry()).is_err());
+    }
+
+    #[test]
+    fn tag_unknown_policy()
+    {
+        let yaml = BorrowReader::new("!Variant 42");
+        let mut registry = registry();
+
+        // Scalars with a non schema tag are always strings
+        assert_eq!(
+            from_reader_with::<_, Value>(&yaml, &registry).unwrap(),
+            Value::Str("42".into())
+        );
+
+        registry.set_unknown(Unknown::Strip);
+        assert_eq!(
+            from_reader_with::<_, Value>(&yaml, &registry).unwrap(),
+            Value::Int(42)
+        );
+
+        registry.set_unknown(Unknown::Error);
+        let err = from_reader_with::<_, Value>(&yaml, &registry).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnknownTag);
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(untagged)]
+    enum Value
+    {
+        Null,
+        Bool(bool),
+        Int(i64),
+        Float(f64),
+        Str(String),
+        Seq(Vec<Value>),
+        Map(BTreeMap<String, Value>),
+    }
+}
@@ -0,0 +1,609 @@
+/*
+ * This Source Code Form is subject to the terms of the
+ * Mozilla Public License, v. 2.0. If a copy of the MPL
+ * was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+//! This module renders [`Error`]s against the YAML source
+//! they were produced from, for display to humans or
+//! consumption by editors.
+//!
+//! A [`Diagnostic`]'s [`Display`] implementation prints a
+//! snippet of the offending line, with a caret under the
+//! column the error occurred at, followed by an explanation
+//! of the error and how it might be fixed:
+//!
+//! ```text
+//! error[E0015]: indentation contained a tab character
+//!  --> config.yaml:2:1
+//!   |
+//! 2 |     - item
+//!   | ^
+//!   |
+//!   = help: YAML does not allow tabs to be used as indentation, indent with spaces instead
+//! ```
+//!
+//! While [`Diagnostic::to_json`] produces the same
+//! information as a single line JSON object.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use yary::{diagnostic::Diagnostic, event::from_reader, reader::borrow::BorrowReader};
+//!
+//! let source = "{first: value second: value}";
+//! let yaml = BorrowReader::new(source);
+//!
+//! let error = from_reader(&yaml)
+//!     .iter()
+//!     .find_map(Result::err)
+//!     .expect("an invalid flow mapping");
+//!
+//! let diagnostic = Diagnostic::new(&error, source).with_name("example.yaml");
+//!
+//! assert!(diagnostic.to_string().contains(" --> example.yaml:1:"));
+//! assert!(diagnostic
+//!     .to_json()
+//!     .contains(r#""kind":"MissingFlowMappingEntryOrEnd""#));
+//! ```
+//!
+//! [`Display`]: std::fmt::Display
+
+use std::fmt::{self, Write};
+
+use crate::error::{Error, ErrorKind};
+
+/// Width that tab characters are expanded to in source
+/// excerpts
+const TAB_WIDTH: usize = 4;
+
+/// An [`Error`] paired with the source text it was produced
+/// from, and optionally the name of the file that text was
+/// read from.
+///
+/// Note that an error's position refers to the stream that
+/// was parsed, and therefore .source must be the complete
+/// text given to the parser (after any transcoding) for the
+/// rendered excerpt to be correct.
+#[derive(Debug, Clone, Copy)]
+pub struct Diagnostic<'a>
+{
+    error:  &'a Error,
+    source: &'a str,
+    name:   Option<&'a str>,
+}
+
+impl<'a> Diagnostic<'a>
+{
+    /// Instantiate a new [`Diagnostic`] for .error, which
+    /// was produced while parsing .source.
+    pub fn new(error: &'a Error, source: &'a str) -> Self
+    {
+        Self {
+            error,
+            source,
+            name: None,
+        }
+    }
+
+    /// Set the file name reported alongside the error's
+    /// position.
+    pub fn with_name(mut self, name: &'a str) -> Self
+    {
+        self.name = Some(name);
+
+        self
+    }
+
+    /// The error being diagnosed
+    pub fn error(&self) -> &'a Error
+    {
+        self.error
+    }
+
+    /// The stable code of the error being diagnosed, e.g
+    /// `E0015`.
+    ///
+    /// See [`ErrorKind::code`] for more information.
+    pub fn code(&self) -> &'static str
+    {
+        self.error.kind().code()
+    }
+
+    /// A human readable explanation of the error, and how
+    /// it might be fixed.
+    pub fn explanation(&self) -> &'static str
+    {
+        explain(self.error.kind())
+    }
+
+    /// The line of .source the error occurred on, if the
+    /// error has a position.
+    pub fn excerpt(&self) -> Option<&'a str>
+    {
+        if !self.error.has_context()
+        {
+            return None;
+        }
+
+        let line = self.error.line() as usize - 1;
+
+        Some(self.source.lines().nth(line).unwrap_or(""))
+    }
+
+    /// Render this diagnostic as a single line JSON object,
+    /// suitable for editor integrations.
+    ///
+    /// The object has the following fields, with `file`,
+    /// `line`, `column` and `offset` being `null` if they
+    /// are unknown:
+    ///
+    /// ```json
+    /// {
+    ///   "severity": "error",
+    ///   "code": "E0015",
+    ///   "kind": "InvalidTab",
+    ///   "category": "Syntax",
+    ///   "message": "indentation contained a tab character",
+    ///   "explanation": "YAML does not allow tabs ...",
+    ///   "file": "config.yaml",
+    ///   "line": 2,
+    ///   "column": 1,
+    ///   "offset": 5
+    /// }
+    /// ```
+    ///
+    /// Lines and columns are one based, columns and offsets
+    /// are counted in bytes.
+    pub fn to_json(&self) -> String
+    {
+        let mut out = String::new();
+
+        out.push_str(r#"{"severity":"error","code":"#);
+        json_str(&mut out, self.code());
+        out.push_str(r#","kind":"#);
+        json_str(&mut out, &format!("{:?}", self.error.kind()));
+        out.push_str(r#","category":"#);
+        json_str(&mut out, &format!("{:?}", self.error.classify()));
+        out.push_str(r#","message":"#);
+        json_str(&mut out, &self.error.repr().to_string());
+        out.push_str(r#","explanation":"#);
+        json_str(&mut out, self.explanation());
+        out.push_str(r#","file":"#);
+        match self.name
+        {
+            Some(name) => json_str(&mut out, name),
+            None => out.push_str("null"),
+        }
+
+        match self.error.has_context()
+        {
+            true =>
+            {
+                let _ = write!(
+                    out,
+                    r#","line":{},"column":{},"offset":{}"#,
+                    self.error.line(),
+                    self.error.column(),
+                    self.error.at()
+                );
+            },
+            false => out.push_str(r#","line":null,"column":null,"offset":null"#),
+        }
+
+        out.push('}');
+
+        out
+    }
+}
+
+impl fmt::Display for Diagnostic<'_>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        writeln!(f, "error[{}]: {}", self.code(), self.error.repr())?;
+
+        let excerpt = match self.excerpt()
+        {
+            Some(excerpt) => excerpt,
+            None =>
+            {
+                if let Some(name) = self.name
+                {
+                    writeln!(f, " --> {}", name)?;
+                }
+
+                return write!(f, "  = help: {}", self.explanation());
+            },
+        };
+
+        let line = self.error.line();
+        let column = self.error.column();
+        let gutter = " ".repeat(line.to_string().len());
+
+        match self.name
+        {
+            Some(name) => writeln!(f, "{}--> {}:{}:{}", gutter, name, line, column)?,
+            None => writeln!(f, "{}--> {}:{}", gutter, line, column)?,
+        }
+
+        // Columns are byte offsets, which may not land on a
+        // character boundary if .source is not the parsed text
+        let mut at = usize::min(column as usize - 1, excerpt.len());
+        while !excerpt.is_char_boundary(at)
+        {
+            at -= 1;
+        }
+
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", line, expand_tabs(excerpt))?;
+        writeln!(
+            f,
+            "{} | {}^",
+            gutter,
+            " ".repeat(display_width(&excerpt[..at]))
+        )?;
+        writeln!(f, "{} |", gutter)?;
+        write!(f, "{} = help: {}", gutter, self.explanation())
+    }
+}
+
+/// Explain the given .kind of error to a human
+fn explain(kind: ErrorKind) -> &'static str
+{
+    use ErrorKind::*;
+
+    match kind
+    {
+        UnexpectedEOF =>
+        {
+            "the stream ended in the middle of a token, check for an unterminated quote, flow \
+             collection or directive"
+        },
+        UnknownDirective => "only the %YAML and %TAG directives are supported by YAML 1.2",
+        MissingMajor => "a %YAML directive must be followed by a version, for example: %YAML 1.2",
+        MissingMinor =>
+        {
+            "a %YAML directive's version must have a minor component, for example: %YAML 1.2"
+        },
+        MissingValue => "a mapping key was found without a ':' separating it from its value",
+        InvalidVersion => "a %YAML directive's version must be two numbers, for example: 1.2",
+        InvalidTagHandle =>
+        {
+            "tag handles must be either '!', '!!' or a named handle like '!name!' containing only \
+             alphanumeric characters"
+        },
+        InvalidTagPrefix =>
+        {
+            "a %TAG directive's prefix must be separated from its handle by whitespace, and \
+             contain only URI characters"
+        },
+        InvalidTagSuffix =>
+        {
+            "a tag's suffix may only contain URI characters, percent encode any others, or quote \
+             the tag as !<...>"
+        },
+        InvalidAnchorName =>
+        {
+            "anchor and alias names may not contain whitespace or flow indicators (',[]{}')"
+        },
+        InvalidFlowScalar =>
+        {
+            "quoted scalars may not contain a document indicator ('---' or '...') at the start of \
+             a line, and must be closed before the stream ends"
+        },
+        InvalidPlainScalar =>
+        {
+            "this plain scalar contains characters that would change its meaning, try quoting it"
+        },
+        InvalidBlockScalar =>
+        {
+            "a block scalar's header may only contain an indentation indicator (1-9), a chomping \
+             indicator ('+' or '-') and a comment, and its content must be indented"
+        },
+        InvalidBlockEntry =>
+        {
+            "a block sequence entry ('- ') may not appear here, check its indentation, or that it \
+             is not inside a flow collection"
+        },
+        InvalidTab =>
+        {
+            "YAML does not allow tabs to be used as indentation, indent with spaces instead"
+        },
+        InvalidKey =>
+        {
+            "a mapping key may not appear here, check its indentation relative to the keys around \
+             it"
+        },
+        InvalidValue =>
+        {
+            "a mapping value (': ') may not appear here, if this is part of a scalar try quoting it"
+        },
+        UnknownEscape =>
+        {
+            "double quoted scalars only support YAML's escape sequences, use '\\\\' for a literal \
+             backslash or switch to single quotes"
+        },
+        UnknownDelimiter =>
+        {
+            "this character cannot start a YAML token, the reserved indicators '@' and '`' must be \
+             quoted"
+        },
+        IntOverflow => "a number in the stream is too large to be represented",
+        CorruptStream =>
+        {
+            "the parser's view of the stream is inconsistent, which typically happens if a reader \
+             is shared between two parsers"
+        },
+        DuplicateVersion => "a document may only have a single %YAML directive",
+        DuplicateTagDirective => "a document may only define each tag handle once",
+        UndefinedTag =>
+        {
+            "this tag uses a named handle that has not been defined, add a %TAG directive for it \
+             before the document's '---'"
+        },
+        MissingDocumentStart =>
+        {
+            "a document following directives, or another document, must begin with '---', end the \
+             previous document with '...' or add a '---'"
+        },
+        MissingBlockEntry =>
+        {
+            "every item of a block sequence must begin with '- ', check that this line is indented \
+             correctly"
+        },
+        MissingNode =>
+        {
+            "a node was expected here, for example after a tag or anchor, add a value or remove \
+             the property"
+        },
+        MissingKey =>
+        {
+            "a mapping key was expected here, check the indentation of this line, and that \
+             implicit keys are on a single line of no more than 1024 characters"
+        },
+        MissingFlowSequenceEntryOrEnd =>
+        {
+            "items in a flow sequence must be separated by ',' and the sequence closed with ']'"
+        },
+        MissingFlowMappingEntryOrEnd =>
+        {
+            "entries in a flow mapping must be separated by ',' and the mapping closed with '}'"
+        },
+        UnexpectedEvent =>
+        {
+            "the emitter was given an event that is not valid at this point of the event stream"
+        },
+        UndefinedAlias =>
+        {
+            "an alias must refer to an anchor ('&name') defined earlier in the same document"
+        },
+        InvalidScalar => "this scalar's value cannot be represented as the type of its tag",
+        InvalidEncoding =>
+        {
+            "the stream contained an invalid UTF16 or UTF32 character, such as an unpaired \
+             surrogate"
+        },
+        IO => "the underlying byte stream returned an error while it was being read",
+        UTF8 =>
+        {
+            "YAML streams must be valid unicode, but this stream contained a byte sequence that is \
+             not valid UTF8"
+        },
+        NotScalar => "only scalar nodes can have their value set",
+        EditConflict => "an edit cannot be made inside a node that has already been replaced",
+        ExpansionLimit =>
+        {
+            "expanding aliases would produce more nodes than permitted, the document may be \
+             maliciously constructed"
+        },
+        AliasDepthLimit => "aliases were nested more deeply than permitted during expansion",
+        DepthLimit => "collections were nested more deeply than permitted",
+        ScalarLimit => "a scalar was longer than permitted",
+        AnchorLimit => "an anchor or alias name was longer than permitted",
+        TagLimit => "a tag or %TAG directive was longer than permitted",
+        DocumentLimit => "the stream contained more documents than permitted",
+        EventLimit => "the stream produced more events than permitted",
+        Message => "the YAML stream could not be converted to or from the requested type",
+        InvalidQuery =>
+        {
+            "query expressions are made of `.key`, `.\"key\"`, `[index]` and `[*]` steps"
+        },
+        UnknownTag => "this tag has no registered constructor, and unknown tags are not permitted",
+        Constructor => "the constructor registered for this node's tag rejected the node",
+        InvalidMerge =>
+        {
+            "a merge key ('<<') must be followed by an alias to a mapping, a mapping, or a \
+             sequence of them"
+        },
+        DuplicateKey =>
+        {
+            "mapping keys must be unique, this key has the same value as an earlier key of the \
+             mapping"
+        },
+    }
+}
+
+/// Expand any tabs in .line to spaces, so carets can be
+/// aligned under it
+fn expand_tabs(line: &str) -> String
+{
+    line.replace('\t', &" ".repeat(TAB_WIDTH))
+}
+
+/// The number of columns .text occupies once tabs have been
+/// expanded
+fn display_width(text: &str) -> usize
+{
+    text.chars()
+        .map(|c| match c
+        {
+            '\t' => TAB_WIDTH,
+            _ => 1,
+        })
+        .sum()
+}
+
+/// Write .s to .out as a JSON string literal
+fn json_str(out: &mut String, s: &str)
+{
+    out.push('"');
+
+    for c in s.chars()
+    {
+        match c
+        {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 =>
+            {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            },
+            c => out.push(c),
+        }
+    }
+
+    out.push('"');
+}
+
+#[cfg(test)]
+mod tests
+{
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        error::{internal::ErrorCode, mkError},
+        event::{from_reader, types::Mark},
+        reader::borrow::BorrowReader,
+    };
+
+    fn first_error(source: &str) -> Error
+    {
+        let yaml = BorrowReader::new(source);
+        let mut events = from_reader(&yaml);
+
+        events
+            .iter()
+            .find_map(Result::err)
+            .expect("source to be invalid YAML")
+    }
+
+    #[test]
+    fn render_flow_mapping()
+    {
+        let source = "key: value\nmap: {first: value second: value}\n";
+        let error = first_error(source);
+
+        // 'value second' is a single plain scalar, so the missing
+        // delimiter is noticed at the following ':'
+        assert_eq!((error.line(), error.column()), (2, 26));
+
+        let expected = "\
+error[E0030]: missing flow mapping delimiter ',' or '}'
+ --> test.yaml:2:26
+  |
+2 | map: {first: value second: value}
+  |                          ^
+  |
+  = help: entries in a flow mapping must be separated by ',' and the mapping closed with '}'";
+
+        assert_eq!(
+            Diagnostic::new(&error, source)
+                .with_name("test.yaml")
+                .to_string(),
+            expected
+        );
+    }
+
+    #[test]
+    fn render_tabs()
+    {
+        let source = "a:\n\tb: c\n";
+        let error = mkError!(ErrorCode::InvalidTab, CODE).with_mark(Mark::new(4, 1, 1));
+
+        let expected = "\
+error[E0015]: indentation contained a tab character
+ --> 2:2
+  |
+2 |     b: c
+  |     ^
+  |
+  = help: YAML does not allow tabs to be used as indentation, indent with spaces instead";
+
+        assert_eq!(Diagnostic::new(&error, source).to_string(), expected);
+    }
+
+    #[test]
+    fn render_without_context()
+    {
+        let error = mkError!(ErrorCode::UndefinedAlias, CODE);
+
+        let expected = "\
+error[E0032]: alias refers to an undefined anchor
+  = help: an alias must refer to an anchor ('&name') defined earlier in the same document";
+
+        assert_eq!(Diagnostic::new(&error, "").to_string(), expected);
+    }
+
+    #[test]
+    fn render_past_end()
+    {
+        let source = "key: \"unterminated\n";
+        let error = mkError!(ErrorCode::UnexpectedEOF, CODE).with_mark(Mark::new(19, 1, 0));
+
+        let rendered = Diagnostic::new(&error, source).to_string();
+
+        assert!(rendered.contains("2 | \n  | ^\n"), "{}", rendered);
+    }
+
+    #[test]
+    fn json()
+    {
+        let source = "[a, b";
+        let error = first_error(source);
+
+        let expected = format!(
+            r#"{{"severity":"error","code":"{}","kind":"{:?}","category":"{:?}","message":"{}","explanation":"{}","file":"in \"quotes\".yaml","line":{},"column":{},"offset":{}}}"#,
+            error.kind().code(),
+            error.kind(),
+            error.classify(),
+            error.repr(),
+            Diagnostic::new(&error, source).explanation(),
+            error.line(),
+            error.column(),
+            error.at()
+        );
+
+        assert_eq!(
+            Diagnostic::new(&error, source)
+                .with_name("in \"quotes\".yaml")
+                .to_json(),
+            expected
+        );
+    }
+
+    #[test]
+    fn json_without_context()
+    {
+        let error = mkError!(ErrorCode::EditConflict, CODE);
+
+        assert_eq!(
+            Diagnostic::new(&error, "").to_json(),
+            r#"{"severity":"error","code":"E0038","kind":"EditConflict","category":"Data","message":"edit overlaps a previously replaced node","explanation":"an edit cannot be made inside a node that has already been replaced","file":null,"line":null,"column":null,"offset":null}"#
+        );
+    }
+
+    #[test]
+    fn json_escapes()
+    {
+        let mut out = String::new();
+
+        json_str(&mut out, "a\"b\\c\nd\u{1}é");
+
+        assert_eq!(out, r#""a\"b\\c\nd\u0001é""#);
+    }
+}
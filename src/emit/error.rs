@@ -0,0 +1,106 @@
+/*
+ * This Source Code Form is subject to the terms of the
+ * Mozilla Public License, v. 2.0. If a copy of the MPL
+ * was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+//! This module contains the errors that may surface while
+//! emitting a YAML byte stream.
+
+use std::{
+    fmt::{self, Debug},
+    io,
+};
+
+use crate::{
+    error::internal::{ErrorCode, ErrorKind, SourceError},
+    scanner::error::ScanError,
+};
+
+/// Result type returned by [`yary::emit`](super)
+pub(crate) type EmitResult<T> = std::result::Result<T, EmitError>;
+
+/// Possible errors that can be encountered while emitting
+/// YAML events.
+#[derive(Debug)]
+pub(crate) enum EmitError
+{
+    /// An event was provided that is not valid at the
+    /// current position in the event stream.
+    ///
+    /// ## Examples
+    ///
+    /// ```text
+    /// StreamStart
+    /// DocumentStart
+    /// SequenceStart
+    /// MappingEnd
+    /// ^^^^^^^^^^ UnexpectedEvent
+    /// ```
+    UnexpectedEvent,
+
+    /// A lazy scalar could not be evaluated.
+    Scanner(ScanError),
+
+    /// An IO error occurred while attempting to write the
+    /// byte stream.
+    IO(io::Error),
+}
+
+impl From<ScanError> for EmitError
+{
+    fn from(e: ScanError) -> Self
+    {
+        Self::Scanner(e)
+    }
+}
+
+impl From<io::Error> for EmitError
+{
+    fn from(e: io::Error) -> Self
+    {
+        Self::IO(e)
+    }
+}
+
+impl fmt::Display for EmitError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        Debug::fmt(self, f)
+    }
+}
+
+impl std::error::Error for EmitError
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)>
+    {
+        match self
+        {
+            Self::Scanner(e) => Some(e),
+            Self::IO(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<EmitError> for ErrorKind
+{
+    fn from(err: EmitError) -> Self
+    {
+        match err
+        {
+            EmitError::UnexpectedEvent => ErrorCode::UnexpectedEvent.into(),
+            EmitError::Scanner(e) => ErrorCode::from(e).into(),
+            EmitError::IO(e) => SourceError::IO(e).into(),
+        }
+    }
+}
+
+impl From<EmitError> for crate::error::Error
+{
+    fn from(err: EmitError) -> Self
+    {
+        crate::error::mkError!(err, KIND)
+    }
+}
@@ -0,0 +1,80 @@
+/*
+ * This Source Code Form is subject to the terms of the
+ * Mozilla Public License, v. 2.0. If a copy of the MPL
+ * was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+//! Configuration flags used to control aspects of the
+//! Emitter's output.
+//!
+//! Read the documentation on each flag's `O_*` constant for
+//! an explanation of it's purpose.
+//!
+//! ```
+//! # use yary::emit::flag::*;
+//! // You can use bitwise operators
+//! let bitwise = O_NIL | O_FLOW;
+//!
+//! // Or if you prefer, method chaining
+//! let chaining = Flags::new().no_flow().nil().flow();
+//!
+//! assert_eq!(bitwise, chaining);
+//! ```
+
+use bitflags::bitflags;
+
+/// An empty, zeroed flag set. This is the default set,
+/// with all other flags disabled.
+pub const O_NIL: Flags = Flags::empty();
+
+/// Set to write collections in flow style.
+///
+/// By default, non empty collections are written in block
+/// style. With this flag set, every collection will be
+/// written in flow style instead, e.g `{a: [1, 2]}`.
+///
+/// Note that flow collections cannot contain block
+/// scalars, so any [Literal] or [Folded] scalars will be
+/// written double quoted.
+///
+/// [Literal]: crate::event::types::ScalarStyle::Literal
+/// [Folded]: crate::event::types::ScalarStyle::Folded
+pub const O_FLOW: Flags = Flags::FLOW;
+
+impl Flags
+{
+    /// Instantiates a new, empty flag set
+    pub const fn new() -> Self
+    {
+        O_NIL
+    }
+
+    /// Nulls the flag set, resetting it to empty
+    pub const fn nil(self) -> Self
+    {
+        O_NIL
+    }
+
+    /// Applies [`O_FLOW`] to this flag set
+    pub const fn flow(self) -> Self
+    {
+        self.union(O_FLOW)
+    }
+
+    /// Removes [`O_FLOW`] from this flag set
+    pub const fn no_flow(self) -> Self
+    {
+        self.difference(O_FLOW)
+    }
+}
+
+bitflags! {
+    /// Controls aspects of [Emitter] behaviors, read each flag for more information.
+    ///
+    /// [Emitter]: super::Emitter
+    #[derive(Default)]
+    pub struct Flags: u32 {
+        /// See [`O_FLOW`]
+        const FLOW          = 0b00000001;
+    }
+}
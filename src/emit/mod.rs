@@ -0,0 +1,1507 @@
+/*
+ * This Source Code Form is subject to the terms of the
+ * Mozilla Public License, v. 2.0. If a copy of the MPL
+ * was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+//! This module exposes methods for serializing YAML event
+//! streams back into a YAML byte stream.
+//!
+//! The [`Emitter`] consumes [`EventData`]s -- typically
+//! produced by an [`Events`](crate::event::Events) stream
+//! -- and writes them out to any [`std::io::Write`] sink.
+//!
+//! Output is written in block style by default, with the
+//! exception of empty collections, which are written as
+//! `[]` or `{}`. Flow style output can be selected with
+//! [`O_FLOW`](flag::O_FLOW). Every [`ScalarStyle`] is
+//! honored where it can represent the scalar's content,
+//! otherwise the emitter falls back to the next most
+//! expressive style, in the order:
+//!
+//! ```text
+//! Plain -> SingleQuote -> DoubleQuote
+//! Literal | Folded -> DoubleQuote
+//! ```
+//!
+//! Anchors, aliases, tags and document directives are
+//! written as given, so parsing the emitted byte stream
+//! again will produce an equivalent event stream. Comment
+//! events are accepted anywhere, but are not written.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use yary::{emit, event::from_reader, reader::borrow::BorrowReader};
+//!
+//! let yaml = BorrowReader::new("{a yaml: [mapping, 'of', \"things\"]}");
+//! let mut emitter = emit::to_writer(Vec::new());
+//!
+//! for event in from_reader(&yaml)
+//! {
+//!     emitter.emit(event?)?;
+//! }
+//!
+//! let output = String::from_utf8(emitter.into_inner()).unwrap();
+//!
+//! assert_eq!(output, "a yaml:\n  - mapping\n  - 'of'\n  - \"things\"\n");
+//! # Ok::<(), yary::Error>(())
+//! ```
+
+use std::io::Write;
+
+use crate::{
+    emit::{
+        error::{EmitError as Error, EmitResult as Result},
+        flag::{Flags, O_FLOW},
+    },
+    event::types::{
+        Alias, DocumentEnd, DocumentStart, EventData, Node, ScalarLike, ScalarStyle, Slice,
+        DEFAULT_TAGS, DEFAULT_VERSION,
+    },
+};
+
+pub(crate) mod error;
+pub mod flag;
+
+/// The maximum length of a scalar that will be written as
+/// an implicit (simple) mapping key. Longer keys are
+/// written in their explicit form (`? key`).
+const MAX_SIMPLE_KEY: usize = 1024;
+
+/// The number of spaces each nested block collection, or
+/// block scalar's content is indented by.
+const INDENT: usize = 2;
+
+/// Instantiates a new [`Emitter`] which will write the YAML
+/// byte stream to the given `writer`.
+///
+/// ## Examples
+///
+/// ```rust
+/// use yary::{
+///     emit,
+///     event::types::{DocumentEnd, DocumentStart, EventData, StreamEncoding, StreamStart},
+/// };
+///
+/// let mut emitter = emit::to_writer(Vec::new());
+///
+/// emitter.emit(EventData::StreamStart(StreamStart {
+///     encoding: StreamEncoding::UTF8,
+/// }))?;
+/// emitter.emit(EventData::DocumentStart(DocumentStart {
+///     directives: Default::default(),
+///     implicit:   false,
+/// }))?;
+/// emitter.emit(EventData::DocumentEnd(DocumentEnd { implicit: false }))?;
+/// emitter.emit(EventData::StreamEnd)?;
+///
+/// assert_eq!(emitter.into_inner(), b"---\n...\n");
+/// # Ok::<(), yary::Error>(())
+/// ```
+pub fn to_writer<W>(writer: W) -> Emitter<W>
+where
+    W: Write,
+{
+    Emitter::new(writer)
+}
+
+/// Instantiates a new [`Emitter`] which will write the YAML
+/// byte stream to the given `writer`, configured by the
+/// given flags.
+///
+/// See [`flag`] for the available options.
+///
+/// ## Examples
+///
+/// ```rust
+/// use yary::{
+///     emit::{self, flag::O_FLOW},
+///     event::from_reader,
+///     reader::borrow::BorrowReader,
+/// };
+///
+/// let yaml = BorrowReader::new("key:\n  - a\n  - b\n");
+/// let mut emitter = emit::to_writer_with(Vec::new(), O_FLOW);
+///
+/// for event in from_reader(&yaml)
+/// {
+///     emitter.emit(event?)?;
+/// }
+///
+/// assert_eq!(emitter.into_inner(), b"{key: [a, b]}\n");
+/// # Ok::<(), yary::Error>(())
+/// ```
+pub fn to_writer_with<W>(writer: W, f: Flags) -> Emitter<W>
+where
+    W: Write,
+{
+    Emitter::with_flags(writer, f)
+}
+
+/// Control structure for serializing [`EventData`]s into a
+/// YAML byte stream.
+///
+/// Events must be provided in the order described by the
+/// [`event`](crate::event) module's productions, any event
+/// that is invalid at its position in the stream will be
+/// rejected with an error.
+///
+/// Note that the `writer` is not buffered by the emitter,
+/// callers that care should wrap it in a
+/// [`BufWriter`](std::io::BufWriter). It is flushed upon
+/// receiving a `StreamEnd` event.
+#[derive(Debug)]
+pub struct Emitter<W>
+{
+    writer: W,
+    flags:  Flags,
+
+    state:   State,
+    levels:  Vec<Level>,
+    pending: Option<Pending>,
+
+    /// Current column of the output
+    column:      usize,
+    /// Was the last character written whitespace?
+    whitespace:  bool,
+    /// Has only indentation (and indentation-like
+    /// indicators) been written on the current line?
+    indention:   bool,
+    /// Was a document start marker ('---') written for the
+    /// current document?
+    marker:      bool,
+    /// Must the next document start with an explicit
+    /// marker?
+    need_marker: bool,
+    /// Must the next document's directives be preceded by a
+    /// document end marker ('...')?
+    open_ended:  bool,
+}
+
+impl<W> Emitter<W>
+where
+    W: Write,
+{
+    /// Instantiate a new [`Emitter`] writing to the given
+    /// `writer`.
+    pub fn new(writer: W) -> Self
+    {
+        Self::with_flags(writer, Flags::default())
+    }
+
+    /// Instantiate a new [`Emitter`] writing to the given
+    /// `writer`, configured by the given flags.
+    pub fn with_flags(writer: W, flags: Flags) -> Self
+    {
+        Self {
+            writer,
+            flags,
+            state: State::StreamStart,
+            levels: Vec::new(),
+            pending: None,
+            column: 0,
+            whitespace: true,
+            indention: true,
+            marker: false,
+            need_marker: false,
+            open_ended: false,
+        }
+    }
+
+    /// Write the given event to the underlying byte stream.
+    ///
+    /// ## Errors
+    ///
+    /// This method will error if the event is not valid at
+    /// the current position in the event stream, if a lazy
+    /// scalar fails to evaluate or if the underlying
+    /// `writer` returns an error.
+    pub fn emit<'de, E>(&mut self, event: E) -> crate::error::Result<()>
+    where
+        E: Into<EventData<'de>>,
+    {
+        self.emit_event(event.into()).map_err(Into::into)
+    }
+
+    /// Retrieve a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W
+    {
+        &self.writer
+    }
+
+    /// Consume this [`Emitter`], returning the underlying
+    /// writer.
+    pub fn into_inner(self) -> W
+    {
+        self.writer
+    }
+
+    fn emit_event(&mut self, event: EventData<'_>) -> Result<()>
+    {
+        // Comments are not written, and may appear anywhere in
+        // the event stream
+        if let EventData::Comment(_) = event
+        {
+            return Ok(());
+        }
+
+        // We cannot know how a collection should be written
+        // until we have seen the event after its start
+        if let Some(pending) = self.pending.take()
+        {
+            return match (pending.kind, event)
+            {
+                (Collection::Sequence, EventData::SequenceEnd)
+                | (Collection::Mapping, EventData::MappingEnd) => self.empty_collection(pending),
+                (_, event) =>
+                {
+                    self.open_collection(pending)?;
+                    self.emit_event(event)
+                },
+            };
+        }
+
+        match (self.state, event)
+        {
+            (State::StreamStart, EventData::StreamStart(_)) =>
+            {
+                self.state = State::DocumentStart;
+
+                Ok(())
+            },
+            (State::DocumentStart, EventData::DocumentStart(doc)) => self.document_start(doc),
+            (State::DocumentStart, EventData::StreamEnd) => self.stream_end(),
+            (State::DocumentContent, EventData::DocumentEnd(doc)) if self.levels.is_empty() =>
+            {
+                self.document_end(doc)
+            },
+            (State::DocumentEnd, EventData::DocumentEnd(doc)) => self.document_end(doc),
+            (State::DocumentContent, EventData::Alias(alias)) => self.alias(alias),
+            (State::DocumentContent, EventData::Scalar(node)) => self.scalar(node),
+            (State::DocumentContent, EventData::SequenceStart(node)) =>
+            {
+                self.pending = Some(Pending::new(Collection::Sequence, node));
+
+                Ok(())
+            },
+            (State::DocumentContent, EventData::MappingStart(node)) =>
+            {
+                self.pending = Some(Pending::new(Collection::Mapping, node));
+
+                Ok(())
+            },
+            (State::DocumentContent, EventData::SequenceEnd) => self.close_collection(true),
+            (State::DocumentContent, EventData::MappingEnd) => self.close_collection(false),
+            _ => Err(Error::UnexpectedEvent),
+        }
+    }
+
+    fn document_start(&mut self, doc: DocumentStart<'_>) -> Result<()>
+    {
+        let DocumentStart {
+            directives,
+            implicit,
+        } = doc;
+
+        let version = Some(directives.version).filter(|v| *v != DEFAULT_VERSION);
+        let mut tags: Vec<_> = directives
+            .tags
+            .iter()
+            .filter(|(handle, prefix)| !is_default_tag(handle, prefix))
+            .collect();
+        tags.sort();
+
+        let has_directives = version.is_some() || !tags.is_empty();
+
+        // Directives may only follow a document that has
+        // been explicitly terminated
+        if has_directives && self.open_ended
+        {
+            self.write_str("...")?;
+            self.write_break()?;
+        }
+
+        if let Some(version) = version
+        {
+            self.write_str(&format!("%YAML {}.{}", version.major, version.minor))?;
+            self.write_break()?;
+        }
+
+        for (handle, prefix) in tags
+        {
+            self.write_str(&format!("%TAG {} {}", handle, prefix))?;
+            self.write_break()?;
+        }
+
+        self.marker = !implicit || has_directives || self.need_marker;
+        if self.marker
+        {
+            self.write_indicator("---", false, false)?;
+        }
+
+        self.state = State::DocumentContent;
+
+        Ok(())
+    }
+
+    fn document_end(&mut self, doc: DocumentEnd) -> Result<()>
+    {
+        let written = self.marker || self.state == State::DocumentEnd;
+
+        self.end_line()?;
+
+        if !doc.implicit
+        {
+            self.write_str("...")?;
+            self.write_break()?;
+        }
+
+        self.need_marker = doc.implicit;
+        self.open_ended = doc.implicit && written;
+        self.state = State::DocumentStart;
+
+        Ok(())
+    }
+
+    fn stream_end(&mut self) -> Result<()>
+    {
+        self.end_line()?;
+        self.writer.flush()?;
+
+        self.state = State::StreamEnd;
+
+        Ok(())
+    }
+
+    fn alias(&mut self, alias: Alias<'_>) -> Result<()>
+    {
+        self.node_start(true)?;
+        self.write_indicator(&format!("*{}", alias.name), true, false)?;
+
+        self.node_end(true)
+    }
+
+    fn scalar(&mut self, node: Node<'_, ScalarLike<'_>>) -> Result<()>
+    {
+        let Node {
+            anchor,
+            tag,
+            content,
+            ..
+        } = node;
+
+        let scalar = content.evaluate_scalar()?;
+        let data: &str = &scalar;
+
+        let root = self.levels.is_empty();
+        let props = anchor.is_some() || tag.is_some();
+        let flow = self.in_flow();
+
+        // An empty plain scalar is only distinguishable from
+        // the absence of content if something else precedes
+        // it on the line, and never inside a flow collection
+        let empty_ok = !flow && (!root || props || self.marker);
+        let style = select_style(data, scalar.style(), empty_ok, flow);
+
+        let simple = match style
+        {
+            ScalarStyle::Plain => !data.is_empty() && data.len() <= MAX_SIMPLE_KEY,
+            ScalarStyle::SingleQuote | ScalarStyle::DoubleQuote => data.len() <= MAX_SIMPLE_KEY,
+            ScalarStyle::Literal | ScalarStyle::Folded => false,
+        };
+
+        self.node_start(simple)?;
+        self.write_properties(anchor.as_deref(), tag.as_ref())?;
+
+        match style
+        {
+            ScalarStyle::Plain => self.write_plain(data)?,
+            ScalarStyle::SingleQuote => self.write_single_quoted(data)?,
+            ScalarStyle::DoubleQuote => self.write_double_quoted(data)?,
+            ScalarStyle::Literal | ScalarStyle::Folded => self.write_block(data, style)?,
+        }
+
+        self.node_end(false)
+    }
+
+    fn empty_collection(&mut self, pending: Pending) -> Result<()>
+    {
+        let indicator = match pending.kind
+        {
+            Collection::Sequence => "[]",
+            Collection::Mapping => "{}",
+        };
+
+        self.node_start(false)?;
+        self.write_properties(pending.anchor.as_deref(), pending.tag.as_ref())?;
+        self.write_indicator(indicator, true, false)?;
+
+        self.node_end(false)
+    }
+
+    fn open_collection(&mut self, pending: Pending) -> Result<()>
+    {
+        let indent = self.child_indent();
+        let flow = self.flags.contains(O_FLOW) || self.in_flow();
+
+        self.node_start(false)?;
+        self.write_properties(pending.anchor.as_deref(), pending.tag.as_ref())?;
+
+        let (kind, indicator) = match pending.kind
+        {
+            Collection::Sequence => (LevelKind::Sequence, "["),
+            Collection::Mapping => (LevelKind::Key, "{"),
+        };
+
+        if flow
+        {
+            self.write_indicator(indicator, true, false)?;
+            self.whitespace = true;
+        }
+
+        self.levels.push(Level {
+            indent,
+            kind,
+            flow,
+            first: true,
+        });
+
+        Ok(())
+    }
+
+    fn close_collection(&mut self, sequence: bool) -> Result<()>
+    {
+        let flow = match self.levels.last()
+        {
+            Some(level) if level.kind == LevelKind::Sequence && sequence => level.flow,
+            Some(level) if level.kind == LevelKind::Key && !sequence => level.flow,
+            _ => return Err(Error::UnexpectedEvent),
+        };
+
+        self.levels.pop();
+
+        if flow
+        {
+            self.write_indicator(if sequence { "]" } else { "}" }, false, false)?;
+        }
+
+        self.node_end(false)
+    }
+
+    /// Write any indicators required by the position of the
+    /// next node, advancing the state of the parent
+    /// collection.
+    ///
+    /// .simple indicates whether the node may be written as
+    /// an implicit mapping key.
+    fn node_start(&mut self, simple: bool) -> Result<()>
+    {
+        let (indent, kind) = match self.levels.last()
+        {
+            Some(level) if level.flow => return self.flow_node_start(simple),
+            Some(level) => (level.indent, level.kind),
+            None => return Ok(()),
+        };
+
+        let next = match kind
+        {
+            LevelKind::Sequence =>
+            {
+                self.write_indent(indent)?;
+                self.write_indicator("-", true, true)?;
+
+                LevelKind::Sequence
+            },
+            LevelKind::Key if simple =>
+            {
+                self.write_indent(indent)?;
+
+                LevelKind::SimpleKey
+            },
+            LevelKind::Key =>
+            {
+                self.write_indent(indent)?;
+                self.write_indicator("?", true, true)?;
+
+                LevelKind::ComplexValue
+            },
+            LevelKind::SimpleValue => LevelKind::Key,
+            LevelKind::ComplexValue =>
+            {
+                self.write_indent(indent)?;
+                self.write_indicator(":", true, true)?;
+
+                LevelKind::Key
+            },
+            // A simple key is always a single event, so we should
+            // never see another node start before its end
+            LevelKind::SimpleKey => unreachable!(),
+        };
+
+        self.set_level(next);
+
+        Ok(())
+    }
+
+    /// Flow collection variant of
+    /// [`node_start`](Self::node_start)
+    fn flow_node_start(&mut self, simple: bool) -> Result<()>
+    {
+        let (kind, first) = match self.levels.last_mut()
+        {
+            Some(level) => (level.kind, std::mem::replace(&mut level.first, false)),
+            None => return Ok(()),
+        };
+
+        // Entries after the first are separated by a ','
+        if !first && matches!(kind, LevelKind::Sequence | LevelKind::Key)
+        {
+            self.write_indicator(",", false, false)?;
+        }
+
+        let next = match kind
+        {
+            LevelKind::Sequence => LevelKind::Sequence,
+            LevelKind::Key if simple => LevelKind::SimpleKey,
+            LevelKind::Key =>
+            {
+                self.write_indicator("?", true, false)?;
+
+                LevelKind::ComplexValue
+            },
+            LevelKind::SimpleValue => LevelKind::Key,
+            LevelKind::ComplexValue =>
+            {
+                self.write_indicator(":", true, false)?;
+
+                LevelKind::Key
+            },
+            // A simple key is always a single event, so we should
+            // never see another node start before its end
+            LevelKind::SimpleKey => unreachable!(),
+        };
+
+        self.set_level(next);
+
+        Ok(())
+    }
+
+    /// Finish the current node, writing the value indicator
+    /// if the node was an implicit mapping key.
+    fn node_end(&mut self, alias: bool) -> Result<()>
+    {
+        match self.levels.last().map(|level| level.kind)
+        {
+            None => self.state = State::DocumentEnd,
+            Some(LevelKind::SimpleKey) =>
+            {
+                // Anchor names may contain ':', so we must separate
+                // the two
+                self.write_indicator(":", alias, false)?;
+                self.set_level(LevelKind::SimpleValue);
+            },
+            Some(_) =>
+            {},
+        }
+
+        Ok(())
+    }
+
+    fn set_level(&mut self, kind: LevelKind)
+    {
+        if let Some(level) = self.levels.last_mut()
+        {
+            level.kind = kind;
+        }
+    }
+
+    /// Is the current position inside a flow collection?
+    fn in_flow(&self) -> bool
+    {
+        self.levels.last().map_or(false, |level| level.flow)
+    }
+
+    /// Indentation of a block collection or block scalar's
+    /// content, nested at the current position
+    fn child_indent(&self) -> usize
+    {
+        self.levels.last().map_or(0, |level| level.indent + INDENT)
+    }
+
+    fn write_properties(
+        &mut self,
+        anchor: Option<&str>,
+        tag: Option<&(Slice<'_>, Slice<'_>)>,
+    ) -> Result<()>
+    {
+        if let Some(anchor) = anchor
+        {
+            self.write_indicator(&format!("&{}", anchor), true, false)?;
+        }
+
+        if let Some((handle, suffix)) = tag
+        {
+            self.write_indicator(&format_tag(handle, suffix), true, false)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_plain(&mut self, data: &str) -> Result<()>
+    {
+        if data.is_empty()
+        {
+            return Ok(());
+        }
+
+        self.write_indicator(data, true, false)
+    }
+
+    fn write_single_quoted(&mut self, data: &str) -> Result<()>
+    {
+        self.write_indicator(&single_quoted(data), true, false)
+    }
+
+    fn write_double_quoted(&mut self, data: &str) -> Result<()>
+    {
+        self.write_indicator(&double_quoted(data), true, false)
+    }
+
+    fn write_block(&mut self, data: &str, style: ScalarStyle) -> Result<()>
+    {
+        let indent = self.child_indent().max(INDENT);
+        let (chomp, lines) = block_body(data, style);
+
+        let mut header = String::from(block_indicator(style));
+
+        // If the content's first line starts with whitespace
+        // auto detection would pick the wrong indentation
+        if needs_indent_indicator(data)
+        {
+            header.push_str(&INDENT.to_string());
+        }
+        header.push_str(chomp);
+
+        self.write_indicator(&header, true, false)?;
+
+        for line in lines
+        {
+            self.write_break()?;
+            if !line.is_empty()
+            {
+                self.write_indent(indent)?;
+                self.write_str(line)?;
+            }
+        }
+
+        // Ensure the next token always starts on a new line
+        self.whitespace = false;
+        self.indention = false;
+
+        Ok(())
+    }
+
+    /// Write the indentation for a new line at .indent,
+    /// starting a new line if required.
+    fn write_indent(&mut self, indent: usize) -> Result<()>
+    {
+        if !self.indention || self.column > indent || (self.column == indent && !self.whitespace)
+        {
+            self.write_break()?;
+        }
+
+        while self.column < indent
+        {
+            self.write_str(" ")?;
+        }
+
+        self.whitespace = true;
+        self.indention = true;
+
+        Ok(())
+    }
+
+    /// Write an indicator, or other token, separating it
+    /// from the previous token if .need_whitespace is
+    /// set.
+    ///
+    /// .indention marks whether this token acts as
+    /// indentation for any following content on the line.
+    fn write_indicator(&mut self, token: &str, need_whitespace: bool, indention: bool)
+        -> Result<()>
+    {
+        if need_whitespace && !self.whitespace
+        {
+            self.write_str(" ")?;
+        }
+
+        self.write_str(token)?;
+
+        self.whitespace = false;
+        self.indention = self.indention && indention;
+
+        Ok(())
+    }
+
+    /// Terminate the current line, if anything has been
+    /// written to it
+    fn end_line(&mut self) -> Result<()>
+    {
+        if self.column != 0 || !self.whitespace
+        {
+            self.write_break()?;
+        }
+
+        Ok(())
+    }
+
+    fn write_break(&mut self) -> Result<()>
+    {
+        self.writer.write_all(b"\n")?;
+
+        self.column = 0;
+        self.whitespace = true;
+        self.indention = true;
+
+        Ok(())
+    }
+
+    fn write_str(&mut self, s: &str) -> Result<()>
+    {
+        self.writer.write_all(s.as_bytes())?;
+
+        self.column += s.chars().count();
+
+        Ok(())
+    }
+}
+
+/// Position of the emitter in the event stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State
+{
+    /// Expecting StreamStart
+    StreamStart,
+    /// Expecting DocumentStart or StreamEnd
+    DocumentStart,
+    /// Expecting the document's content, or DocumentEnd if
+    /// there is no (more) content
+    DocumentContent,
+    /// Expecting DocumentEnd, after the root node
+    DocumentEnd,
+    /// No more events may be emitted
+    StreamEnd,
+}
+
+/// An open collection
+#[derive(Debug, Clone, Copy)]
+struct Level
+{
+    /// Indentation of the collection's entries
+    indent: usize,
+    /// Expected next node in the collection
+    kind:   LevelKind,
+    /// Is this a flow collection?
+    flow:   bool,
+    /// Has no node been started in this collection yet?
+    first:  bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LevelKind
+{
+    /// Sequence entry
+    Sequence,
+    /// Mapping key
+    Key,
+    /// Inside of an implicit mapping key
+    SimpleKey,
+    /// Mapping value, after an implicit key
+    SimpleValue,
+    /// Mapping value, after an explicit key
+    ComplexValue,
+}
+
+/// A collection start whose layout has not yet been
+/// decided
+#[derive(Debug)]
+struct Pending
+{
+    kind:   Collection,
+    anchor: Option<String>,
+    tag:    Option<(Slice<'static>, Slice<'static>)>,
+}
+
+impl Pending
+{
+    fn new<T>(kind: Collection, node: Node<'_, T>) -> Self
+    {
+        let anchor = node.anchor.map(|a| a.into_owned());
+        let tag = node
+            .tag
+            .map(|(h, s)| (h.into_owned().into(), s.into_owned().into()));
+
+        Self { kind, anchor, tag }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Collection
+{
+    Sequence,
+    Mapping,
+}
+
+/// Pick the style that .data should be written in, using
+/// .requested if possible
+///
+/// .flow indicates whether the scalar is inside of a flow
+/// collection, where block scalars are not permitted
+fn select_style(data: &str, requested: ScalarStyle, empty_ok: bool, flow: bool) -> ScalarStyle
+{
+    use ScalarStyle::*;
+
+    let quoted = || match single_quote_allowed(data)
+    {
+        true => SingleQuote,
+        false => DoubleQuote,
+    };
+
+    match requested
+    {
+        Plain if data.is_empty() && empty_ok => Plain,
+        Plain if plain_allowed(data) && !(flow && data.contains(is_flow_indicator)) => Plain,
+        Plain | SingleQuote => quoted(),
+        DoubleQuote => DoubleQuote,
+        Literal | Folded if !flow && block_allowed(data) => requested,
+        Literal | Folded => DoubleQuote,
+    }
+}
+
+/// Render .data as a scalar that fits on a single line,
+/// using .requested style if possible
+///
+/// Block styles are never produced, as they depend on the
+/// indentation of the surrounding document
+pub(crate) fn inline_scalar(data: &str, requested: ScalarStyle, flow: bool) -> String
+{
+    let style = match requested
+    {
+        ScalarStyle::Literal | ScalarStyle::Folded => select_style(data, requested, false, true),
+        _ => select_style(data, requested, false, flow),
+    };
+
+    match style
+    {
+        ScalarStyle::Plain => data.to_string(),
+        ScalarStyle::SingleQuote => single_quoted(data),
+        _ => double_quoted(data),
+    }
+}
+
+/// Render .data as a block scalar of .style, with its
+/// content indented to .indent, or None if .data cannot be
+/// represented by a block scalar.
+///
+/// The returned text begins with the block header and ends
+/// with the final content line, excluding its line break.
+/// Any indentation indicator is relative to .parent, the
+/// indentation of the block scalar's parent node.
+pub(crate) fn block_scalar(
+    data: &str,
+    style: ScalarStyle,
+    indent: usize,
+    parent: usize,
+) -> Option<String>
+{
+    if !block_allowed(data)
+    {
+        return None;
+    }
+
+    let (chomp, lines) = block_body(data, style);
+    let mut text = String::from(block_indicator(style));
+
+    if needs_indent_indicator(data)
+    {
+        match indent.checked_sub(parent)
+        {
+            Some(n @ 1..=9) => text.push_str(&n.to_string()),
+            _ => return None,
+        }
+    }
+    text.push_str(chomp);
+
+    for line in lines
+    {
+        text.push('\n');
+        if !line.is_empty()
+        {
+            text.extend(std::iter::repeat(' ').take(indent));
+            text.push_str(line);
+        }
+    }
+
+    Some(text)
+}
+
+fn block_indicator(style: ScalarStyle) -> &'static str
+{
+    match style
+    {
+        ScalarStyle::Folded => ">",
+        _ => "|",
+    }
+}
+
+/// Would auto detection pick the wrong indentation for
+/// .data's block scalar, because its first line starts with
+/// whitespace?
+fn needs_indent_indicator(data: &str) -> bool
+{
+    data.split('\n')
+        .find(|line| !line.is_empty())
+        .map_or(false, |line| line.starts_with(is_blank))
+}
+
+/// Split .data into the lines of a block scalar's body,
+/// returning them with the chomping indicator required to
+/// preserve .data's trailing line breaks.
+///
+/// When folding, an empty line is inserted between any two
+/// text lines, as a single line break between them would
+/// be folded into a space.
+fn block_body(data: &str, style: ScalarStyle) -> (&'static str, Vec<&str>)
+{
+    let folded = style == ScalarStyle::Folded;
+
+    let trailing = data.len() - data.trim_end_matches('\n').len();
+    let (chomp, body) = match trailing
+    {
+        0 => ("-", data),
+        1 if data.len() > 1 => ("", &data[..data.len() - 1]),
+        _ => ("+", &data[..data.len() - 1]),
+    };
+
+    let mut lines = Vec::new();
+    let mut previous_text = false;
+    for line in body.split('\n').filter(|_| !data.is_empty())
+    {
+        let text = !line.is_empty() && !line.starts_with(is_blank);
+
+        if folded && text && previous_text
+        {
+            lines.push("");
+        }
+
+        lines.push(line);
+
+        if !line.is_empty()
+        {
+            previous_text = text;
+        }
+    }
+
+    (chomp, lines)
+}
+
+fn single_quoted(data: &str) -> String
+{
+    format!("'{}'", data.replace('\'', "''"))
+}
+
+fn double_quoted(data: &str) -> String
+{
+    let mut quoted = String::with_capacity(data.len() + 2);
+
+    quoted.push('"');
+    for ch in data.chars()
+    {
+        escape_char(ch, &mut quoted);
+    }
+    quoted.push('"');
+
+    quoted
+}
+
+/// Can .data be represented as a (non empty) block context
+/// plain scalar?
+fn plain_allowed(data: &str) -> bool
+{
+    let mut chars = data.chars();
+
+    let (first, second) = match (chars.next(), chars.next())
+    {
+        (Some(first), second) => (first, second),
+        (None, _) => return false,
+    };
+
+    // Indicators may only start a plain scalar if followed by
+    // a "safe" character
+    let start = match first
+    {
+        '-' | '?' | ':' => second.map_or(false, |c| !is_blank(c) && !is_break(c)),
+        ',' | '[' | ']' | '{' | '}' | '#' | '&' | '*' | '!' | '|' | '>' | '\'' | '"' | '%'
+        | '@' | '`' => false,
+        _ => true,
+    };
+
+    start
+        && !data.starts_with(is_blank)
+        && !data.ends_with(is_blank)
+        && !data.ends_with(':')
+        && !data.starts_with("---")
+        && !data.starts_with("...")
+        && !data.contains(": ")
+        && !data.contains(":\t")
+        && !data.contains(" #")
+        && !data.contains("\t#")
+        && data.chars().all(|c| is_printable(c) && !is_break(c))
+}
+
+/// Can .data be represented as a single quoted scalar?
+///
+/// We never fold single quoted scalars, so line breaks
+/// require a double quoted scalar
+fn single_quote_allowed(data: &str) -> bool
+{
+    data.chars().all(|c| is_printable(c) && !is_break(c))
+}
+
+/// Can .data be represented as a block scalar?
+fn block_allowed(data: &str) -> bool
+{
+    data.chars()
+        .all(|c| c == '\n' || (is_printable(c) && !is_break(c)))
+}
+
+/// Write .ch to .buffer, escaping it if required in a
+/// double quoted scalar
+fn escape_char(ch: char, buffer: &mut String)
+{
+    let escape = match ch
+    {
+        '\0' => "\\0",
+        '\x07' => "\\a",
+        '\x08' => "\\b",
+        '\t' => "\\t",
+        '\n' => "\\n",
+        '\x0B' => "\\v",
+        '\x0C' => "\\f",
+        '\r' => "\\r",
+        '\x1B' => "\\e",
+        '"' => "\\\"",
+        '\\' => "\\\\",
+        '\u{85}' => "\\N",
+        '\u{2028}' => "\\L",
+        '\u{2029}' => "\\P",
+        c if is_printable(c) =>
+        {
+            buffer.push(c);
+            return;
+        },
+        c =>
+        {
+            let escaped = match c as u32
+            {
+                n @ 0..=0xFF => format!("\\x{:02X}", n),
+                n @ 0x100..=0xFFFF => format!("\\u{:04X}", n),
+                n => format!("\\U{:08X}", n),
+            };
+
+            buffer.push_str(&escaped);
+            return;
+        },
+    };
+
+    buffer.push_str(escape);
+}
+
+/// Format the given tag as a YAML tag property
+fn format_tag(handle: &str, suffix: &str) -> String
+{
+    // Verbatim tags have no handle
+    if handle.is_empty()
+    {
+        return format!("!<{}>", escape_uri(suffix, true));
+    }
+
+    format!("{}{}", handle, escape_uri(suffix, false))
+}
+
+/// Percent encode any characters in .suffix that may not be
+/// written literally in a tag
+fn escape_uri(suffix: &str, verbatim: bool) -> String
+{
+    let mut escaped = String::with_capacity(suffix.len());
+    let mut scratch = [0; 4];
+
+    for ch in suffix.chars()
+    {
+        let literal = match ch
+        {
+            '&'..='+' | '-'..='9' | 'A'..='Z' | 'a'..='z' => true,
+            '$' | ':' | ';' | '=' | '?' | '@' | '_' | '~' => true,
+            // '!' would be ambiguous with a named tag handle
+            '!' | ',' | '[' | ']' => verbatim,
+            _ => false,
+        };
+
+        match literal
+        {
+            true => escaped.push(ch),
+            false => ch
+                .encode_utf8(&mut scratch)
+                .bytes()
+                .for_each(|b| escaped.push_str(&format!("%{:02X}", b))),
+        }
+    }
+
+    escaped
+}
+
+fn is_default_tag(handle: &str, prefix: &str) -> bool
+{
+    DEFAULT_TAGS.iter().any(|(h, p)| h == handle && p == prefix)
+}
+
+fn is_flow_indicator(c: char) -> bool
+{
+    matches!(c, ',' | '[' | ']' | '{' | '}')
+}
+
+fn is_blank(c: char) -> bool
+{
+    matches!(c, ' ' | '\t')
+}
+
+fn is_break(c: char) -> bool
+{
+    matches!(c, '\n' | '\r' | '\u{85}' | '\u{2028}' | '\u{2029}')
+}
+
+/// Characters which may appear unescaped in a YAML stream,
+/// as defined in [Section 5.1][Link], excluding the byte
+/// order mark.
+///
+/// [Link]: https://yaml.org/spec/1.2/spec.html#c-printable
+fn is_printable(c: char) -> bool
+{
+    matches!(c,
+        '\t'
+        | '\n'
+        | '\x20'..='\x7E'
+        | '\u{85}'
+        | '\u{A0}'..='\u{D7FF}'
+        | '\u{E000}'..='\u{FEFE}'
+        | '\u{FF00}'..='\u{FFFD}'
+        | '\u{10000}'..='\u{10FFFF}'
+    )
+}
+
+#[cfg(test)]
+mod tests
+{
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        event::{
+            from_reader,
+            types::{ResolvedTag, StreamEncoding, StreamStart},
+        },
+        reader::borrow::BorrowReader,
+    };
+
+    type TestResult = anyhow::Result<()>;
+
+    fn events(yaml: &str) -> anyhow::Result<Vec<EventData<'static>>>
+    {
+        let reader = BorrowReader::new(yaml);
+
+        from_reader(&reader)
+            .into_iter()
+            .map(|event| Ok(event?.into_owned()?.into()))
+            .collect()
+    }
+
+    fn emit_events(events: Vec<EventData<'_>>) -> anyhow::Result<String>
+    {
+        emit_events_with(events, Flags::default())
+    }
+
+    fn emit_events_with(events: Vec<EventData<'_>>, flags: Flags) -> anyhow::Result<String>
+    {
+        let mut emitter = to_writer_with(Vec::new(), flags);
+
+        for event in events
+        {
+            emitter.emit(event)?;
+        }
+
+        Ok(String::from_utf8(emitter.into_inner())?)
+    }
+
+    fn emit_yaml(yaml: &str) -> anyhow::Result<String>
+    {
+        emit_events(events(yaml)?)
+    }
+
+    /// Parse .yaml, emit it and parse the output again,
+    /// checking that the two event streams match
+    fn round_trip(yaml: &str) -> TestResult
+    {
+        round_trip_with(yaml, Flags::default())
+    }
+
+    fn round_trip_with(yaml: &str, flags: Flags) -> TestResult
+    {
+        let expected = events(yaml)?;
+        let output = emit_events_with(expected.clone(), flags)?;
+        let got = events(&output)
+            .map_err(|e| anyhow::anyhow!("failed to parse emitted yaml:\n{}\n{}", output, e))?;
+
+        assert_eq!(expected, got, "emitted yaml:\n{}", output);
+
+        Ok(())
+    }
+
+    fn scalar(data: &'static str, style: ScalarStyle) -> EventData<'static>
+    {
+        EventData::Scalar(Node {
+            anchor:   None,
+            tag:      None,
+            content:  ScalarLike::eager(data.into(), style),
+            kind:     crate::event::types::NodeKind::Root,
+            resolved: match style
+            {
+                ScalarStyle::Plain => ResolvedTag::Question,
+                _ => ResolvedTag::Exclamation,
+            },
+        })
+    }
+
+    fn document(root: EventData<'static>) -> Vec<EventData<'static>>
+    {
+        vec![
+            EventData::StreamStart(StreamStart {
+                encoding: StreamEncoding::UTF8,
+            }),
+            EventData::DocumentStart(DocumentStart {
+                directives: Default::default(),
+                implicit:   true,
+            }),
+            root,
+            EventData::DocumentEnd(DocumentEnd { implicit: true }),
+            EventData::StreamEnd,
+        ]
+    }
+
+    #[test]
+    fn empty_stream() -> TestResult
+    {
+        assert_eq!(emit_yaml("")?, "");
+
+        round_trip("")
+    }
+
+    #[test]
+    fn block_mapping() -> TestResult
+    {
+        let yaml = "key: value\nnested:\n  inner: 'quoted'\n  seq:\n    - a\n    - \"b\"\n";
+
+        assert_eq!(emit_yaml(yaml)?, yaml);
+
+        round_trip(yaml)
+    }
+
+    #[test]
+    fn block_sequence_compact() -> TestResult
+    {
+        let yaml = "- - a\n  - b\n- c: d\n  e: f\n- []\n- {}\n";
+
+        assert_eq!(emit_yaml(yaml)?, yaml);
+
+        round_trip(yaml)
+    }
+
+    #[test]
+    fn flow_collections_become_block() -> TestResult
+    {
+        assert_eq!(
+            emit_yaml("{a: [1, 2], b: {}}")?,
+            "a:\n  - 1\n  - 2\nb: {}\n"
+        );
+
+        round_trip("{a: [1, {x: y}], b: {}, c: []}")
+    }
+
+    #[test]
+    fn flow_style() -> TestResult
+    {
+        let yaml = "a:\n  - 1\n  - b: c\n    d: []\ne: {}\n";
+
+        assert_eq!(
+            emit_events_with(events(yaml)?, O_FLOW)?,
+            "{a: [1, {b: c, d: []}], e: {}}\n"
+        );
+
+        round_trip_with(yaml, O_FLOW)?;
+        round_trip_with("- &a [x, 'y, z', '']\n- *a\n- !t {? [k] : v}\n", O_FLOW)?;
+        round_trip_with("--- [a]\n--- b\n...\n", O_FLOW)
+    }
+
+    #[test]
+    fn flow_style_scalars() -> TestResult
+    {
+        let mut events = events("[a]")?;
+        events.insert(4, scalar("line\nbreak\n", ScalarStyle::Literal));
+        events.insert(4, scalar("{}", ScalarStyle::Plain));
+
+        assert_eq!(
+            emit_events_with(events, O_FLOW)?,
+            "[a, '{}', \"line\\nbreak\\n\"]\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn complex_keys() -> TestResult
+    {
+        let yaml = "? - a\n  - b\n: value\n? c: d\n: - e\n";
+
+        assert_eq!(emit_yaml(yaml)?, yaml);
+
+        round_trip(yaml)?;
+        round_trip("? [a, b]\n: {c: d}\n? |\n  block\n: x\n")
+    }
+
+    #[test]
+    fn anchors_aliases() -> TestResult
+    {
+        let yaml = "anchor: &a value\nalias: *a\n*a : key\nseq: &s\n  - x\nmap: &m {}\n";
+
+        assert_eq!(emit_yaml(yaml)?, yaml);
+
+        round_trip(yaml)?;
+        round_trip("- &a\n  - &b [1]\n- *b\n- *a\n")
+    }
+
+    #[test]
+    fn tags() -> TestResult
+    {
+        let yaml = "%TAG !e! tag:example.com:2000:\n--- !!map\n!e!foo key: !local [1, !!int 2]\n";
+
+        assert_eq!(
+            emit_yaml(yaml)?,
+            "%TAG !e! tag:example.com:2000:\n--- !!map\n!e!foo key: !local\n  - 1\n  - !!int 2\n"
+        );
+
+        round_trip(yaml)
+    }
+
+    #[test]
+    fn tag_escapes()
+    {
+        assert_eq!(format_tag("!", "a!b c"), "!a%21b%20c");
+        assert_eq!(format_tag("!!", "str"), "!!str");
+        assert_eq!(format_tag("", "tag:a,b"), "!<tag:a,b>");
+    }
+
+    #[test]
+    fn scalar_styles() -> TestResult
+    {
+        round_trip(
+            "- plain text\n- 'single ''quoted'''\n- \"double\\tquoted\\n\"\n- ''\n- \"\"\n",
+        )?;
+        round_trip("- \"\\x01\\e\\0\\\\\"\n- 'a: b'\n- '- a'\n- '#comment'\n")?;
+        round_trip("-\n- ~\n- key:\n  other: ''\n")
+    }
+
+    #[test]
+    fn double_quote_escapes() -> TestResult
+    {
+        let events = document(scalar(
+            "\x01\u{A0}\u{85}\u{2028}\u{FEFF}\"\\",
+            ScalarStyle::DoubleQuote,
+        ));
+        assert_eq!(
+            emit_events(events)?,
+            "\"\\x01\u{A0}\\N\\L\\uFEFF\\\"\\\\\"\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn plain_fallback() -> TestResult
+    {
+        let events = document(scalar("a: b", ScalarStyle::Plain));
+        assert_eq!(emit_events(events)?, "'a: b'\n");
+
+        let events = document(scalar("line\nbreak", ScalarStyle::Plain));
+        assert_eq!(emit_events(events)?, "\"line\\nbreak\"\n");
+
+        let events = document(scalar("", ScalarStyle::Plain));
+        assert_eq!(emit_events(events)?, "''\n");
+
+        let events = document(scalar("\r", ScalarStyle::Literal));
+        assert_eq!(emit_events(events)?, "\"\\r\"\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn literal_scalars() -> TestResult
+    {
+        let yaml = "strip: |-\n  text\nclip: |\n  text\n\n  more\nkeep: |+\n  text\n\nnext: x\n";
+
+        assert_eq!(emit_yaml(yaml)?, yaml);
+
+        round_trip(yaml)?;
+        round_trip("- |2\n    leading\n  text\n- |\n\n  after empty\n- |+\n\n- |-\n")?;
+        round_trip("--- |\n  root\n--- |+\n  root keep\n\n")
+    }
+
+    #[test]
+    fn folded_scalars() -> TestResult
+    {
+        let yaml = "- >\n  folded\n  text\n\n  para\n- >-\n  a\n\n    more indented\n  b\n";
+
+        round_trip(yaml)?;
+        round_trip("- >\n  a\n\n\n  b\n- >+\n  trailing\n\n\n")?;
+
+        let events = document(scalar("a\nb\n", ScalarStyle::Folded));
+        assert_eq!(emit_events(events)?, ">\n  a\n\n  b\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn documents() -> TestResult
+    {
+        round_trip("a\n---\nb\n...\n--- c\n...\n")?;
+        round_trip("--- []\n--- {}\n---\n...\n")?;
+        round_trip("%YAML 1.1\n---\na\n...\n%YAML 1.1\n%TAG !x! tag:x:\n--- !x!y b\n")?;
+
+        assert_eq!(emit_yaml("a\n---\nb\n...\n")?, "a\n--- b\n...\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn unexpected_event()
+    {
+        let mut emitter = to_writer(Vec::new());
+
+        let bad = emitter.emit(EventData::StreamEnd).unwrap_err();
+        assert_eq!(bad.classify(), crate::error::Category::Syntax);
+
+        let mut events = document(scalar("a", ScalarStyle::Plain));
+        events.insert(3, EventData::MappingEnd);
+
+        assert!(emit_events(events).is_err());
+    }
+}
@@ -11,11 +11,18 @@
 //! type into [`std::io::Error`], which may be more
 //! portable, at the expense of losing all of the local
 //! context this error type provides.
+//!
+//! Specific errors can be identified by their
+//! [`ErrorKind`], each of which has a stable code (e.g
+//! `E0015`) that will not change between releases.
 
 use std::{error::Error as StdError, fmt};
 
+use macros::error_kinds;
 pub(crate) use macros::mkError;
 
+use crate::event::types::Mark;
+
 /// Result typedef used throughout this library's public API
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -59,6 +66,8 @@ impl Error
     ///   surfaced an error while doing IO
     /// - [`Category::EOF`] The YAML stream ended
     ///   unexpectedly
+    /// - [`Category::Limit`] A configured resource limit
+    ///   was exceeded
     pub fn classify(&self) -> Category
     {
         self.inner.classify()
@@ -78,6 +87,17 @@ impl Error
         self.inner.has_context()
     }
 
+    /// The specific kind of error that occurred.
+    ///
+    /// Each [`ErrorKind`] has a stable [`code`], suitable
+    /// for matching on in configuration or documentation.
+    ///
+    /// [`code`]: ErrorKind::code
+    pub fn kind(&self) -> ErrorKind
+    {
+        ErrorKind::from(&self.inner.kind)
+    }
+
     /// Boxes the internal error, returning new public error
     /// type
     pub(crate) fn new(err: internal::Error) -> Self
@@ -86,6 +106,27 @@ impl Error
             inner: Box::new(err),
         }
     }
+
+    /// The internal representation of this error's cause
+    pub(crate) fn repr(&self) -> &internal::ErrorKind
+    {
+        &self.inner.kind
+    }
+
+    /// Contextualize this error with the given stream
+    /// position, converting the [`Mark`]'s zero based line
+    /// and column to the one based values reported to
+    /// callers.
+    pub(crate) fn with_mark(mut self, mark: Mark) -> Self
+    {
+        self.inner.context(
+            mark.offset as u64,
+            mark.line as u64 + 1,
+            mark.column as u64 + 1,
+        );
+
+        self
+    }
 }
 
 /// Rough category of an [`Error`].
@@ -108,6 +149,262 @@ pub enum Category
     /// The YAML stream unexpectedly terminated before it
     /// was appropriate to do so
     EOF,
+
+    /// The YAML stream exceeded a configured resource
+    /// limit, and processing was stopped
+    Limit,
+}
+
+error_kinds! {
+    /*
+     * ==== Scanner Errors ====
+     */
+    /// `E0001` The YAML stream ended in the middle of a token
+    UnexpectedEOF = "E0001",
+    /// `E0002` A directive was not either `%YAML` or `%TAG`
+    UnknownDirective = "E0002",
+    /// `E0003` A `%YAML` directive was missing its major
+    /// version
+    MissingMajor = "E0003",
+    /// `E0004` A `%YAML` directive was missing its minor
+    /// version
+    MissingMinor = "E0004",
+    /// `E0005` A mapping value was expected, but not found
+    MissingValue = "E0005",
+    /// `E0006` A `%YAML` directive's version was invalid
+    InvalidVersion = "E0006",
+    /// `E0007` A tag handle was not `!`, `!!` or `!name!`
+    InvalidTagHandle = "E0007",
+    /// `E0008` A `%TAG` directive's prefix was invalid
+    InvalidTagPrefix = "E0008",
+    /// `E0009` A tag's suffix was invalid
+    InvalidTagSuffix = "E0009",
+    /// `E0010` An anchor or alias name was invalid
+    InvalidAnchorName = "E0010",
+    /// `E0011` A quoted scalar was invalid
+    InvalidFlowScalar = "E0011",
+    /// `E0012` A plain scalar contained a character
+    /// sequence that is not permitted
+    InvalidPlainScalar = "E0012",
+    /// `E0013` A block scalar contained a character
+    /// sequence that is not permitted
+    InvalidBlockScalar = "E0013",
+    /// `E0014` A block sequence entry was not allowed
+    InvalidBlockEntry = "E0014",
+    /// `E0015` A tab character was found in indentation
+    InvalidTab = "E0015",
+    /// `E0016` A mapping key was not allowed
+    InvalidKey = "E0016",
+    /// `E0017` A mapping value was not allowed
+    InvalidValue = "E0017",
+    /// `E0018` A double quoted scalar contained an unknown
+    /// escape sequence
+    UnknownEscape = "E0018",
+    /// `E0019` A character was found that cannot start a
+    /// token
+    UnknownDelimiter = "E0019",
+    /// `E0020` An integer overflowed
+    IntOverflow = "E0020",
+
+    /*
+     * ==== Parser Errors ====
+     */
+    /// `E0021` The parser's view of the YAML stream was
+    /// inconsistent
+    CorruptStream = "E0021",
+    /// `E0022` A document had more than one `%YAML`
+    /// directive
+    DuplicateVersion = "E0022",
+    /// `E0023` A document had more than one `%TAG`
+    /// directive for the same handle
+    DuplicateTagDirective = "E0023",
+    /// `E0024` A tag used a handle that was not defined
+    UndefinedTag = "E0024",
+    /// `E0025` A document start (`---`) was required but
+    /// not found
+    MissingDocumentStart = "E0025",
+    /// `E0026` A block sequence entry was required but not
+    /// found
+    MissingBlockEntry = "E0026",
+    /// `E0027` A node was required but not found
+    MissingNode = "E0027",
+    /// `E0028` A mapping key was required but not found
+    MissingKey = "E0028",
+    /// `E0029` A flow sequence was missing a `,` or `]`
+    MissingFlowSequenceEntryOrEnd = "E0029",
+    /// `E0030` A flow mapping was missing a `,` or `}`
+    MissingFlowMappingEntryOrEnd = "E0030",
+
+    /*
+     * ==== Emitter Errors ====
+     */
+    /// `E0031` An emitter was given an event that was not
+    /// valid at its position in the event stream
+    UnexpectedEvent = "E0031",
+
+    /*
+     * ==== Tree Errors ====
+     */
+    /// `E0032` An alias referred to an undefined anchor
+    UndefinedAlias = "E0032",
+
+    /*
+     * ==== Schema Errors ====
+     */
+    /// `E0033` A scalar did not match the type of its tag
+    InvalidScalar = "E0033",
+
+    /*
+     * ==== Reader Errors ====
+     */
+    /// `E0034` A UTF16 or UTF32 stream contained an invalid
+    /// character
+    InvalidEncoding = "E0034",
+    /// `E0035` The underlying byte stream returned an IO
+    /// error
+    IO = "E0035",
+    /// `E0036` The YAML stream was not valid UTF8
+    UTF8 = "E0036",
+
+    /*
+     * ==== CST Errors ====
+     */
+    /// `E0037` A scalar edit was made to a non scalar node
+    NotScalar = "E0037",
+    /// `E0038` An edit was made inside of an already
+    /// replaced node
+    EditConflict = "E0038",
+
+    /*
+     * ==== Limit Errors ====
+     */
+    /// `E0039` Alias expansion produced too many nodes
+    ExpansionLimit = "E0039",
+    /// `E0040` Aliases were nested too deeply during
+    /// expansion
+    AliasDepthLimit = "E0040",
+    /// `E0041` Collections were nested too deeply
+    DepthLimit = "E0041",
+    /// `E0042` A scalar was too long
+    ScalarLimit = "E0042",
+    /// `E0043` An anchor or alias name was too long
+    AnchorLimit = "E0043",
+    /// `E0044` A tag or tag directive was too long
+    TagLimit = "E0044",
+    /// `E0045` The YAML stream contained too many documents
+    DocumentLimit = "E0045",
+    /// `E0046` The YAML stream produced too many events
+    EventLimit = "E0046",
+
+    /*
+     * ==== Serde Errors ====
+     */
+    /// `E0047` A (de)serialization implementation reported
+    /// an error
+    Message = "E0047",
+
+    /*
+     * ==== Query Errors ====
+     */
+    /// `E0048` A query expression was not valid
+    InvalidQuery = "E0048",
+
+    /*
+     * ==== Tag Errors ====
+     */
+    /// `E0049` A node's tag had no registered constructor
+    UnknownTag = "E0049",
+    /// `E0050` A tag constructor rejected its node
+    Constructor = "E0050",
+
+    /*
+     * ==== Merge Errors ====
+     */
+    /// `E0051` A merge key's value was not a mapping, or a
+    /// sequence of mappings
+    InvalidMerge = "E0051",
+
+    /*
+     * ==== Duplicate Key Errors ====
+     */
+    /// `E0052` A mapping contained the same key more than
+    /// once
+    DuplicateKey = "E0052",
+}
+
+impl From<&'_ internal::ErrorKind> for ErrorKind
+{
+    fn from(kind: &'_ internal::ErrorKind) -> Self
+    {
+        use internal::{ErrorCode as C, ErrorKind as K, SourceError as S};
+
+        match kind
+        {
+            K::Code(code) => match code
+            {
+                C::UnexpectedEOF => Self::UnexpectedEOF,
+                C::UnknownDirective => Self::UnknownDirective,
+                C::MissingMajor => Self::MissingMajor,
+                C::MissingMinor => Self::MissingMinor,
+                C::MissingValue => Self::MissingValue,
+                C::InvalidVersion => Self::InvalidVersion,
+                C::InvalidTagHandle => Self::InvalidTagHandle,
+                C::InvalidTagPrefix => Self::InvalidTagPrefix,
+                C::InvalidTagSuffix => Self::InvalidTagSuffix,
+                C::InvalidAnchorName => Self::InvalidAnchorName,
+                C::InvalidFlowScalar => Self::InvalidFlowScalar,
+                C::InvalidPlainScalar => Self::InvalidPlainScalar,
+                C::InvalidBlockScalar => Self::InvalidBlockScalar,
+                C::InvalidBlockEntry => Self::InvalidBlockEntry,
+                C::InvalidTab => Self::InvalidTab,
+                C::InvalidKey => Self::InvalidKey,
+                C::InvalidValue => Self::InvalidValue,
+                C::UnknownEscape => Self::UnknownEscape,
+                C::UnknownDelimiter => Self::UnknownDelimiter,
+                C::IntOverflow => Self::IntOverflow,
+                C::CorruptStream => Self::CorruptStream,
+                C::DuplicateVersion => Self::DuplicateVersion,
+                C::DuplicateTagDirective => Self::DuplicateTagDirective,
+                C::UndefinedTag => Self::UndefinedTag,
+                C::MissingDocumentStart => Self::MissingDocumentStart,
+                C::MissingBlockEntry => Self::MissingBlockEntry,
+                C::MissingNode => Self::MissingNode,
+                C::MissingKey => Self::MissingKey,
+                C::MissingFlowSequenceEntryOrEnd => Self::MissingFlowSequenceEntryOrEnd,
+                C::MissingFlowMappingEntryOrEnd => Self::MissingFlowMappingEntryOrEnd,
+                C::UnexpectedEvent => Self::UnexpectedEvent,
+                C::UndefinedAlias => Self::UndefinedAlias,
+                C::InvalidScalar => Self::InvalidScalar,
+                C::InvalidEncoding => Self::InvalidEncoding,
+                C::NotScalar => Self::NotScalar,
+                C::EditConflict => Self::EditConflict,
+                C::ExpansionLimit => Self::ExpansionLimit,
+                C::AliasDepthLimit => Self::AliasDepthLimit,
+                C::DepthLimit => Self::DepthLimit,
+                C::ScalarLimit => Self::ScalarLimit,
+                C::AnchorLimit => Self::AnchorLimit,
+                C::TagLimit => Self::TagLimit,
+                C::DocumentLimit => Self::DocumentLimit,
+                C::EventLimit => Self::EventLimit,
+                C::InvalidQuery => Self::InvalidQuery,
+                C::UnknownTag => Self::UnknownTag,
+                C::InvalidMerge => Self::InvalidMerge,
+                C::DuplicateKey => Self::DuplicateKey,
+            },
+            K::Source(S::IO(_)) => Self::IO,
+            K::Source(S::UTF8(_)) => Self::UTF8,
+            K::Source(S::Message(_)) => Self::Message,
+            K::Source(S::Constructor(_)) => Self::Constructor,
+        }
+    }
+}
+
+impl fmt::Display for ErrorKind
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        f.write_str(self.code())
+    }
 }
 
 pub(crate) mod internal
@@ -465,6 +762,132 @@ pub(crate) mod internal
         /// #                                  ^ MissingFlowMappingEntryOrEnd
         /// ```
         MissingFlowMappingEntryOrEnd,
+
+        /*
+         * ==== Emitter Errors ====
+         */
+        /// An event was provided to an emitter that is not
+        /// valid at its current position in the event
+        /// stream.
+        ///
+        /// ## Examples
+        ///
+        /// ```text
+        /// SequenceStart
+        /// MappingEnd
+        /// ^^^^^^^^^^ UnexpectedEvent
+        /// ```
+        UnexpectedEvent,
+
+        /*
+         * ==== Tree Errors ====
+         */
+        /// An alias referred to an anchor that was not
+        /// defined, or whose node was incomplete.
+        ///
+        /// ## Examples
+        ///
+        /// ```yaml
+        /// key: *missing
+        /// #    ^^^^^^^^ UndefinedAlias
+        /// ```
+        UndefinedAlias,
+
+        /*
+         * ==== Schema Errors ====
+         */
+        /// A scalar could not be resolved by the active
+        /// schema, or did not match the type of its tag.
+        ///
+        /// ## Examples
+        ///
+        /// ```yaml
+        /// key: !!int seventeen
+        /// #          ^^^^^^^^^ InvalidScalar
+        /// ```
+        InvalidScalar,
+
+        /*
+         * ==== Reader Errors ====
+         */
+        /// A UTF16 or UTF32 encoded byte stream contained
+        /// an invalid or incomplete character, such as an
+        /// unpaired surrogate.
+        InvalidEncoding,
+
+        /*
+         * ==== CST Errors ====
+         */
+        /// A scalar edit was made to a node which is not a
+        /// scalar.
+        NotScalar,
+        /// An edit was made inside of a node which had
+        /// already been replaced.
+        EditConflict,
+
+        /*
+         * ==== Limit Errors ====
+         */
+        /// Alias expansion produced more nodes than were
+        /// permitted, either in total or relative to the
+        /// size of the YAML stream.
+        ///
+        /// ## Examples
+        ///
+        /// ```yaml
+        /// a: &a [x, x, x, x, x, x, x, x, x]
+        /// b: &b [*a, *a, *a, *a, *a, *a, *a, *a, *a]
+        /// c: &c [*b, *b, *b, *b, *b, *b, *b, *b, *b]
+        /// #      ^^ ExpansionLimit
+        /// ```
+        ExpansionLimit,
+        /// Aliases were nested more deeply than was
+        /// permitted during alias expansion.
+        AliasDepthLimit,
+        /// Collections were nested more deeply than was
+        /// permitted.
+        DepthLimit,
+        /// A scalar was longer than was permitted.
+        ScalarLimit,
+        /// An anchor or alias name was longer than was
+        /// permitted.
+        AnchorLimit,
+        /// A tag or tag directive was longer than was
+        /// permitted.
+        TagLimit,
+        /// The YAML stream contained more documents than
+        /// were permitted.
+        DocumentLimit,
+        /// The YAML stream produced more events than were
+        /// permitted.
+        EventLimit,
+
+        /*
+         * ==== Query Errors ====
+         */
+        /// A query expression could not be parsed.
+        InvalidQuery,
+
+        /*
+         * ==== Tag Errors ====
+         */
+        /// A node's tag was not registered, and unknown
+        /// tags are rejected
+        UnknownTag,
+
+        /*
+         * ==== Merge Errors ====
+         */
+        /// A merge key's value was not an alias to a
+        /// mapping, a mapping or a sequence of either
+        InvalidMerge,
+
+        /*
+         * ==== Duplicate Key Errors ====
+         */
+        /// A mapping key was equal to an earlier key of the
+        /// same mapping
+        DuplicateKey,
     }
 
     /// Heavy and/or external errors that can occur during
@@ -481,6 +904,19 @@ pub(crate) mod internal
 
         /// Encountered invalid an UTF8 sequence
         UTF8(Utf8Error),
+
+        /*
+         * ==== Serde Errors ====
+         */
+        /// A free form error message, typically produced by
+        /// a (de)serialization implementation
+        Message(Box<str>),
+
+        /*
+         * ==== Tag Errors ====
+         */
+        /// A tag constructor rejected the node it was given
+        Constructor(Box<dyn StdError + Send + Sync>),
     }
 
     impl fmt::Debug for Error
@@ -562,6 +998,7 @@ pub(crate) mod internal
                 Category::Syntax => io::Error::new(io::ErrorKind::InvalidInput, err),
                 Category::Data => io::Error::new(io::ErrorKind::InvalidData, err),
                 Category::EOF => io::Error::new(io::ErrorKind::UnexpectedEof, err),
+                Category::Limit => io::Error::new(io::ErrorKind::InvalidData, err),
                 Category::IO => unreachable!(),
             }
         }
@@ -643,11 +1080,17 @@ pub(crate) mod internal
                 | MissingNode
                 | MissingKey
                 | MissingFlowSequenceEntryOrEnd
-                | MissingFlowMappingEntryOrEnd => Category::Syntax,
+                | MissingFlowMappingEntryOrEnd
+                | UnexpectedEvent => Category::Syntax,
 
-                IntOverflow | CorruptStream => Category::Data,
+                IntOverflow | CorruptStream | UndefinedAlias | InvalidScalar | InvalidEncoding
+                | NotScalar | EditConflict | InvalidQuery | UnknownTag | InvalidMerge
+                | DuplicateKey => Category::Data,
 
                 UnexpectedEOF => Category::EOF,
+
+                ExpansionLimit | AliasDepthLimit | DepthLimit | ScalarLimit | AnchorLimit
+                | TagLimit | DocumentLimit | EventLimit => Category::Limit,
             }
         }
     }
@@ -713,6 +1156,24 @@ pub(crate) mod internal
                 {
                     f.write_str("missing flow mapping delimiter ',' or '}'")
                 },
+                UnexpectedEvent => f.write_str("event is not valid at this point in the stream"),
+                UndefinedAlias => f.write_str("alias refers to an undefined anchor"),
+                InvalidScalar => f.write_str("scalar does not match its resolved type"),
+                InvalidEncoding => f.write_str("invalid UTF16 or UTF32 character sequence"),
+                NotScalar => f.write_str("node is not a scalar"),
+                EditConflict => f.write_str("edit overlaps a previously replaced node"),
+                ExpansionLimit => f.write_str("alias expansion exceeded the node limit"),
+                AliasDepthLimit => f.write_str("alias expansion exceeded the depth limit"),
+                DepthLimit => f.write_str("collection nesting exceeded the depth limit"),
+                ScalarLimit => f.write_str("scalar exceeded the length limit"),
+                AnchorLimit => f.write_str("anchor name exceeded the length limit"),
+                TagLimit => f.write_str("tag exceeded the length limit"),
+                DocumentLimit => f.write_str("stream exceeded the document limit"),
+                EventLimit => f.write_str("stream exceeded the event limit"),
+                InvalidQuery => f.write_str("invalid query expression"),
+                UnknownTag => f.write_str("no constructor is registered for this tag"),
+                InvalidMerge => f.write_str("merge key value is not a mapping"),
+                DuplicateKey => f.write_str("duplicate mapping key"),
             }
         }
     }
@@ -726,7 +1187,10 @@ pub(crate) mod internal
             match err
             {
                 SourceError::IO(_) => Category::IO,
-                SourceError::UTF8(_) => Category::Data,
+                SourceError::UTF8(_) | SourceError::Message(_) | SourceError::Constructor(_) =>
+                {
+                    Category::Data
+                },
             }
         }
     }
@@ -739,6 +1203,8 @@ pub(crate) mod internal
             {
                 SourceError::IO(ref e) => fmt::Display::fmt(e, f),
                 SourceError::UTF8(ref e) => fmt::Display::fmt(e, f),
+                SourceError::Message(ref msg) => f.write_str(msg),
+                SourceError::Constructor(ref e) => fmt::Display::fmt(e, f),
             }
         }
     }
@@ -751,6 +1217,8 @@ pub(crate) mod internal
             {
                 SourceError::IO(e) => Some(e),
                 SourceError::UTF8(e) => Some(e),
+                SourceError::Message(_) => None,
+                SourceError::Constructor(e) => Some(&**e),
             }
         }
     }
@@ -844,6 +1312,59 @@ mod macros
     }
 
     pub(crate) use mkError;
+
+    /// error_kinds declares the public
+    /// [`ErrorKind`](super::ErrorKind) enum, pairing each
+    /// variant with its stable error code.
+    ///
+    /// Codes must never be changed or reused once assigned,
+    /// new variants should be given the next unused code.
+    ///
+    /// Variants
+    ///     /1 $( $(#[$attr])* $kind = $code ),+
+    macro_rules! error_kinds {
+        ($( $(#[$attr:meta])* $kind:ident = $code:literal ),+ $(,)?) => {
+            /// The specific kind of an [`Error`].
+            ///
+            /// Each variant has a stable code, of the form
+            /// `E0000`, which will not change between
+            /// releases. New variants may be added in
+            /// future releases.
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            #[non_exhaustive]
+            pub enum ErrorKind
+            {
+                $( $(#[$attr])* $kind, )+
+            }
+
+            impl ErrorKind
+            {
+                /// This kind's stable error code, e.g
+                /// `E0015` for [`ErrorKind::InvalidTab`]
+                pub fn code(&self) -> &'static str
+                {
+                    match self
+                    {
+                        $( Self::$kind => $code, )+
+                    }
+                }
+
+                /// Look up an [`ErrorKind`] by its
+                /// [`code`](#method.code), returning None
+                /// if no kind has the given .code
+                pub fn from_code(code: &str) -> Option<Self>
+                {
+                    match code
+                    {
+                        $( $code => Some(Self::$kind), )+
+                        _ => None,
+                    }
+                }
+            }
+        };
+    }
+
+    pub(crate) use error_kinds;
 }
 
 impl fmt::Debug for Error
@@ -877,3 +1398,71 @@ impl From<Error> for std::io::Error
         From::from(*err.inner)
     }
 }
+
+#[cfg(feature = "serde")]
+impl serde::de::Error for Error
+{
+    fn custom<T>(msg: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        mkError!(
+            internal::SourceError::Message(msg.to_string().into()),
+            SOURCE
+        )
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::ser::Error for Error
+{
+    fn custom<T>(msg: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        mkError!(
+            internal::SourceError::Message(msg.to_string().into()),
+            SOURCE
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{event::from_reader, reader::borrow::BorrowReader};
+
+    #[test]
+    fn kind_codes()
+    {
+        // Codes are assigned contiguously, and each maps back to
+        // a single kind
+        for n in 1..=52
+        {
+            let code = format!("E{:04}", n);
+            let kind = ErrorKind::from_code(&code).expect("a kind for each code");
+
+            assert_eq!(kind.code(), code);
+        }
+
+        assert_eq!(ErrorKind::from_code("E0000"), None);
+        assert_eq!(ErrorKind::from_code("E0053"), None);
+    }
+
+    #[test]
+    fn kind_of_error()
+    {
+        let yaml = BorrowReader::new("%YAML 1.2\n%YAML 1.2\n---\nkey: value\n");
+        let error = from_reader(&yaml)
+            .iter()
+            .find_map(Result::err)
+            .expect("a duplicate %YAML directive");
+
+        assert_eq!(error.kind(), ErrorKind::DuplicateVersion);
+        assert_eq!(error.kind().code(), "E0022");
+        assert_eq!(error.kind().to_string(), "E0022");
+    }
+}
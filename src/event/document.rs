@@ -0,0 +1,488 @@
+/*
+ * This Source Code Form is subject to the terms of the
+ * Mozilla Public License, v. 2.0. If a copy of the MPL
+ * was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+//! This module exposes [`Documents`], which splits an
+//! [`Events`] stream into its documents.
+//!
+//! Each call to [`Documents::next_document`] returns a
+//! [`Document`] handle, carrying the document's
+//! [`Directives`] and whether its start was explicit
+//! (`---`). The handle is an iterator over the events of
+//! the document's content, that is every event between its
+//! DocumentStart and DocumentEnd. Once these are exhausted,
+//! the handle also reports the document's end, and whether
+//! it was explicit (`...`). Alternatively, the document can
+//! be materialized with [`Document::into_tree`].
+//!
+//! A handle borrows its [`Documents`], so only one document
+//! may be open at a time. Dropping a handle before its
+//! content is exhausted skips the rest of the document,
+//! without evaluating any of its scalars. Comments outside
+//! of any document are discarded.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use yary::{
+//!     event::{document::documents, from_reader},
+//!     reader::borrow::BorrowReader,
+//! };
+//!
+//! let yaml = BorrowReader::new("%YAML 1.1\n--- [a, b]\n...\nc\n--- {d: e}\n");
+//! let mut documents = documents(from_reader(&yaml));
+//! let mut explicit = Vec::new();
+//!
+//! while let Some(document) = documents.next_document()
+//! {
+//!     let document = document?;
+//!
+//!     explicit.push(document.is_explicit_start());
+//!
+//!     // Only the first document is needed in full, the rest
+//!     // are skipped
+//!     if document.directives().version.minor == 1
+//!     {
+//!         let root = document.into_tree()?;
+//!
+//!         assert_eq!(root[1].as_str(), Some("b"));
+//!     }
+//! }
+//!
+//! assert_eq!(explicit, vec![true, false, true]);
+//! # Ok::<(), yary::Error>(())
+//! ```
+//!
+//! [`Events`]: super::Events
+//! [`Directives`]: super::types::Directives
+
+use crate::{
+    error::Result,
+    event::{
+        types::{Directives, Event, EventData, Span},
+        Events,
+    },
+    reader::Read,
+    tree::{self, tag::Registry, Builder},
+};
+
+/// Instantiate a new [`Documents`] over the given events.
+///
+/// See the [module](self) documentation for more
+/// information.
+pub fn documents<R>(events: Events<'_, R>) -> Documents<'_, R>
+where
+    R: Read,
+{
+    Documents::new(events)
+}
+
+/// Splits an [`Events`] stream into its [`Document`]s.
+///
+/// See the [module](self) documentation for more
+/// information.
+#[derive(Debug)]
+pub struct Documents<'de, R>
+{
+    events:     Events<'de, R>,
+    /// The last document's handle was dropped before its
+    /// end was seen
+    unfinished: bool,
+    done:       bool,
+}
+
+impl<'de, R> Documents<'de, R>
+where
+    R: Read,
+{
+    /// Instantiate a new [`Documents`] over the given
+    /// events
+    pub fn new(events: Events<'de, R>) -> Self
+    {
+        Self {
+            events,
+            unfinished: false,
+            done: false,
+        }
+    }
+
+    /// Consume this structure, returning the underlying
+    /// events
+    pub fn into_inner(self) -> Events<'de, R>
+    {
+        self.events
+    }
+
+    /// Retrieve a handle to the next document in the
+    /// stream, or None if the stream is finished.
+    ///
+    /// Any unread events of the previous document are
+    /// skipped first. After an error is returned, either
+    /// here or from a [`Document`], every subsequent call
+    /// returns None.
+    pub fn next_document(&mut self) -> Option<Result<Document<'_, 'de, R>>>
+    {
+        if self.done
+        {
+            return None;
+        }
+
+        match self.start_document()
+        {
+            Ok(Some((span, directives, implicit))) => Some(Ok(Document {
+                documents: self,
+                directives,
+                start: (span, implicit),
+                end: None,
+            })),
+            Ok(None) =>
+            {
+                self.done = true;
+
+                None
+            },
+            Err(e) =>
+            {
+                self.done = true;
+
+                Some(Err(e))
+            },
+        }
+    }
+
+    /// Skip to the next DocumentStart, returning its span
+    /// and contents
+    #[allow(clippy::type_complexity)]
+    fn start_document(&mut self) -> Result<Option<(Span, Directives<'de>, bool)>>
+    {
+        if self.unfinished
+        {
+            self.unfinished = false;
+            self.skip_document()?;
+        }
+
+        while let Some(event) = self.events.next_event()?
+        {
+            let span = event.span();
+
+            if let EventData::DocumentStart(start) = EventData::from(event)
+            {
+                return Ok(Some((span, start.directives, start.implicit)));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Skip the remaining events of the current document,
+    /// up to and including its DocumentEnd
+    fn skip_document(&mut self) -> Result<()>
+    {
+        loop
+        {
+            // Skip whole nodes where possible, avoiding the cost
+            // of evaluating their scalars
+            if self.events.skip_node()?.is_some()
+            {
+                continue;
+            }
+
+            match self.events.next_event()?.as_ref().map(Event::data)
+            {
+                Some(EventData::DocumentEnd(_)) | None => return Ok(()),
+                Some(_) => continue,
+            }
+        }
+    }
+}
+
+/// A handle to a single document of a YAML stream,
+/// returned by [`Documents::next_document`].
+///
+/// Iterating over the handle produces the events of the
+/// document's content, in stream order.
+#[derive(Debug)]
+pub struct Document<'a, 'de, R>
+where
+    R: Read,
+{
+    documents:  &'a mut Documents<'de, R>,
+    directives: Directives<'de>,
+    /// The DocumentStart's span, and if it was implicit
+    start:      (Span, bool),
+    /// The DocumentEnd's span, and if it was implicit,
+    /// once seen
+    end:        Option<(Span, bool)>,
+}
+
+impl<'a, 'de, R> Document<'a, 'de, R>
+where
+    R: Read,
+{
+    /// The directives in scope for this document
+    pub fn directives(&self) -> &Directives<'de>
+    {
+        &self.directives
+    }
+
+    /// The span of this document's start, which is zero
+    /// width if the start was implicit
+    pub fn start(&self) -> Span
+    {
+        self.start.0
+    }
+
+    /// Did this document begin with an explicit `---`
+    pub fn is_explicit_start(&self) -> bool
+    {
+        !self.start.1
+    }
+
+    /// The span of this document's end, if its content has
+    /// been exhausted
+    pub fn end(&self) -> Option<Span>
+    {
+        self.end.map(|(span, _)| span)
+    }
+
+    /// Did this document finish with an explicit `...`,
+    /// if its content has been exhausted
+    pub fn is_explicit_end(&self) -> Option<bool>
+    {
+        self.end.map(|(_, implicit)| !implicit)
+    }
+
+    /// Materialize the rest of this document into a tree,
+    /// returning its root [`Node`](tree::Node).
+    ///
+    /// If no content remains, the root is an empty plain
+    /// scalar.
+    pub fn into_tree(self) -> Result<tree::Node<'de>>
+    {
+        self.build(Builder::new())
+    }
+
+    /// Materialize the rest of this document into a tree,
+    /// passing tagged nodes through the constructors of the
+    /// given [`Registry`].
+    ///
+    /// See [`Document::into_tree`] for more information.
+    pub fn into_tree_with(self, registry: &Registry) -> Result<tree::Node<'de>>
+    {
+        self.build(Builder::with_registry(registry.clone()))
+    }
+
+    fn build(mut self, mut builder: Builder<'de>) -> Result<tree::Node<'de>>
+    {
+        for event in self.by_ref()
+        {
+            builder.process(event?.into())?;
+        }
+
+        Ok(builder.end_document())
+    }
+}
+
+impl<'a, 'de, R> Iterator for Document<'a, 'de, R>
+where
+    R: Read,
+{
+    type Item = Result<Event<'de>>;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        if self.end.is_some() || self.documents.done
+        {
+            return None;
+        }
+
+        let event = match self.documents.events.next_event()
+        {
+            Ok(Some(event)) => event,
+            Ok(None) =>
+            {
+                self.documents.done = true;
+
+                return None;
+            },
+            Err(e) =>
+            {
+                self.documents.done = true;
+
+                return Some(Err(e));
+            },
+        };
+
+        match event.data()
+        {
+            EventData::DocumentEnd(end) =>
+            {
+                self.end = Some((event.span(), end.implicit));
+
+                None
+            },
+            _ => Some(Ok(event)),
+        }
+    }
+}
+
+impl<'a, 'de, R> std::iter::FusedIterator for Document<'a, 'de, R> where R: Read {}
+
+impl<'a, 'de, R> Drop for Document<'a, 'de, R>
+where
+    R: Read,
+{
+    fn drop(&mut self)
+    {
+        // Skipping is deferred to the next call to
+        // Documents::next_document, so any error can be
+        // returned from it
+        if self.end.is_none()
+        {
+            self.documents.unfinished = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{event::from_reader, reader::borrow::BorrowReader};
+
+    /// Summarize each document of the given YAML, as its
+    /// explicit start and end markers, and the number of
+    /// its content events
+    fn summarize(yaml: &str) -> Vec<(bool, Option<bool>, usize)>
+    {
+        let reader = BorrowReader::new(yaml);
+        let mut documents = documents(from_reader(&reader));
+        let mut summary = Vec::new();
+
+        while let Some(document) = documents.next_document()
+        {
+            let mut document = document.expect("a valid YAML stream");
+            let count = document.by_ref().inspect(|e| assert!(e.is_ok())).count();
+
+            summary.push((
+                document.is_explicit_start(),
+                document.is_explicit_end(),
+                count,
+            ));
+        }
+
+        summary
+    }
+
+    #[test]
+    fn markers()
+    {
+        let yaml = "a\n---\n[b, c]\n...\n--- d\n";
+
+        assert_eq!(
+            summarize(yaml),
+            vec![
+                (false, Some(false), 1),
+                (true, Some(true), 4),
+                (true, Some(false), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_documents()
+    {
+        // An empty stream still contains a single, implicit
+        // document
+        assert_eq!(summarize(""), vec![(false, Some(false), 0)]);
+        assert_eq!(
+            summarize("# only a comment\n"),
+            vec![(false, Some(false), 0)]
+        );
+        assert_eq!(
+            summarize("---\n---\n"),
+            vec![(true, Some(false), 0), (true, Some(false), 0)]
+        );
+    }
+
+    #[test]
+    fn directives()
+    {
+        let yaml = "%YAML 1.1\n%TAG !e! tag:example.com:2000:\n--- a\n...\n--- b\n";
+        let reader = BorrowReader::new(yaml);
+        let mut documents = documents(from_reader(&reader));
+
+        let first = documents.next_document().unwrap().unwrap();
+        assert_eq!(first.directives().version.minor, 1);
+        assert!(first.directives().tags.contains_key("!e!"));
+        drop(first);
+
+        let second = documents.next_document().unwrap().unwrap();
+        assert_eq!(second.directives().version.minor, 2);
+        assert!(!second.directives().tags.contains_key("!e!"));
+        drop(second);
+
+        assert!(documents.next_document().is_none());
+    }
+
+    #[test]
+    fn trees()
+    {
+        let yaml = "{a: 1}\n--- [2, 3]\n---\n";
+        let reader = BorrowReader::new(yaml);
+        let mut documents = documents(from_reader(&reader));
+        let mut roots = Vec::new();
+
+        while let Some(document) = documents.next_document()
+        {
+            roots.push(document.unwrap().into_tree().unwrap());
+        }
+
+        assert_eq!(roots.len(), 3);
+        assert_eq!(roots[0]["a"].as_str(), Some("1"));
+        assert_eq!(roots[1][1].as_str(), Some("3"));
+        assert_eq!(roots[2].as_str(), Some(""));
+    }
+
+    #[test]
+    fn drop_skips_document()
+    {
+        let yaml = "[a, {b: [c, d]}, e]\n---\nf: g\n...\n--- h\n";
+        let reader = BorrowReader::new(yaml);
+        let mut documents = documents(from_reader(&reader));
+
+        // Stop partway through the first document's sequence
+        let mut first = documents.next_document().unwrap().unwrap();
+        first.by_ref().take(3).for_each(drop);
+        drop(first);
+
+        // The second is dropped without reading any events
+        let second = documents.next_document().unwrap().unwrap();
+        assert!(second.is_explicit_start());
+        drop(second);
+
+        let third = documents.next_document().unwrap().unwrap();
+        assert_eq!(third.into_tree().unwrap().as_str(), Some("h"));
+
+        assert!(documents.next_document().is_none());
+    }
+
+    #[test]
+    fn errors_are_final()
+    {
+        let yaml = "a\n--- [b\n--- c\n";
+        let reader = BorrowReader::new(yaml);
+        let mut documents = documents(from_reader(&reader));
+
+        drop(documents.next_document().unwrap().unwrap());
+
+        let second = documents.next_document().unwrap().unwrap();
+        assert!(second.into_tree().is_err());
+
+        assert!(documents.next_document().is_none());
+    }
+}
@@ -15,6 +15,7 @@ use std::{
 
 use crate::{
     error::internal::{ErrorCode, ErrorKind},
+    event::types::StreamEncoding,
     reader::error::ReaderError,
     scanner::error::ScanError,
 };
@@ -187,6 +188,10 @@ pub(crate) enum ParseError
     /// error.
     UTF8(Utf8Error),
 
+    /// A UTF16 or UTF32 encoded byte stream encountered an
+    /// encoding error.
+    Encoding(StreamEncoding),
+
     /// An IO error occurred while attempting to read the
     /// byte stream.
     IO(io::Error),
@@ -208,6 +213,7 @@ impl From<ReaderError> for ParseError
         {
             ReaderError::UTF8(e) => Self::UTF8(e),
             ReaderError::IO(e) => Self::IO(e),
+            ReaderError::Encoding(e) => Self::Encoding(e),
             ReaderError::Scanner(e) => Self::Scanner(e),
         }
     }
@@ -269,6 +275,7 @@ impl From<ParseError> for ErrorKind
             ParseError::MissingFlowMappingEntryOrEnd => MissingFlowMappingEntryOrEnd.into(),
             ParseError::UnexpectedEOF => UnexpectedEOF.into(),
             ParseError::Scanner(e) => ErrorCode::from(e).into(),
+            ParseError::Encoding(_) => InvalidEncoding.into(),
             ParseError::UTF8(e) => ErrorKind::Source(e.into()),
             ParseError::IO(e) => ErrorKind::Source(e.into()),
         }
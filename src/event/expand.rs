@@ -0,0 +1,695 @@
+/*
+ * This Source Code Form is subject to the terms of the
+ * Mozilla Public License, v. 2.0. If a copy of the MPL
+ * was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+//! This module exposes [`Expand`], an [`Event`] adapter
+//! which replays the events of an anchored node in place of
+//! each [`Alias`] event that refers to it.
+//!
+//! Naively expanding aliases is dangerous when the YAML
+//! stream is untrusted, as a small stream can nest aliases
+//! to produce an exponential number of nodes (the "billion
+//! laughs" attack). [`Expand`] therefore enforces the
+//! [`Limits`] it was created with, returning an error of
+//! [`Category::Limit`] once any limit is exceeded.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use yary::{
+//!     event::{expand::expand, from_reader, types::EventData},
+//!     reader::borrow::BorrowReader,
+//! };
+//!
+//! let yaml = BorrowReader::new("[&a hello, *a]");
+//! let events = expand(from_reader(&yaml));
+//!
+//! let scalars = events
+//!     .filter_map(Result::ok)
+//!     .filter(|event| matches!(event.data(), EventData::Scalar(_)))
+//!     .count();
+//!
+//! assert_eq!(scalars, 2);
+//! ```
+//!
+//! [`Alias`]: type@super::types::EventData::Alias
+//! [`Category::Limit`]: enum@crate::error::Category
+
+use std::collections::HashMap;
+
+use crate::{
+    error::{internal::ErrorCode, mkError, Result},
+    event::types::{Event, EventData, NodeKind, Slice, Span},
+};
+
+/// Wraps the given events, expanding any aliases using the
+/// default [`Limits`].
+///
+/// See [`expand_with`] for more information.
+pub fn expand<'de, I>(events: I) -> Expand<'de, I::IntoIter>
+where
+    I: IntoIterator<Item = Result<Event<'de>>>,
+{
+    expand_with(events, Limits::default())
+}
+
+/// Wraps the given events, expanding any aliases subject to
+/// the given [`Limits`].
+///
+/// Expansion stops at the first error, either from the
+/// underlying events or from exceeding a limit, after which
+/// the returned iterator is fused.
+pub fn expand_with<'de, I>(events: I, limits: Limits) -> Expand<'de, I::IntoIter>
+where
+    I: IntoIterator<Item = Result<Event<'de>>>,
+{
+    Expand::new(events.into_iter(), limits)
+}
+
+/// Limits applied while expanding aliases.
+///
+/// Only nodes produced by expanding an alias count towards
+/// these limits, nodes present in the YAML stream are
+/// unrestricted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits
+{
+    /// Maximum number of nodes that may be produced by
+    /// alias expansion, across the entire stream
+    pub max_nodes: usize,
+    /// Maximum number of nodes that may be produced by
+    /// alias expansion, for each node (or alias) present
+    /// in the stream
+    pub max_ratio: usize,
+    /// Maximum number of aliases that may be nested inside
+    /// the nodes replayed by other aliases
+    pub max_depth: usize,
+}
+
+impl Limits
+{
+    /// Instantiates a new set of limits
+    pub const fn new(max_nodes: usize, max_ratio: usize, max_depth: usize) -> Self
+    {
+        Self {
+            max_nodes,
+            max_ratio,
+            max_depth,
+        }
+    }
+
+    /// Instantiates a set of limits which will never be
+    /// exceeded.
+    ///
+    /// This should only be used with trusted YAML streams.
+    pub const fn unlimited() -> Self
+    {
+        Self::new(usize::MAX, usize::MAX, usize::MAX)
+    }
+}
+
+impl Default for Limits
+{
+    fn default() -> Self
+    {
+        Self::new(1_000_000, 100, 64)
+    }
+}
+
+/// An [`Event`] adapter which replays anchored nodes in
+/// place of any aliases to them.
+///
+/// Replayed events carry the [`Span`] of the alias they
+/// replaced, and have their anchors removed.
+#[derive(Debug)]
+pub struct Expand<'de, I>
+{
+    events: I,
+    limits: Limits,
+
+    /// Events recorded from anchored nodes in the current
+    /// document
+    log:     Vec<Entry<'de>>,
+    /// Map of anchor names to their node's range in .log
+    anchors: HashMap<Slice<'de>, (usize, usize)>,
+    /// Stack of open collections, and the anchor and .log
+    /// position of each
+    open:    Vec<(Option<Slice<'de>>, usize)>,
+    /// Number of collections in .open which are anchored,
+    /// and thus being recorded
+    record:  usize,
+
+    /// Stack of .log ranges currently being replayed
+    replay: Vec<(usize, usize)>,
+    /// Span of the alias being replayed
+    span:   Span,
+
+    /// Position of the next node emitted
+    levels: Vec<Level>,
+
+    /// Nodes (and aliases) seen in the underlying stream
+    nodes:    usize,
+    /// Nodes produced by alias expansion
+    expanded: usize,
+    done:     bool,
+}
+
+impl<'de, I> Expand<'de, I>
+where
+    I: Iterator<Item = Result<Event<'de>>>,
+{
+    /// Instantiate a new [`Expand`] adapter over the given
+    /// events
+    pub fn new(events: I, limits: Limits) -> Self
+    {
+        Self {
+            events,
+            limits,
+            log: Vec::new(),
+            anchors: HashMap::new(),
+            open: Vec::new(),
+            record: 0,
+            replay: Vec::new(),
+            span: Span::default(),
+            levels: Vec::new(),
+            nodes: 0,
+            expanded: 0,
+            done: false,
+        }
+    }
+
+    /// Consume the adapter, returning the underlying
+    /// events
+    pub fn into_inner(self) -> I
+    {
+        self.events
+    }
+
+    fn next_event(&mut self) -> Option<Result<Event<'de>>>
+    {
+        if let Some(event) = self.next_replayed()
+        {
+            return Some(event);
+        }
+
+        let event = match self.events.next()?
+        {
+            Ok(event) => event,
+            Err(e) => return Some(Err(e)),
+        };
+
+        match self.record_event(&event)
+        {
+            // An alias begins a replay, emit its first event
+            // instead
+            Ok(true) => self.next_replayed(),
+            Ok(false) =>
+            {
+                self.advance(event.data());
+
+                Some(Ok(event))
+            },
+            Err(e) => Some(Err(e)),
+        }
+    }
+
+    /// Record the given event from the underlying stream,
+    /// returning true if it was an alias which should be
+    /// replayed
+    fn record_event(&mut self, event: &Event<'de>) -> Result<bool>
+    {
+        match event.data()
+        {
+            EventData::DocumentStart(_) | EventData::DocumentEnd(_) =>
+            {
+                self.log.clear();
+                self.anchors.clear();
+            },
+            EventData::Alias(alias) =>
+            {
+                self.nodes += 1;
+
+                let range = self
+                    .anchors
+                    .get(&alias.name)
+                    .copied()
+                    .ok_or_else(|| mkError!(ErrorCode::UndefinedAlias, CODE))
+                    .map_err(|e| e.with_mark(event.span().start))?;
+
+                if self.record > 0
+                {
+                    self.log.push(Entry::Alias(range));
+                }
+
+                self.span = event.span();
+                self.push_replay(range)?;
+
+                return Ok(true);
+            },
+            EventData::Scalar(node) =>
+            {
+                self.nodes += 1;
+
+                let start = self.log.len();
+
+                if self.record > 0 || node.anchor.is_some()
+                {
+                    self.log.push(Entry::Event(event.clone()));
+                }
+
+                if let Some(name) = &node.anchor
+                {
+                    self.anchors.insert(name.clone(), (start, start + 1));
+                }
+            },
+            EventData::MappingStart(node) => self.record_open(event, node.anchor.clone()),
+            EventData::SequenceStart(node) => self.record_open(event, node.anchor.clone()),
+            EventData::MappingEnd | EventData::SequenceEnd =>
+            {
+                if self.record > 0
+                {
+                    self.log.push(Entry::Event(event.clone()));
+                }
+
+                if let Some((Some(name), start)) = self.open.pop()
+                {
+                    self.record -= 1;
+                    self.anchors.insert(name, (start, self.log.len()));
+                }
+            },
+            _ =>
+            {},
+        }
+
+        Ok(false)
+    }
+
+    fn record_open(&mut self, event: &Event<'de>, anchor: Option<Slice<'de>>)
+    {
+        self.nodes += 1;
+
+        if anchor.is_some()
+        {
+            self.record += 1;
+        }
+
+        self.open.push((anchor, self.log.len()));
+
+        if self.record > 0
+        {
+            self.log.push(Entry::Event(event.clone()));
+        }
+    }
+
+    /// Fetch the next event of the current replay, if one
+    /// is in progress
+    fn next_replayed(&mut self) -> Option<Result<Event<'de>>>
+    {
+        while let Some((pos, end)) = self.replay.last_mut()
+        {
+            if pos >= end
+            {
+                self.replay.pop();
+                continue;
+            }
+
+            let entry = &self.log[*pos];
+            *pos += 1;
+
+            let mut event = match entry
+            {
+                Entry::Alias(range) =>
+                {
+                    let range = *range;
+
+                    if let Err(e) = self.push_replay(range)
+                    {
+                        return Some(Err(e));
+                    }
+
+                    continue;
+                },
+                Entry::Event(event) => Event::new(self.span, event.data().clone()),
+            };
+
+            let kind = self.kind();
+
+            match event.data_mut()
+            {
+                EventData::Scalar(node) =>
+                {
+                    node.anchor = None;
+                    node.kind = kind;
+                },
+                EventData::MappingStart(node) =>
+                {
+                    node.anchor = None;
+                    node.kind = kind;
+                },
+                EventData::SequenceStart(node) =>
+                {
+                    node.anchor = None;
+                    node.kind = kind;
+                },
+                _ =>
+                {},
+            }
+
+            if let Err(e) = self.count_expanded(event.data())
+            {
+                return Some(Err(e));
+            }
+
+            self.advance(event.data());
+
+            return Some(Ok(event));
+        }
+
+        None
+    }
+
+    fn push_replay(&mut self, range: (usize, usize)) -> Result<()>
+    {
+        if self.replay.len() >= self.limits.max_depth
+        {
+            return Err(mkError!(ErrorCode::AliasDepthLimit, CODE).with_mark(self.span.start));
+        }
+
+        self.replay.push(range);
+
+        Ok(())
+    }
+
+    fn count_expanded(&mut self, data: &EventData<'de>) -> Result<()>
+    {
+        if !is_node(data)
+        {
+            return Ok(());
+        }
+
+        self.expanded += 1;
+
+        if self.expanded > self.limits.max_nodes
+            || self.expanded > self.nodes.saturating_mul(self.limits.max_ratio)
+        {
+            return Err(mkError!(ErrorCode::ExpansionLimit, CODE).with_mark(self.span.start));
+        }
+
+        Ok(())
+    }
+
+    /// The kind of the next node emitted
+    fn kind(&self) -> NodeKind
+    {
+        match self.levels.last()
+        {
+            None => NodeKind::Root,
+            Some(Level::Sequence) => NodeKind::Entry,
+            Some(Level::Mapping { key: true }) => NodeKind::Key,
+            Some(Level::Mapping { key: false }) => NodeKind::Value,
+        }
+    }
+
+    /// Update the node position with an emitted event
+    fn advance(&mut self, data: &EventData<'de>)
+    {
+        if is_node(data) || matches!(data, EventData::Alias(_))
+        {
+            if let Some(Level::Mapping { key }) = self.levels.last_mut()
+            {
+                *key = !*key;
+            }
+        }
+
+        match data
+        {
+            EventData::SequenceStart(_) => self.levels.push(Level::Sequence),
+            EventData::MappingStart(_) => self.levels.push(Level::Mapping { key: true }),
+            EventData::SequenceEnd | EventData::MappingEnd =>
+            {
+                self.levels.pop();
+            },
+            _ =>
+            {},
+        }
+    }
+}
+
+impl<'de, I> Iterator for Expand<'de, I>
+where
+    I: Iterator<Item = Result<Event<'de>>>,
+{
+    type Item = Result<Event<'de>>;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        if self.done
+        {
+            return None;
+        }
+
+        let next = self.next_event();
+
+        if !matches!(next, Some(Ok(_)))
+        {
+            self.done = true;
+        }
+
+        next
+    }
+}
+
+impl<'de, I> std::iter::FusedIterator for Expand<'de, I> where I: Iterator<Item = Result<Event<'de>>>
+{}
+
+/// A recorded event, or an alias resolved to the .log
+/// range of its anchored node
+#[derive(Debug)]
+enum Entry<'de>
+{
+    Event(Event<'de>),
+    Alias((usize, usize)),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Level
+{
+    Sequence,
+    Mapping
+    {
+        key: bool,
+    },
+}
+
+fn is_node(data: &EventData<'_>) -> bool
+{
+    matches!(
+        data,
+        EventData::Scalar(_) | EventData::MappingStart(_) | EventData::SequenceStart(_)
+    )
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::{error::Category, event::from_reader, reader::borrow::BorrowReader};
+
+    /// Render the expanded events of the given YAML as a
+    /// flow style string
+    fn render(yaml: &str, limits: Limits) -> Result<String>
+    {
+        let reader = BorrowReader::new(yaml);
+        let mut out = Vec::new();
+
+        for event in expand_with(from_reader(&reader), limits)
+        {
+            match event?.data()
+            {
+                EventData::Scalar(node) =>
+                {
+                    let scalar = node.content.clone().evaluate()?;
+                    out.push(scalar.data().to_string());
+                },
+                EventData::SequenceStart(_) => out.push("[".into()),
+                EventData::SequenceEnd => out.push("]".into()),
+                EventData::MappingStart(_) => out.push("{".into()),
+                EventData::MappingEnd => out.push("}".into()),
+                EventData::Alias(_) => panic!("unexpanded alias"),
+                _ =>
+                {},
+            }
+        }
+
+        Ok(out.join(" "))
+    }
+
+    fn laughs(levels: usize) -> String
+    {
+        let mut yaml = String::from("l0: &l0 [lol, lol, lol, lol, lol, lol, lol, lol, lol]\n");
+
+        for i in 1..=levels
+        {
+            let aliases = vec![format!("*l{}", i - 1); 9].join(", ");
+
+            yaml.push_str(&format!("l{i}: &l{i} [{}]\n", aliases, i = i));
+        }
+
+        yaml
+    }
+
+    #[test]
+    fn expand_scalar()
+    {
+        let yaml = "[&a hello, *a, *a]";
+
+        assert_eq!(
+            render(yaml, Limits::default()).unwrap(),
+            "[ hello hello hello ]"
+        );
+    }
+
+    #[test]
+    fn expand_collections()
+    {
+        let yaml = "a: &a {k: &b [x, y]}\nb: *a\nc: *b\n";
+
+        assert_eq!(
+            render(yaml, Limits::default()).unwrap(),
+            "{ a { k [ x y ] } b { k [ x y ] } c [ x y ] }"
+        );
+    }
+
+    #[test]
+    fn expand_nested()
+    {
+        let yaml = "- &a x\n- &b [*a, *a]\n- &c [*b, *b]\n- *c\n";
+
+        assert_eq!(
+            render(yaml, Limits::default()).unwrap(),
+            "[ x [ x x ] [ [ x x ] [ x x ] ] [ [ x x ] [ x x ] ] ]"
+        );
+    }
+
+    #[test]
+    fn expand_redefined_anchor()
+    {
+        let yaml = "[&a one, *a, &a two, *a]";
+
+        assert_eq!(
+            render(yaml, Limits::default()).unwrap(),
+            "[ one one two two ]"
+        );
+    }
+
+    #[test]
+    fn expand_node_kind()
+    {
+        let yaml = "- &a value\n- {*a : *a}";
+        let reader = BorrowReader::new(yaml);
+
+        let kinds: Vec<_> = expand(from_reader(&reader))
+            .filter_map(|event| match event.unwrap().data()
+            {
+                EventData::Scalar(node) => Some(node.kind),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(kinds, [NodeKind::Entry, NodeKind::Key, NodeKind::Value]);
+    }
+
+    #[test]
+    fn expand_span()
+    {
+        let yaml = "[&a hello, *a]";
+        let reader = BorrowReader::new(yaml);
+
+        let events: Vec<_> = expand(from_reader(&reader))
+            .map(|event| event.unwrap())
+            .collect();
+
+        // The replayed scalar takes the alias' span
+        assert_eq!(events[4].start(), 11);
+        assert_eq!(events[4].end(), 13);
+    }
+
+    #[test]
+    fn expand_undefined_alias()
+    {
+        let yaml = "[*a, &a x]";
+        let err = render(yaml, Limits::default()).unwrap_err();
+
+        assert_eq!(err.classify(), Category::Data);
+        assert!(err.has_context());
+    }
+
+    #[test]
+    fn expand_incomplete_anchor()
+    {
+        let yaml = "&a [*a]";
+        let err = render(yaml, Limits::default()).unwrap_err();
+
+        assert_eq!(err.classify(), Category::Data);
+    }
+
+    #[test]
+    fn expand_anchors_document_scope()
+    {
+        let yaml = "--- &a x\n--- *a\n";
+        let err = render(yaml, Limits::default()).unwrap_err();
+
+        assert_eq!(err.classify(), Category::Data);
+    }
+
+    #[test]
+    fn limit_billion_laughs()
+    {
+        let err = render(&laughs(9), Limits::default()).unwrap_err();
+
+        assert_eq!(err.classify(), Category::Limit);
+        assert!(err.has_context());
+        assert_eq!(err.line(), 4);
+    }
+
+    #[test]
+    fn limit_nodes()
+    {
+        let yaml = laughs(2);
+        let limits = Limits::new(100, usize::MAX, usize::MAX);
+
+        assert!(render(&yaml, Limits::unlimited()).is_ok());
+        assert_eq!(
+            render(&yaml, limits).unwrap_err().classify(),
+            Category::Limit
+        );
+    }
+
+    #[test]
+    fn limit_ratio()
+    {
+        let yaml = laughs(2);
+        let limits = Limits::new(usize::MAX, 2, usize::MAX);
+
+        assert_eq!(
+            render(&yaml, limits).unwrap_err().classify(),
+            Category::Limit
+        );
+    }
+
+    #[test]
+    fn limit_depth()
+    {
+        let yaml = laughs(3);
+
+        assert!(render(&yaml, Limits::new(usize::MAX, usize::MAX, 3)).is_ok());
+        assert_eq!(
+            render(&yaml, Limits::new(usize::MAX, usize::MAX, 2))
+                .unwrap_err()
+                .classify(),
+            Category::Limit
+        );
+    }
+}
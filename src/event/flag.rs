@@ -51,6 +51,36 @@ pub const O_NIL: Flags = Flags::empty();
 /// [ScalarLike::Lazy]: super::types::ScalarLike::Lazy
 pub const O_LAZY: Flags = Flags::LAZY;
 
+/// Set to treat every document in the stream as YAML 1.1.
+///
+/// By default, documents are parsed following the YAML 1.2
+/// spec, unless they start with a `%YAML 1.1` directive.
+/// With this flag set, documents without a `%YAML`
+/// directive are also treated as YAML 1.1, and their
+/// [DocumentStart] event will report version 1.1.
+///
+/// This affects which escapes are allowed in double quoted
+/// scalars, and callers resolving scalar types should use
+/// the [Yaml11] schema for such documents.
+///
+/// [DocumentStart]: super::types::DocumentStart
+/// [Yaml11]: crate::schema::Yaml11
+pub const O_YAML11: Flags = Flags::YAML11;
+
+/// Set to produce [Comment] events for any comments in the
+/// stream.
+///
+/// By default comments are discarded. With this flag set,
+/// each comment produces a [Comment] event, interleaved
+/// with the other events in stream order. This allows
+/// tools which rewrite YAML to preserve its comments.
+///
+/// Note that comments in a block scalar's header line
+/// (`key: | # comment`) are not reported.
+///
+/// [Comment]: super::types::EventData::Comment
+pub const O_COMMENTS: Flags = Flags::COMMENTS;
+
 impl Flags
 {
     /// Instantiates a new, empty flag set
@@ -76,6 +106,30 @@ impl Flags
     {
         self.difference(O_LAZY)
     }
+
+    /// Applies [`O_YAML11`] to this flag set
+    pub const fn yaml11(self) -> Self
+    {
+        self.union(O_YAML11)
+    }
+
+    /// Removes [`O_YAML11`] from this flag set
+    pub const fn no_yaml11(self) -> Self
+    {
+        self.difference(O_YAML11)
+    }
+
+    /// Applies [`O_COMMENTS`] to this flag set
+    pub const fn comments(self) -> Self
+    {
+        self.union(O_COMMENTS)
+    }
+
+    /// Removes [`O_COMMENTS`] from this flag set
+    pub const fn no_comments(self) -> Self
+    {
+        self.difference(O_COMMENTS)
+    }
 }
 
 /// Convert from public flags to Scanner specific flags
@@ -90,6 +144,16 @@ pub(crate) const fn as_scanner(f: Flags) -> scanner::flag::Flags
         flags = flags.union(flag::O_LAZY);
     }
 
+    if f.contains(O_YAML11)
+    {
+        flags = flags.union(flag::O_YAML11);
+    }
+
+    if f.contains(O_COMMENTS)
+    {
+        flags = flags.union(flag::O_COMMENTS);
+    }
+
     flags
 }
 
@@ -101,5 +165,9 @@ bitflags! {
     pub struct Flags: u32 {
         /// See [`O_LAZY`]
         const LAZY          = 0b00000001;
+        /// See [`O_YAML11`]
+        const YAML11        = 0b00000010;
+        /// See [`O_COMMENTS`]
+        const COMMENTS      = 0b00000100;
     }
 }
@@ -0,0 +1,116 @@
+/*
+ * This Source Code Form is subject to the terms of the
+ * Mozilla Public License, v. 2.0. If a copy of the MPL
+ * was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+//! Resource limits applied while parsing a YAML stream.
+//!
+//! When the YAML stream is untrusted, these limits bound
+//! the resources any single stream may consume. A stream
+//! that exceeds a limit fails as soon as the limit is
+//! crossed, with an error of [`Category::Limit`] carrying
+//! the position at which it occurred.
+//!
+//! ```
+//! use yary::{
+//!     error::Category,
+//!     event::{flag::O_NIL, from_reader_limited, limit::Limits},
+//!     reader::borrow::BorrowReader,
+//! };
+//!
+//! let yaml = BorrowReader::new("[[[[deeply nested]]]]");
+//! let limits = Limits {
+//!     max_depth: 2,
+//!     ..Limits::new()
+//! };
+//!
+//! let err = from_reader_limited(&yaml, O_NIL, limits)
+//!     .into_iter()
+//!     .find_map(Result::err)
+//!     .unwrap();
+//!
+//! assert_eq!(err.classify(), Category::Limit);
+//! assert_eq!((err.line(), err.column()), (1, 3));
+//! ```
+//!
+//! [`Category::Limit`]: enum@crate::error::Category
+
+/// Limits on the resources consumed while parsing a YAML
+/// stream.
+///
+/// Limits only apply when explicitly passed to a stream,
+/// e.g via [`from_reader_limited`], streams created without
+/// them are unlimited. The default limits bound the size of
+/// individual nodes, while leaving the length of the stream
+/// unbounded. Use struct update syntax to adjust individual
+/// limits:
+///
+/// ```
+/// # use yary::event::limit::Limits;
+/// let limits = Limits {
+///     max_documents: 1,
+///     ..Limits::new()
+/// };
+/// ```
+///
+/// [`from_reader_limited`]: super::from_reader_limited
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits
+{
+    /// Maximum nesting depth of block and flow collections
+    pub max_depth:      usize,
+    /// Maximum length of a scalar, in bytes of the YAML
+    /// stream it occupies
+    pub max_scalar_len: usize,
+    /// Maximum length of an anchor or alias name, in bytes
+    pub max_anchor_len: usize,
+    /// Maximum length of a tag or %TAG directive, in bytes
+    /// of its handle and suffix (or prefix)
+    pub max_tag_len:    usize,
+    /// Maximum number of documents in the YAML stream
+    pub max_documents:  usize,
+    /// Maximum number of events produced from the YAML
+    /// stream
+    pub max_events:     usize,
+}
+
+impl Limits
+{
+    /// Instantiates the default set of limits
+    pub const fn new() -> Self
+    {
+        Self {
+            max_depth:      1024,
+            max_scalar_len: 64 * 1024 * 1024,
+            max_anchor_len: 1024,
+            max_tag_len:    4096,
+            max_documents:  usize::MAX,
+            max_events:     usize::MAX,
+        }
+    }
+
+    /// Instantiates a set of limits which will never be
+    /// exceeded.
+    ///
+    /// This should only be used with trusted YAML streams.
+    pub const fn unlimited() -> Self
+    {
+        Self {
+            max_depth:      usize::MAX,
+            max_scalar_len: usize::MAX,
+            max_anchor_len: usize::MAX,
+            max_tag_len:    usize::MAX,
+            max_documents:  usize::MAX,
+            max_events:     usize::MAX,
+        }
+    }
+}
+
+impl Default for Limits
+{
+    fn default() -> Self
+    {
+        Self::new()
+    }
+}
@@ -0,0 +1,876 @@
+/*
+ * This Source Code Form is subject to the terms of the
+ * Mozilla Public License, v. 2.0. If a copy of the MPL
+ * was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+//! This module exposes [`Merge`], an [`Event`] adapter
+//! which applies YAML merge keys (`<<`).
+//!
+//! A merge key's value may be an alias to a mapping, an
+//! inline mapping, or a sequence of either. The entries of
+//! each merged mapping are spliced into the mapping holding
+//! the merge key, in place of the merge key's entry, except
+//! for any key that the mapping already has; explicit keys
+//! always override merged ones. When merging a sequence of
+//! mappings, earlier mappings override later ones.
+//!
+//! A merge key is an untagged plain `<<` scalar, or any
+//! scalar tagged `!!merge`. Keys are compared by their
+//! content, complex keys are compared structurally.
+//!
+//! Merging a mapping copies its entries, which carry the
+//! [`Span`]s of the originals and have their anchors
+//! removed. Like alias expansion, this can be abused to
+//! produce an exponential number of nodes, so [`Merge`]
+//! enforces the [`Limits`] it was created with, returning
+//! an error of [`Category::Limit`] once any limit is
+//! exceeded.
+//!
+//! Every mapping must be complete before its merge keys
+//! can be applied, so [`Merge`] buffers the events of each
+//! document's root collection before emitting them. Any
+//! aliases that are not merged are left as is, use
+//! [`expand`] to replace them.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use yary::{
+//!     event::{from_reader, merge::merge},
+//!     reader::borrow::BorrowReader,
+//!     tree,
+//! };
+//!
+//! let yaml = BorrowReader::new(
+//!     "
+//! defaults: &defaults
+//!   image: alpine
+//!   retries: 3
+//! job:
+//!   <<: *defaults
+//!   retries: 5
+//! ",
+//! );
+//!
+//! let docs = tree::from_events(merge(from_reader(&yaml)))?;
+//! let job = &docs[0]["job"];
+//!
+//! assert_eq!(job["image"].as_str(), Some("alpine"));
+//! assert_eq!(job["retries"].as_str(), Some("5"));
+//! assert!(job.get("<<").is_none());
+//! # Ok::<(), yary::Error>(())
+//! ```
+//!
+//! [`Span`]: super::types::Span
+//! [`expand`]: mod@super::expand
+//! [`Category::Limit`]: enum@crate::error::Category
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::{
+    error::{internal::ErrorCode, mkError, Result},
+    event::types::{Event, EventData, ResolvedTag, ScalarStyle, Slice},
+};
+
+/// Wraps the given events, applying any merge keys using
+/// the default [`Limits`].
+///
+/// See [`merge_with`] for more information.
+pub fn merge<'de, I>(events: I) -> Merge<'de, I::IntoIter>
+where
+    I: IntoIterator<Item = Result<Event<'de>>>,
+{
+    merge_with(events, Limits::default())
+}
+
+/// Wraps the given events, applying any merge keys subject
+/// to the given [`Limits`].
+///
+/// Merging stops at the first error, either from the
+/// underlying events, an invalid merge or from exceeding a
+/// limit, after which the returned iterator is fused.
+pub fn merge_with<'de, I>(events: I, limits: Limits) -> Merge<'de, I::IntoIter>
+where
+    I: IntoIterator<Item = Result<Event<'de>>>,
+{
+    Merge::new(events.into_iter(), limits)
+}
+
+/// Limits applied while applying merge keys.
+///
+/// Only nodes copied by merging count towards these
+/// limits, nodes present in the YAML stream are
+/// unrestricted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits
+{
+    /// Maximum number of nodes that may be copied by
+    /// merging, across the entire stream
+    pub max_nodes: usize,
+    /// Maximum number of nodes that may be copied by
+    /// merging, for each node (or alias) present in the
+    /// stream
+    pub max_ratio: usize,
+    /// Maximum number of merges that may be nested inside
+    /// the mappings merged by other merges
+    pub max_depth: usize,
+}
+
+impl Limits
+{
+    /// Instantiates a new set of limits
+    pub const fn new(max_nodes: usize, max_ratio: usize, max_depth: usize) -> Self
+    {
+        Self {
+            max_nodes,
+            max_ratio,
+            max_depth,
+        }
+    }
+
+    /// Instantiates a set of limits which will never be
+    /// exceeded.
+    ///
+    /// This should only be used with trusted YAML streams.
+    pub const fn unlimited() -> Self
+    {
+        Self::new(usize::MAX, usize::MAX, usize::MAX)
+    }
+}
+
+impl Default for Limits
+{
+    fn default() -> Self
+    {
+        Self::new(1_000_000, 100, 64)
+    }
+}
+
+/// An [`Event`] adapter which applies merge keys, replacing
+/// them with the entries of the mappings they refer to.
+#[derive(Debug)]
+pub struct Merge<'de, I>
+{
+    events: I,
+    limits: Limits,
+
+    /// Events of the document root being buffered
+    buffer:  Vec<Event<'de>>,
+    /// Position in .buffer of each collection's end, by
+    /// the position of its start
+    ends:    HashMap<usize, usize>,
+    /// Map of anchor names to their node's position in
+    /// .buffer
+    anchors: HashMap<Slice<'de>, usize>,
+    /// Position in .buffer of each alias' node, resolved
+    /// when the alias was seen
+    aliases: HashMap<usize, usize>,
+    /// Stack of open collections, and the anchor and
+    /// .buffer position of each
+    open:    Vec<(Option<Slice<'de>>, usize)>,
+
+    /// Events ready to be emitted
+    queue: VecDeque<Event<'de>>,
+
+    /// Nodes (and aliases) seen in the underlying stream
+    nodes:  usize,
+    /// Nodes copied by merging
+    copied: usize,
+    done:   bool,
+}
+
+impl<'de, I> Merge<'de, I>
+where
+    I: Iterator<Item = Result<Event<'de>>>,
+{
+    /// Instantiate a new [`Merge`] adapter over the given
+    /// events
+    pub fn new(events: I, limits: Limits) -> Self
+    {
+        Self {
+            events,
+            limits,
+            buffer: Vec::new(),
+            ends: HashMap::new(),
+            anchors: HashMap::new(),
+            aliases: HashMap::new(),
+            open: Vec::new(),
+            queue: VecDeque::new(),
+            nodes: 0,
+            copied: 0,
+            done: false,
+        }
+    }
+
+    /// Consume the adapter, returning the underlying
+    /// events
+    pub fn into_inner(self) -> I
+    {
+        self.events
+    }
+
+    fn next_event(&mut self) -> Option<Result<Event<'de>>>
+    {
+        loop
+        {
+            if let Some(event) = self.queue.pop_front()
+            {
+                return Some(Ok(event));
+            }
+
+            let event = match self.events.next()?
+            {
+                Ok(event) => event,
+                Err(e) => return Some(Err(e)),
+            };
+
+            match self.buffer_event(event)
+            {
+                Ok(Some(event)) => return Some(Ok(event)),
+                Ok(None) => continue,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+
+    /// Buffer the given event if it belongs to a document's
+    /// root collection, returning it otherwise
+    fn buffer_event(&mut self, event: Event<'de>) -> Result<Option<Event<'de>>>
+    {
+        let pos = self.buffer.len();
+
+        match event.data()
+        {
+            EventData::Scalar(node) =>
+            {
+                self.nodes += 1;
+
+                if let Some(name) = &node.anchor
+                {
+                    self.anchors.insert(name.clone(), pos);
+                }
+            },
+            EventData::Alias(alias) =>
+            {
+                self.nodes += 1;
+
+                if let Some(&node) = self.anchors.get(&alias.name)
+                {
+                    self.aliases.insert(pos, node);
+                }
+            },
+            EventData::MappingStart(node) =>
+            {
+                self.nodes += 1;
+                self.open.push((node.anchor.clone(), pos));
+            },
+            EventData::SequenceStart(node) =>
+            {
+                self.nodes += 1;
+                self.open.push((node.anchor.clone(), pos));
+            },
+            EventData::DocumentStart(_) | EventData::DocumentEnd(_) => self.anchors.clear(),
+            EventData::MappingEnd | EventData::SequenceEnd =>
+            {
+                if let Some((anchor, start)) = self.open.pop()
+                {
+                    self.ends.insert(start, pos);
+
+                    if let Some(name) = anchor
+                    {
+                        self.anchors.insert(name, start);
+                    }
+                }
+            },
+            _ =>
+            {},
+        }
+
+        if self.buffer.is_empty() && self.open.is_empty()
+        {
+            return Ok(Some(event));
+        }
+
+        self.buffer.push(event);
+
+        if self.open.is_empty()
+        {
+            self.flush()?;
+        }
+
+        Ok(None)
+    }
+
+    /// Apply the merge keys of the buffered root
+    /// collection, queuing the resulting events
+    fn flush(&mut self) -> Result<()>
+    {
+        let mut out = VecDeque::with_capacity(self.buffer.len());
+
+        self.emit(0, false, &mut out)?;
+
+        self.queue = out;
+        self.buffer.clear();
+        self.ends.clear();
+        self.anchors.clear();
+        self.aliases.clear();
+
+        Ok(())
+    }
+
+    /// Emit the node starting at .pos, with its merge keys
+    /// applied
+    fn emit(&mut self, pos: usize, copied: bool, out: &mut VecDeque<Event<'de>>) -> Result<()>
+    {
+        match self.buffer[pos].data()
+        {
+            EventData::MappingStart(_) =>
+            {
+                let entries = self.entries(pos, 0)?;
+
+                self.push(pos, copied, out)?;
+
+                for item in entries
+                {
+                    match item
+                    {
+                        Item::Comment(comment) => self.push(comment, copied, out)?,
+                        Item::Entry { key, value, merged } =>
+                        {
+                            self.emit(key, copied || merged, out)?;
+                            self.emit(value, copied || merged, out)?;
+                        },
+                    }
+                }
+
+                self.push(self.ends[&pos], copied, out)?;
+            },
+            EventData::SequenceStart(_) =>
+            {
+                let end = self.ends[&pos];
+                let mut child = pos + 1;
+
+                self.push(pos, copied, out)?;
+
+                while child < end
+                {
+                    self.emit(child, copied, out)?;
+                    child = self.next_sibling(child);
+                }
+
+                self.push(end, copied, out)?;
+            },
+            _ => self.push(pos, copied, out)?,
+        }
+
+        Ok(())
+    }
+
+    /// Queue the event at .pos, removing its anchor if it
+    /// was .copied
+    fn push(&mut self, pos: usize, copied: bool, out: &mut VecDeque<Event<'de>>) -> Result<()>
+    {
+        let mut event = self.buffer[pos].clone();
+
+        if copied
+        {
+            match event.data_mut()
+            {
+                EventData::Scalar(node) => node.anchor = None,
+                EventData::MappingStart(node) => node.anchor = None,
+                EventData::SequenceStart(node) => node.anchor = None,
+                _ =>
+                {},
+            }
+
+            if is_node(event.data())
+            {
+                self.copied += 1;
+
+                if self.copied > self.limits.max_nodes
+                    || self.copied > self.nodes.saturating_mul(self.limits.max_ratio)
+                {
+                    return Err(
+                        mkError!(ErrorCode::ExpansionLimit, CODE).with_mark(event.span().start)
+                    );
+                }
+            }
+        }
+
+        out.push_back(event);
+
+        Ok(())
+    }
+
+    /// Collect the entries of the mapping starting at .pos,
+    /// with its merge keys applied
+    fn entries(&mut self, pos: usize, depth: usize) -> Result<Vec<Item>>
+    {
+        if depth > self.limits.max_depth
+        {
+            let mark = self.buffer[pos].span().start;
+
+            return Err(mkError!(ErrorCode::AliasDepthLimit, CODE).with_mark(mark));
+        }
+
+        let end = self.ends[&pos];
+        let mut raw = Vec::new();
+        let mut key = None;
+        let mut child = pos + 1;
+
+        while child < end
+        {
+            if let EventData::Comment(_) = self.buffer[child].data()
+            {
+                raw.push(Item::Comment(child));
+                child += 1;
+                continue;
+            }
+
+            match key.take()
+            {
+                None => key = Some(child),
+                Some(key) => raw.push(Item::Entry {
+                    key,
+                    value: child,
+                    merged: false,
+                }),
+            }
+
+            child = self.next_sibling(child);
+        }
+
+        // Explicit keys override any merged key, regardless of
+        // their position in the mapping
+        let mut seen = HashSet::new();
+
+        for item in raw.iter()
+        {
+            if let Item::Entry { key, .. } = *item
+            {
+                if !self.is_merge_key(key)?
+                {
+                    seen.insert(self.signature(key)?);
+                }
+            }
+        }
+
+        let mut items = Vec::with_capacity(raw.len());
+
+        for item in raw
+        {
+            match item
+            {
+                Item::Entry { key, value, .. } if self.is_merge_key(key)? =>
+                {
+                    for (source, copied) in self.sources(value)?
+                    {
+                        for merged in self.entries(source, depth + 1)?
+                        {
+                            if let Item::Entry { key, value, merged } = merged
+                            {
+                                if seen.insert(self.signature(key)?)
+                                {
+                                    items.push(Item::Entry {
+                                        key,
+                                        value,
+                                        merged: copied || merged,
+                                    });
+                                }
+                            }
+                        }
+                    }
+                },
+                item => items.push(item),
+            }
+        }
+
+        Ok(items)
+    }
+
+    /// Find the mappings to merge from a merge key's
+    /// .value, in order of precedence, and whether each
+    /// must be copied
+    fn sources(&self, value: usize) -> Result<Vec<(usize, bool)>>
+    {
+        let mut sources = Vec::new();
+
+        match self.buffer[value].data()
+        {
+            EventData::SequenceStart(_) =>
+            {
+                let end = self.ends[&value];
+                let mut child = value + 1;
+
+                while child < end
+                {
+                    if !matches!(self.buffer[child].data(), EventData::Comment(_))
+                    {
+                        sources.push(self.source(child)?);
+                    }
+
+                    child = self.next_sibling(child);
+                }
+            },
+            _ => sources.push(self.source(value)?),
+        }
+
+        Ok(sources)
+    }
+
+    /// Resolve a single merge source at .pos to a mapping
+    fn source(&self, pos: usize) -> Result<(usize, bool)>
+    {
+        let mark = self.buffer[pos].span().start;
+
+        let (node, copied) = match self.buffer[pos].data()
+        {
+            EventData::Alias(_) => match self.aliases.get(&pos)
+            {
+                Some(&node) => (node, true),
+                None => return Err(mkError!(ErrorCode::UndefinedAlias, CODE).with_mark(mark)),
+            },
+            _ => (pos, false),
+        };
+
+        match self.buffer[node].data()
+        {
+            EventData::MappingStart(_) => Ok((node, copied)),
+            _ => Err(mkError!(ErrorCode::InvalidMerge, CODE).with_mark(mark)),
+        }
+    }
+
+    /// Check if the key at .pos is a merge key
+    fn is_merge_key(&mut self, pos: usize) -> Result<bool>
+    {
+        let mark = self.buffer[pos].span().start;
+
+        let node = match self.buffer[pos].data_mut()
+        {
+            EventData::Scalar(node) => node,
+            _ => return Ok(false),
+        };
+
+        match node.resolved
+        {
+            ResolvedTag::Specific(ref uri) => return Ok(uri == MERGE_TAG),
+            ResolvedTag::Exclamation => return Ok(false),
+            ResolvedTag::Question =>
+            {},
+        }
+
+        let scalar = node
+            .content
+            .evaluate_by_ref()
+            .map_err(|e| e.with_mark(mark))?;
+
+        Ok(scalar.style() == ScalarStyle::Plain && scalar.data() == "<<")
+    }
+
+    /// Produce a string which uniquely identifies the
+    /// structure and content of the key at .pos
+    fn signature(&mut self, pos: usize) -> Result<String>
+    {
+        let mut signature = String::new();
+        let end = match self.ends.get(&pos)
+        {
+            Some(&end) => end,
+            None => pos,
+        };
+
+        for event in self.buffer[pos..=end].iter_mut()
+        {
+            let mark = event.span().start;
+
+            match event.data_mut()
+            {
+                EventData::Scalar(node) =>
+                {
+                    let scalar = node
+                        .content
+                        .evaluate_by_ref()
+                        .map_err(|e| e.with_mark(mark))?;
+
+                    signature.push_str(&format!("{:?} ", scalar.data()));
+                },
+                EventData::Alias(alias) => signature.push_str(&format!("*{} ", alias.name)),
+                EventData::MappingStart(_) => signature.push_str("{ "),
+                EventData::MappingEnd => signature.push_str("} "),
+                EventData::SequenceStart(_) => signature.push_str("[ "),
+                EventData::SequenceEnd => signature.push_str("] "),
+                _ =>
+                {},
+            }
+        }
+
+        Ok(signature)
+    }
+
+    /// Position of the node (or comment) following the one
+    /// at .pos
+    fn next_sibling(&self, pos: usize) -> usize
+    {
+        match self.ends.get(&pos)
+        {
+            Some(end) => end + 1,
+            None => pos + 1,
+        }
+    }
+}
+
+impl<'de, I> Iterator for Merge<'de, I>
+where
+    I: Iterator<Item = Result<Event<'de>>>,
+{
+    type Item = Result<Event<'de>>;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        if self.done
+        {
+            return None;
+        }
+
+        let next = self.next_event();
+
+        if !matches!(next, Some(Ok(_)))
+        {
+            self.done = true;
+        }
+
+        next
+    }
+}
+
+impl<'de, I> std::iter::FusedIterator for Merge<'de, I> where I: Iterator<Item = Result<Event<'de>>> {}
+
+/// The resolved tag of an explicit merge key
+const MERGE_TAG: &str = "tag:yaml.org,2002:merge";
+
+/// A mapping's child, as positions in the buffer
+#[derive(Debug, Clone, Copy)]
+enum Item
+{
+    Comment(usize),
+    Entry
+    {
+        key:    usize,
+        value:  usize,
+        /// Was this entry merged from a mapping that is
+        /// emitted elsewhere
+        merged: bool,
+    },
+}
+
+fn is_node(data: &EventData<'_>) -> bool
+{
+    matches!(
+        data,
+        EventData::Scalar(_) | EventData::MappingStart(_) | EventData::SequenceStart(_)
+    )
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::{
+        error::{Category, ErrorKind},
+        event::from_reader,
+        reader::borrow::BorrowReader,
+        tree,
+    };
+
+    /// Render the merged events of the given YAML as a flow
+    /// style string
+    fn render(yaml: &str, limits: Limits) -> Result<String>
+    {
+        let reader = BorrowReader::new(yaml);
+        let mut out = Vec::new();
+
+        for event in merge_with(from_reader(&reader), limits)
+        {
+            match event?.data()
+            {
+                EventData::Scalar(node) =>
+                {
+                    let scalar = node.content.clone().evaluate()?;
+                    out.push(scalar.data().to_string());
+                },
+                EventData::Alias(alias) => out.push(format!("*{}", alias.name)),
+                EventData::SequenceStart(_) => out.push("[".into()),
+                EventData::SequenceEnd => out.push("]".into()),
+                EventData::MappingStart(_) => out.push("{".into()),
+                EventData::MappingEnd => out.push("}".into()),
+                _ =>
+                {},
+            }
+        }
+
+        Ok(out.join(" "))
+    }
+
+    fn merged(yaml: &str) -> String
+    {
+        render(yaml, Limits::default()).unwrap()
+    }
+
+    #[test]
+    fn merge_alias()
+    {
+        let yaml = "a: &a {x: 1, y: 2}\nb: {<<: *a, z: 3}\n";
+
+        assert_eq!(merged(yaml), "{ a { x 1 y 2 } b { x 1 y 2 z 3 } }");
+    }
+
+    #[test]
+    fn merge_explicit_override()
+    {
+        let yaml = "- &a {x: 1, y: 2}\n- {x: 0, <<: *a}\n- {<<: *a, y: 0}\n";
+
+        assert_eq!(merged(yaml), "[ { x 1 y 2 } { x 0 y 2 } { x 1 y 0 } ]");
+    }
+
+    #[test]
+    fn merge_sequence()
+    {
+        let yaml = "- &a {x: a}\n- &b {x: b, y: b}\n- {<<: [*a, *b, {z: c}]}\n";
+
+        assert_eq!(merged(yaml), "[ { x a } { x b y b } { x a y b z c } ]");
+    }
+
+    #[test]
+    fn merge_inline_mapping()
+    {
+        let yaml = "<<: {a: &x 1}\nb: *x\n";
+
+        assert_eq!(merged(yaml), "{ a 1 b *x }");
+    }
+
+    #[test]
+    fn merge_nested()
+    {
+        let yaml = "- &a {x: 1}\n- &b {<<: *a, y: 2}\n- {<<: *b, z: {<<: *a}}\n";
+
+        assert_eq!(
+            merged(yaml),
+            "[ { x 1 } { x 1 y 2 } { x 1 y 2 z { x 1 } } ]"
+        );
+    }
+
+    #[test]
+    fn merge_not_a_merge_key()
+    {
+        let yaml = "- &a {x: 1}\n- {'<<': *a, ! <<: *a}\n- {!!merge <<: *a}\n";
+
+        assert_eq!(merged(yaml), "[ { x 1 } { << *a << *a } { x 1 } ]");
+    }
+
+    #[test]
+    fn merge_complex_keys()
+    {
+        let yaml = "- &a {? [k]\n  : merged, ? [j]\n  : merged}\n- <<: *a\n  ? [k]\n  : explicit\n";
+
+        assert_eq!(
+            merged(yaml),
+            "[ { [ k ] merged [ j ] merged } { [ j ] merged [ k ] explicit } ]"
+        );
+    }
+
+    #[test]
+    fn merge_anchors_removed()
+    {
+        let yaml = "- &a {k: &b v}\n- {<<: *a}\n";
+        let reader = BorrowReader::new(yaml);
+
+        let anchors = merge(from_reader(&reader))
+            .filter_map(|event| match event.unwrap().data()
+            {
+                EventData::Scalar(node) => Some(node.anchor.is_some()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(anchors, [false, true, false, false]);
+    }
+
+    #[test]
+    fn merge_tree()
+    {
+        let yaml = "base: &base {image: alpine, retries: 3}\njob:\n  <<: *base\n  retries: 5\n";
+        let reader = BorrowReader::new(yaml);
+
+        let docs = tree::from_events(merge(from_reader(&reader))).unwrap();
+        let job = &docs[0]["job"];
+
+        assert_eq!(job["image"].as_str(), Some("alpine"));
+        assert_eq!(job["retries"].as_str(), Some("5"));
+        assert_eq!(job.as_mapping().map(tree::Mapping::len), Some(2));
+    }
+
+    #[test]
+    fn merge_invalid()
+    {
+        for yaml in &[
+            "a: &a [1]\nb: {<<: *a}\n",
+            "<<: scalar\n",
+            "<<: [{a: 1}, b]\n",
+        ]
+        {
+            let err = render(yaml, Limits::default()).unwrap_err();
+
+            assert_eq!(err.kind(), ErrorKind::InvalidMerge, "{}", yaml);
+            assert!(err.has_context());
+        }
+
+        let err = render("<<: *a\n", Limits::default()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UndefinedAlias);
+    }
+
+    #[test]
+    fn merge_documents()
+    {
+        let yaml = "--- &a {x: 1}\n--- scalar\n--- {<<: *a}\n";
+
+        assert_eq!(
+            render(yaml, Limits::default()).unwrap_err().kind(),
+            ErrorKind::UndefinedAlias
+        );
+        assert_eq!(merged("--- {a: 1}\n--- b\n--- [c]\n"), "{ a 1 } b [ c ]");
+    }
+
+    #[test]
+    fn limit_nodes()
+    {
+        let yaml = "- &a {a: 1, b: 2, c: 3}\n- {<<: *a}\n- {<<: *a}\n";
+
+        assert!(render(yaml, Limits::new(12, usize::MAX, usize::MAX)).is_ok());
+        assert_eq!(
+            render(yaml, Limits::new(11, usize::MAX, usize::MAX))
+                .unwrap_err()
+                .classify(),
+            Category::Limit
+        );
+        assert_eq!(
+            render(yaml, Limits::new(usize::MAX, 0, usize::MAX))
+                .unwrap_err()
+                .classify(),
+            Category::Limit
+        );
+    }
+
+    #[test]
+    fn limit_depth()
+    {
+        let yaml = "- &a {x: 1}\n- &b {<<: *a}\n- &c {<<: *b}\n- {<<: *c}\n";
+
+        assert!(render(yaml, Limits::new(usize::MAX, usize::MAX, 3)).is_ok());
+        assert_eq!(
+            render(yaml, Limits::new(usize::MAX, usize::MAX, 2))
+                .unwrap_err()
+                .classify(),
+            Category::Limit
+        );
+    }
+}
@@ -71,14 +71,17 @@
 //! [`Read`]:           trait@crate::reader::Read
 
 use crate::{
-    error::Result,
+    error::{internal::ErrorCode, mkError, Error, Result},
     event::{
-        error::ParseResult,
+        error::ParseError,
         flag::{Flags, O_NIL},
+        limit::Limits,
         parser::Parser,
-        types::Event,
+        types::{Comment, Event, EventData, Span},
     },
     reader::{PeekReader, Reader},
+    scanner::{entry::TokenEntry, flag::O_LAZY},
+    token::Token,
 };
 
 mod parser;
@@ -86,11 +89,21 @@ mod state;
 
 pub(crate) mod error;
 
+pub mod document;
+pub mod expand;
 pub mod flag;
+pub mod limit;
+pub mod merge;
+pub mod owned;
+pub mod path;
+pub mod query;
+pub mod recover;
 pub mod types;
+pub mod unique;
 
 /// Instantiates a new [`Events`] stream from the given
-/// read source, with the default flag set.
+/// read source, with the default flag set and no resource
+/// limits.
 ///
 /// See [`from_reader_with`] for more information.
 ///
@@ -136,7 +149,7 @@ where
 }
 
 /// Instantiates a new [`Events`] stream from the given
-/// read source and flags.
+/// read source and flags, with no resource limits.
 ///
 /// The [`Event`]s produced by this stream will have their
 /// lifetime bound to the source reference, and will remain
@@ -188,6 +201,41 @@ where
     Events::new(src, f)
 }
 
+/// Instantiates a new [`Events`] stream from the given
+/// read source and flags, which errors once any of the
+/// given resource .limits are exceeded.
+///
+/// See [`from_reader_with`] for more information.
+///
+/// ## Examples
+///
+/// ```rust
+/// use yary::{
+///     error::Category,
+///     event::{flag::O_NIL, from_reader_limited, limit::Limits},
+///     reader::borrow::BorrowReader,
+/// };
+///
+/// let yaml = BorrowReader::new("[a, b, c]");
+/// let limits = Limits {
+///     max_events: 4,
+///     ..Limits::new()
+/// };
+///
+/// let err = from_reader_limited(&yaml, O_NIL, limits)
+///     .into_iter()
+///     .find_map(Result::err)
+///     .unwrap();
+///
+/// assert_eq!(err.classify(), Category::Limit);
+/// ```
+pub fn from_reader_limited<R>(src: &R, f: Flags, limits: Limits) -> Events<'_, R>
+where
+    R: crate::reader::Read,
+{
+    Events::with_limits(src, f, limits)
+}
+
 /// Control structure for [`Event`] production, providing an
 /// iterator based API for consuming events.
 ///
@@ -205,6 +253,13 @@ pub struct Events<'de, R>
 {
     reader: PeekReader<'de, R>,
     parser: Parser,
+    /// An event held back while preceding comments are
+    /// returned
+    held:   Option<Event<'de>>,
+
+    limits:    Limits,
+    events:    usize,
+    documents: usize,
 }
 
 impl<'de, R> Events<'de, R>
@@ -212,7 +267,9 @@ where
     R: crate::reader::Read,
 {
     /// Instantiate a new [`Events`] from the given read
-    /// source and flags.
+    /// source and flags, without any resource limits. Use
+    /// [`Events::with_limits`] when the source is
+    /// untrusted.
     ///
     /// **Note:** After you have generated >=1 [`Event`]s
     /// from the returned [`Events`]' iterator, this
@@ -232,11 +289,47 @@ where
     /// ```
     pub fn new(src: &'de R, flags: Flags) -> Self
     {
-        let inner = Reader::new(src, flag::as_scanner(flags));
+        Self::with_limits(src, flags, Limits::unlimited())
+    }
+
+    /// Instantiate a new [`Events`] from the given read
+    /// source, flags and limits.
+    ///
+    /// Once any of the given [`Limits`] are exceeded, the
+    /// [`Events`] will return an error.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// # use yary::reader::borrow::BorrowReader;
+    /// # use yary::event::{Events, flag::O_NIL, limit::Limits};
+    ///
+    /// let yaml = BorrowReader::new("[1, 2, 3, 4, 5]");
+    /// let limits = Limits {
+    ///     max_events: 16,
+    ///     ..Limits::new()
+    /// };
+    ///
+    /// let events = Events::with_limits(&yaml, O_NIL, limits);
+    /// ```
+    pub fn with_limits(src: &'de R, flags: Flags, limits: Limits) -> Self
+    {
+        let inner = Reader::with_limits(src, flag::as_scanner(flags), limits);
         let reader = PeekReader::new(inner);
-        let parser = Parser::new();
+        let parser = match flags.contains(flag::O_YAML11)
+        {
+            true => Parser::with_version(types::YAML11_VERSION),
+            false => Parser::new(),
+        };
 
-        Self { reader, parser }
+        Self {
+            reader,
+            parser,
+            held: None,
+            limits,
+            events: 0,
+            documents: 0,
+        }
     }
 
     /// Return an iterator which borrows from the underlying
@@ -270,6 +363,212 @@ where
     {
         EventIterRef::new(self)
     }
+
+    /// Skip the next node in the stream, along with every
+    /// event nested inside of it, returning the [`Span`]
+    /// the node covered.
+    ///
+    /// If the next event does not begin a node, for example
+    /// the end of the enclosing collection, it is left in
+    /// place and `None` is returned. Scalars scanned while
+    /// skipping are never evaluated, as if [`O_LAZY`] was
+    /// set, and any comments up to the end of the node are
+    /// discarded.
+    ///
+    /// [`O_LAZY`]: flag::O_LAZY
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// # use yary::reader::borrow::BorrowReader;
+    /// # use yary::event::{Events, flag::O_NIL, types::*};
+    ///
+    /// let yaml = BorrowReader::new("{skip: [1, {2: 3}], keep: 4}");
+    /// let mut events = Events::new(&yaml, O_NIL);
+    ///
+    /// // StreamStart, DocumentStart, MappingStart, 'skip'
+    /// events.iter().take(4).for_each(drop);
+    ///
+    /// let span = events.skip_node().unwrap().expect("a node");
+    /// assert_eq!((span.start.offset, span.end.offset), (7, 18));
+    ///
+    /// // 'keep'
+    /// let event = events.iter().next().unwrap().unwrap();
+    /// assert_eq!(event.span().start.offset, 20);
+    /// ```
+    pub fn skip_node(&mut self) -> Result<Option<Span>>
+    {
+        let options = self.reader.options();
+
+        self.reader.set_options(options | O_LAZY);
+        let skipped = self.skip_events();
+        self.reader.set_options(options);
+
+        skipped
+    }
+
+    /// The lowest stream offset that may still be
+    /// referenced by any unprocessed token
+    pub(crate) fn bound(&self) -> usize
+    {
+        self.held
+            .iter()
+            .map(Event::start)
+            .fold(self.reader.bound(), usize::min)
+    }
+
+    /// Process the next event, enforcing our limits
+    fn next_event(&mut self) -> Result<Option<Event<'de>>>
+    {
+        let event = match self.held.take()
+        {
+            Some(event) => Some(event),
+            None => self.parse_event()?,
+        };
+
+        // Any comments found while parsing the event which
+        // precede it are returned first
+        let event = match self.reader.pop_comment(event.as_ref().map(Event::start))
+        {
+            Some(comment) =>
+            {
+                self.held = event;
+
+                comment_event(comment)?
+            },
+            None => match event
+            {
+                Some(event) => event,
+                None => return Ok(None),
+            },
+        };
+
+        self.count(&event)?;
+
+        // Scalars scanned while skipping a node are deferred,
+        // and must be evaluated unless the caller asked for
+        // lazy scalars
+        let mut event = event;
+        if !self.reader.options().contains(O_LAZY)
+        {
+            let start = event.span().start;
+
+            if let EventData::Scalar(node) = event.data_mut()
+            {
+                node.content
+                    .evaluate_by_ref()
+                    .map_err(|e| e.with_mark(start))?;
+            }
+        }
+
+        Ok(Some(event))
+    }
+
+    /// Consume the next node's events, see
+    /// [`Events::skip_node`]
+    fn skip_events(&mut self) -> Result<Option<Span>>
+    {
+        let event = match self.held.take()
+        {
+            Some(event) => Some(event),
+            None => self.parse_event()?,
+        };
+
+        let event = match event
+        {
+            Some(event) if nesting(event.data()).is_some() => event,
+            event =>
+            {
+                self.held = event;
+
+                return Ok(None);
+            },
+        };
+
+        self.count(&event)?;
+
+        let mut span = event.span();
+        let mut depth = nesting(event.data()).unwrap_or(0);
+
+        while depth > 0
+        {
+            let event = match self.next_event()?
+            {
+                Some(event) => event,
+                None => break,
+            };
+
+            span.end = event.span().end;
+            depth = match event.data()
+            {
+                EventData::MappingEnd | EventData::SequenceEnd => depth - 1,
+                data => depth + nesting(data).unwrap_or(0),
+            };
+        }
+
+        Ok(Some(span))
+    }
+
+    /// Enforce our limits on the given .event
+    fn count(&mut self, event: &Event<'de>) -> Result<()>
+    {
+        self.events += 1;
+        if self.events > self.limits.max_events
+        {
+            return Err(mkError!(ErrorCode::EventLimit, CODE).with_mark(event.span().start));
+        }
+
+        if let EventData::DocumentStart(_) = event.data()
+        {
+            self.documents += 1;
+            if self.documents > self.limits.max_documents
+            {
+                return Err(mkError!(ErrorCode::DocumentLimit, CODE).with_mark(event.span().start));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Retrieve the next event from the parser
+    fn parse_event(&mut self) -> Result<Option<Event<'de>>>
+    {
+        match self.parser.next_event(&mut self.reader).transpose()
+        {
+            Ok(event) => Ok(event),
+            // Scanner errors occur at the scanner's current position
+            Err(e @ ParseError::Scanner(_)) => Err(Error::from(e).with_mark(self.reader.mark())),
+            // Parser errors occur at the token being inspected
+            Err(e) => Err(Error::from(e).with_mark(self.reader.position())),
+        }
+    }
+}
+
+/// The change in nesting depth caused by an event which
+/// begins a node, or None if .data does not begin a node
+pub(crate) fn nesting(data: &EventData<'_>) -> Option<usize>
+{
+    match data
+    {
+        EventData::MappingStart(_) | EventData::SequenceStart(_) => Some(1),
+        EventData::Scalar(_) | EventData::Alias(_) => Some(0),
+        _ => None,
+    }
+}
+
+/// Convert a comment token into its [`Event`]
+fn comment_event(entry: TokenEntry<'_>) -> Result<Event<'_>>
+{
+    let span = entry.span();
+
+    match entry.into_token().map_err(ParseError::from)?
+    {
+        Token::Comment(text, placement) => Ok(Event::new(
+            span,
+            EventData::Comment(Comment { text, placement }),
+        )),
+        _ => Err(ParseError::CorruptStream.into()),
+    }
 }
 
 impl<'de, R> IntoIterator for Events<'de, R>
@@ -314,13 +613,11 @@ where
         self.inner
     }
 
-    /// Process the next event
-    fn next_event(&mut self) -> ParseResult<Option<Event<'de>>>
+    /// Skip the next node in the stream, see
+    /// [`Events::skip_node`]
+    pub fn skip_node(&mut self) -> Result<Option<Span>>
     {
-        self.inner
-            .parser
-            .next_event(&mut self.inner.reader)
-            .transpose()
+        self.inner.skip_node()
     }
 }
 
@@ -332,7 +629,7 @@ where
 
     fn next(&mut self) -> Option<Self::Item>
     {
-        self.next_event().map_err(Into::into).transpose()
+        self.inner.next_event().transpose()
     }
 }
 
@@ -356,13 +653,11 @@ where
         Self { inner: parent }
     }
 
-    /// Process the next event
-    fn next_event(&mut self) -> ParseResult<Option<Event<'de>>>
+    /// Skip the next node in the stream, see
+    /// [`Events::skip_node`]
+    pub fn skip_node(&mut self) -> Result<Option<Span>>
     {
-        self.inner
-            .parser
-            .next_event(&mut self.inner.reader)
-            .transpose()
+        self.inner.skip_node()
     }
 }
 
@@ -374,6 +669,642 @@ where
 
     fn next(&mut self) -> Option<Self::Item>
     {
-        self.next_event().map_err(Into::into).transpose()
+        self.inner.next_event().transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::{
+        event::types::{Mark, Span},
+        reader::borrow::BorrowReader,
+    };
+
+    fn spans(yaml: &str) -> Vec<Span>
+    {
+        let reader = BorrowReader::new(yaml);
+
+        from_reader(&reader)
+            .into_iter()
+            .map(|event| event.expect("valid YAML").span())
+            .collect()
+    }
+
+    #[test]
+    fn span_block_mapping()
+    {
+        let yaml = "---\nkey: value\nother:\n  nested\n";
+        let spans = spans(yaml);
+
+        // Key scalar 'key'
+        assert_eq!(spans[3], Span::new(Mark::new(4, 1, 0), Mark::new(7, 1, 3)));
+        // Value scalar 'value'
+        assert_eq!(spans[4].start, Mark::new(9, 1, 5));
+        // Key scalar 'other'
+        assert_eq!(spans[5].start, Mark::new(15, 2, 0));
+        // Value scalar 'nested'
+        assert_eq!(spans[6].start, Mark::new(24, 3, 2));
+    }
+
+    #[test]
+    fn span_flow_sequence()
+    {
+        let yaml = "[a, 'b']";
+        let spans = spans(yaml);
+
+        // '['
+        assert_eq!(spans[2], Span::new(Mark::new(0, 0, 0), Mark::new(1, 0, 1)));
+        // 'a'
+        assert_eq!(spans[3], Span::new(Mark::new(1, 0, 1), Mark::new(2, 0, 2)));
+        // "'b'"
+        assert_eq!(spans[4], Span::new(Mark::new(4, 0, 4), Mark::new(7, 0, 7)));
+        // ']'
+        assert_eq!(spans[5], Span::new(Mark::new(7, 0, 7), Mark::new(8, 0, 8)));
+    }
+
+    #[test]
+    fn span_node_properties()
+    {
+        let yaml = "- &anchor !!str value\n- *anchor\n";
+        let spans = spans(yaml);
+
+        // Scalar span starts at its first property
+        assert_eq!(
+            spans[3],
+            Span::new(Mark::new(2, 0, 2), Mark::new(21, 0, 21))
+        );
+        // Alias
+        assert_eq!(
+            spans[4],
+            Span::new(Mark::new(24, 1, 2), Mark::new(31, 1, 9))
+        );
+    }
+
+    #[test]
+    fn yaml11_flag()
+    {
+        use crate::event::types::{EventData, DEFAULT_VERSION, YAML11_VERSION};
+
+        let version = |yaml: &str, flags: Flags| {
+            let reader = BorrowReader::new(yaml);
+
+            from_reader_with(&reader, flags)
+                .into_iter()
+                .filter_map(|event| match event.expect("valid YAML").data()
+                {
+                    EventData::DocumentStart(doc) => Some(doc.directives.version),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(version("--- a\n", O_NIL), vec![DEFAULT_VERSION]);
+        assert_eq!(version("%YAML 1.1\n--- a\n", O_NIL), vec![YAML11_VERSION]);
+        assert_eq!(version("--- a\n", flag::O_YAML11), vec![YAML11_VERSION]);
+        assert_eq!(
+            version("%YAML 1.2\n--- a\n", flag::O_YAML11),
+            vec![DEFAULT_VERSION]
+        );
+
+        let reader = BorrowReader::new("\"\\/\"");
+        let errored = from_reader_with(&reader, flag::O_YAML11)
+            .into_iter()
+            .any(|event| event.is_err());
+
+        assert!(errored);
+
+        let reader = BorrowReader::new("%YAML 1.2\n--- \"\\/\"");
+        let errored = from_reader_with(&reader, flag::O_YAML11)
+            .into_iter()
+            .any(|event| event.is_err());
+
+        assert!(!errored);
+    }
+
+    /// Returns the first error produced by the given YAML
+    /// under .limits
+    fn limited(yaml: &str, limits: Limits) -> Option<Error>
+    {
+        let reader = BorrowReader::new(yaml);
+
+        from_reader_limited(&reader, O_NIL, limits)
+            .into_iter()
+            .find_map(Result::err)
+    }
+
+    fn assert_limit(yaml: &str, limits: Limits, (line, column): (u64, u64))
+    {
+        let err = limited(yaml, limits).expect("a limit error");
+
+        assert_eq!(err.classify(), crate::error::Category::Limit);
+        assert!(err.has_context());
+        assert_eq!((err.line(), err.column()), (line, column), "{}", err);
+    }
+
+    #[test]
+    fn limit_none_by_default()
+    {
+        let yaml = format!("{}a{}", "[".repeat(2048), "]".repeat(2048));
+        let reader = BorrowReader::new(&yaml);
+
+        assert!(from_reader(&reader).into_iter().all(|event| event.is_ok()));
+        assert!(limited(&yaml, Limits::default()).is_some());
+    }
+
+    #[test]
+    fn limit_depth_flow()
+    {
+        let limits = Limits {
+            max_depth: 3,
+            ..Limits::new()
+        };
+
+        assert!(limited("[[[a]]]", limits).is_none());
+        assert_limit("[[[[a]]]]", limits, (1, 4));
+    }
+
+    #[test]
+    fn limit_depth_block()
+    {
+        let limits = Limits {
+            max_depth: 2,
+            ..Limits::new()
+        };
+
+        assert!(limited("a:\n  b: c\n", limits).is_none());
+        assert_limit("a:\n  b:\n    c: d\n", limits, (3, 6));
+        assert_limit("a:\n  b: [c]\n", limits, (2, 6));
+    }
+
+    #[test]
+    fn limit_scalar()
+    {
+        let limits = Limits {
+            max_scalar_len: 8,
+            ..Limits::new()
+        };
+
+        assert!(limited("- 12345678\n- '123456'\n- |\n 12\n", limits).is_none());
+        assert_limit("[ok, 123456789]", limits, (1, 6));
+        assert_limit("- '12345678'\n", limits, (1, 3));
+        assert_limit("key: |\n  1234567\n", limits, (1, 6));
+    }
+
+    #[test]
+    fn limit_anchor()
+    {
+        let limits = Limits {
+            max_anchor_len: 4,
+            ..Limits::new()
+        };
+
+        assert!(limited("[&abcd a, *abcd]", limits).is_none());
+        assert_limit("[&abcd a, &abcde b]", limits, (1, 11));
+        assert_limit("[&abcd a, *abcde]", limits, (1, 11));
+    }
+
+    #[test]
+    fn limit_tag()
+    {
+        let limits = Limits {
+            max_tag_len: 8,
+            ..Limits::new()
+        };
+
+        assert!(limited("!!str a", limits).is_none());
+        assert_limit("!!strstrstr a", limits, (1, 1));
+        assert_limit("%TAG !e! tag:example.com:2000:\n--- a\n", limits, (1, 1));
+    }
+
+    #[test]
+    fn limit_documents()
+    {
+        let limits = Limits {
+            max_documents: 2,
+            ..Limits::new()
+        };
+
+        assert!(limited("--- a\n--- b\n", limits).is_none());
+        assert_limit("--- a\n--- b\n--- c\n", limits, (3, 1));
+    }
+
+    #[test]
+    fn limit_events()
+    {
+        let limits = Limits {
+            max_events: 6,
+            ..Limits::new()
+        };
+
+        // StreamStart, DocumentStart, SequenceStart, Scalar,
+        // SequenceEnd, DocumentEnd, StreamEnd
+        assert_limit("[a]", limits, (1, 4));
+        assert!(limited("a", limits).is_none());
+    }
+
+    #[test]
+    fn limit_unbounded_read()
+    {
+        use std::io;
+
+        /// An infinite stream of 'a's
+        struct Endless;
+
+        impl io::Read for Endless
+        {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize>
+            {
+                buf.iter_mut().for_each(|b| *b = b'a');
+
+                Ok(buf.len())
+            }
+        }
+
+        let reader = crate::reader::from_read(Endless);
+        let limits = Limits {
+            max_scalar_len: 1024,
+            ..Limits::new()
+        };
+
+        let err = from_reader_limited(&reader, O_NIL, limits)
+            .into_iter()
+            .find_map(Result::err)
+            .expect("a limit error");
+
+        assert_eq!(err.classify(), crate::error::Category::Limit);
+    }
+
+    fn encoded(yaml: &str, encoding: types::StreamEncoding) -> Vec<u8>
+    {
+        use types::StreamEncoding::*;
+
+        // Always include a BOM, which must be skipped
+        let chars = std::iter::once('\u{FEFF}').chain(yaml.chars());
+
+        match encoding
+        {
+            UTF8 => chars.collect::<String>().into_bytes(),
+            UTF16LE => chars
+                .collect::<String>()
+                .encode_utf16()
+                .flat_map(u16::to_le_bytes)
+                .collect(),
+            UTF16BE => chars
+                .collect::<String>()
+                .encode_utf16()
+                .flat_map(u16::to_be_bytes)
+                .collect(),
+            UTF32LE => chars.flat_map(|c| (c as u32).to_le_bytes()).collect(),
+            UTF32BE => chars.flat_map(|c| (c as u32).to_be_bytes()).collect(),
+        }
+    }
+
+    fn encoded_events<R>(reader: &R) -> Vec<types::EventData<'static>>
+    where
+        R: crate::reader::Read,
+    {
+        from_reader(reader)
+            .into_iter()
+            .map(|e| e.and_then(types::Event::into_owned).unwrap().data().clone())
+            .collect()
+    }
+
+    #[test]
+    fn encoding_detected()
+    {
+        use types::StreamEncoding::*;
+
+        let yaml = "key: [värde, \"🦀\"]\nother: |\n  text\n";
+        let expected = &encoded_events(&BorrowReader::new(yaml))[1..];
+
+        for &encoding in [UTF8, UTF16LE, UTF16BE, UTF32LE, UTF32BE].iter()
+        {
+            let bytes = encoded(yaml, encoding);
+            let start = types::EventData::StreamStart(types::StreamStart { encoding });
+
+            let reader = crate::reader::try_from_bytes(&bytes).unwrap();
+            let events = encoded_events(&reader);
+            assert_eq!(events[0], start, "slice: {:?}", encoding);
+            assert_eq!(&events[1..], expected, "slice: {:?}", encoding);
+
+            let reader = crate::reader::from_read(std::io::Cursor::new(bytes));
+            let events = encoded_events(&reader);
+            assert_eq!(events[0], start, "read: {:?}", encoding);
+            assert_eq!(&events[1..], expected, "read: {:?}", encoding);
+        }
+    }
+
+    #[test]
+    fn encoding_chunked_read()
+    {
+        // Every character straddles a chunk boundary somewhere
+        let yaml = "ä🦀".repeat(8 * 1024);
+        let bytes = encoded(&yaml, types::StreamEncoding::UTF16BE);
+
+        let reader = crate::reader::from_read(std::io::Cursor::new(bytes));
+        let events = encoded_events(&reader);
+
+        let mut expected = encoded_events(&BorrowReader::new(&yaml));
+        expected[0] = types::EventData::StreamStart(types::StreamStart {
+            encoding: types::StreamEncoding::UTF16BE,
+        });
+
+        assert_eq!(events, expected);
+    }
+
+    #[test]
+    fn encoding_invalid()
+    {
+        // Unpaired UTF16LE surrogate
+        let bytes = [b'a', 0x00, 0x00, 0xDC, b'\n', 0x00];
+
+        let err = crate::reader::try_from_bytes(&bytes).unwrap_err();
+        assert_eq!(err.classify(), crate::error::Category::Data);
+
+        let reader = crate::reader::from_read(std::io::Cursor::new(bytes));
+        let err = from_reader(&reader)
+            .into_iter()
+            .find_map(Result::err)
+            .expect("an encoding error");
+        assert_eq!(err.classify(), crate::error::Category::Data);
+    }
+
+    /// Summarizes the events of .yaml, with comments
+    /// enabled, as short strings
+    fn commented<R>(reader: &R) -> Vec<String>
+    where
+        R: crate::reader::Read,
+    {
+        use types::{CommentPlacement::*, EventData::*, ScalarLike};
+
+        from_reader_with(reader, flag::O_COMMENTS)
+            .into_iter()
+            .map(
+                |e| match e.and_then(types::Event::into_owned).unwrap().data()
+                {
+                    Comment(c) if c.placement == OwnLine => format!("#{}", c.text),
+                    Comment(c) if c.placement == Trailing => format!("#>{}", c.text),
+                    Scalar(node) => match &node.content
+                    {
+                        ScalarLike::Eager(s) => format!("={}", s.data()),
+                        ScalarLike::Lazy(_) => unreachable!("owned events are eager"),
+                    },
+                    MappingStart(_) => "{".into(),
+                    MappingEnd => "}".into(),
+                    SequenceStart(_) => "[".into(),
+                    SequenceEnd => "]".into(),
+                    DocumentStart(_) => "+DOC".into(),
+                    DocumentEnd(_) => "-DOC".into(),
+                    StreamStart(_) => "+STR".into(),
+                    StreamEnd => "-STR".into(),
+                    other => format!("{:?}", other),
+                },
+            )
+            .collect()
+    }
+
+    #[test]
+    fn comments_placement()
+    {
+        let yaml = "# head\nkey: value # trailing\n# own line\nother: [a, # flow\n  b]\n# tail\n";
+        let expected = [
+            "+STR",
+            "# head",
+            "+DOC",
+            "{",
+            "=key",
+            "=value",
+            "#> trailing",
+            "# own line",
+            "=other",
+            "[",
+            "=a",
+            "#> flow",
+            "=b",
+            "]",
+            "# tail",
+            "}",
+            "-DOC",
+            "-STR",
+        ];
+
+        assert_eq!(commented(&BorrowReader::new(yaml)), expected);
+
+        let reader = crate::reader::from_read(std::io::Cursor::new(yaml));
+        assert_eq!(commented(&reader), expected);
+    }
+
+    #[test]
+    fn comments_sequence()
+    {
+        let yaml = "- a #1\n#2\n- b\n";
+        let expected = [
+            "+STR", "+DOC", "[", "=a", "#>1", "#2", "=b", "]", "-DOC", "-STR",
+        ];
+
+        assert_eq!(commented(&BorrowReader::new(yaml)), expected);
+    }
+
+    #[test]
+    fn comments_disabled()
+    {
+        let yaml = "# head\nkey: value # trailing\n";
+
+        let comments = from_reader(&BorrowReader::new(yaml))
+            .into_iter()
+            .map(|e| e.unwrap())
+            .filter(|e| matches!(e.data(), types::EventData::Comment(_)))
+            .count();
+
+        assert_eq!(comments, 0);
+    }
+
+    /// Render the events of .yaml, skipping the node after
+    /// any 'skip' scalar and recording the text it covered
+    fn skipping(yaml: &str, flags: Flags) -> Vec<String>
+    {
+        use types::{EventData::*, ScalarLike};
+
+        let reader = BorrowReader::new(yaml);
+        let mut events = from_reader_with(&reader, flags);
+        let mut rendered = Vec::new();
+
+        while let Some(event) = events.iter().next()
+        {
+            let event = event.unwrap();
+            let render = match event.data()
+            {
+                Scalar(node) => match &node.content
+                {
+                    ScalarLike::Eager(s) => format!("={}", s.data()),
+                    ScalarLike::Lazy(_) => "=~".into(),
+                },
+                Comment(c) => format!("#{}", c.text),
+                MappingStart(_) => "{".into(),
+                MappingEnd => "}".into(),
+                SequenceStart(_) => "[".into(),
+                SequenceEnd => "]".into(),
+                _ => continue,
+            };
+
+            let skip = render == "=skip";
+            rendered.push(render);
+
+            if skip
+            {
+                let skipped = events.skip_node().unwrap();
+
+                rendered.push(format!(
+                    "-{}",
+                    skipped.map_or("", |span| &yaml[span.start.offset..span.end.offset])
+                ));
+            }
+        }
+
+        rendered
+    }
+
+    #[test]
+    fn skip_node_block()
+    {
+        let yaml = "skip:\n  a: [1, 2]\n  b: {c: d}\nkeep: 1\n";
+        let expected = [
+            "{",
+            "=skip",
+            "-a: [1, 2]\n  b: {c: d}\n",
+            "=keep",
+            "=1",
+            "}",
+        ];
+
+        assert_eq!(skipping(yaml, O_NIL), expected);
+    }
+
+    #[test]
+    fn skip_node_flow()
+    {
+        let yaml = "[skip, [1, [2]], skip, *a, skip]";
+        let expected = ["[", "=skip", "-[1, [2]]", "=skip", "-*a", "=skip", "-", "]"];
+
+        assert_eq!(skipping(yaml, O_NIL), expected);
+    }
+
+    #[test]
+    fn skip_node_unevaluated()
+    {
+        // The invalid escape is never evaluated
+        let yaml = "- skip\n- \"\\q\"\n- \"\\t\"\n";
+        let expected = ["[", "=skip", "-\"\\q\"", "=\t", "]"];
+
+        assert_eq!(skipping(yaml, O_NIL), expected);
+
+        let expected = ["[", "=~", "=~", "=~", "]"];
+
+        assert_eq!(skipping(yaml, flag::O_LAZY), expected);
+    }
+
+    #[test]
+    fn skip_node_comments()
+    {
+        let yaml = "#1\nskip: #2\n  - a #3\n#4\nkeep: b\n";
+        // Comments up to the end of the skipped node are discarded
+        let expected = ["#1", "{", "=skip", "-- a #3\n#4\n", "=keep", "=b", "}"];
+
+        assert_eq!(skipping(yaml, flag::O_COMMENTS), expected);
+    }
+
+    #[test]
+    fn skip_node_limits()
+    {
+        let reader = BorrowReader::new("[[a, b, c]]");
+        let limits = Limits {
+            max_events: 5,
+            ..Limits::new()
+        };
+        let mut events = from_reader_limited(&reader, O_NIL, limits);
+
+        events.iter().take(3).for_each(drop);
+
+        let err = events.skip_node().expect_err("an event limit error");
+        assert_eq!(err.kind(), crate::error::ErrorKind::EventLimit);
+    }
+
+    #[test]
+    fn resolved_tags()
+    {
+        use types::EventData::*;
+
+        let yaml = "%TAG !e! tag:example.com:2000:\n--- !e!m%21 {a: !!str b, 'c': d, e: !local f, \
+                    g: !<tag:x,y> [h], i: ! j, ! k: !e! l}\n";
+        let reader = BorrowReader::new(yaml);
+
+        let resolved: Vec<_> = from_reader(&reader)
+            .into_iter()
+            .filter_map(|event| match event.unwrap().data()
+            {
+                Scalar(node) => Some((node.resolved.to_string(), node.tag.is_some())),
+                MappingStart(node) => Some((node.resolved.to_string(), node.tag.is_some())),
+                SequenceStart(node) => Some((node.resolved.to_string(), node.tag.is_some())),
+                _ => None,
+            })
+            .collect();
+
+        let expected = [
+            ("tag:example.com:2000:m!", true),
+            ("?", false),
+            ("tag:yaml.org,2002:str", true),
+            ("!", false),
+            ("?", false),
+            ("?", false),
+            ("!local", true),
+            ("?", false),
+            ("tag:x,y", true),
+            ("?", false),
+            ("?", false),
+            ("!", false),
+            ("!", false),
+            ("tag:example.com:2000:", true),
+        ];
+        let expected: Vec<_> = expected.iter().map(|&(t, b)| (t.to_string(), b)).collect();
+
+        assert_eq!(resolved, expected);
+    }
+
+    #[test]
+    fn redefined_default_tags()
+    {
+        use types::EventData::Scalar;
+
+        let yaml =
+            "%TAG !! tag:example.com:2000:\n%TAG ! !app-\n--- [!!int 1, !local 2]\n--- !!int 3\n";
+        let reader = BorrowReader::new(yaml);
+
+        let resolved: Vec<_> = from_reader(&reader)
+            .into_iter()
+            .filter_map(|event| match event.unwrap().data()
+            {
+                Scalar(node) => Some(node.resolved.to_string()),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(
+            resolved,
+            vec![
+                "tag:example.com:2000:int",
+                "!app-local",
+                "tag:yaml.org,2002:int"
+            ]
+        );
+
+        let duplicate = BorrowReader::new("%TAG !! tag:a.com:\n%TAG !! tag:b.com:\n--- a\n");
+        let err = from_reader(&duplicate)
+            .into_iter()
+            .find_map(Result::err)
+            .expect("a duplicate directive");
+
+        assert_eq!(err.kind(), crate::error::ErrorKind::DuplicateTagDirective);
     }
 }
@@ -0,0 +1,257 @@
+/*
+ * This Source Code Form is subject to the terms of the
+ * Mozilla Public License, v. 2.0. If a copy of the MPL
+ * was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+//! Bounded memory event streams over [`std::io::Read`]
+//! sources.
+//!
+//! An [`Events`] stream borrows every [`Event`] it produces
+//! from its read source, which forces an [`OwnedReader`] to
+//! retain every byte it has ever read. [`OwnedEvents`]
+//! instead produces owned (`'static`) events, which allows
+//! the reader to release its buffers once the stream has
+//! moved past them. Memory use is thereby proportional to
+//! the largest token in the stream, rather than the whole
+//! stream.
+//!
+//! ```
+//! use std::io::Cursor;
+//!
+//! use yary::{
+//!     event::{flag::O_NIL, owned::OwnedEvents, types::EventData},
+//!     reader::from_read,
+//! };
+//!
+//! let yaml = Cursor::new("--- a\n--- b\n--- c\n");
+//! let events = OwnedEvents::new(from_read(yaml), O_NIL);
+//!
+//! let documents = events
+//!     .filter_map(Result::ok)
+//!     .filter(|e| matches!(e.data(), EventData::DocumentStart(_)))
+//!     .count();
+//!
+//! assert_eq!(documents, 3);
+//! ```
+//!
+//! [`Events`]: struct@super::Events
+//! [`Event`]: struct@super::types::Event
+//! [`OwnedReader`]: struct@crate::reader::owned::OwnedReader
+
+use std::fmt;
+
+use crate::{
+    error::Result,
+    event::{flag::Flags, limit::Limits, types::Event, Events},
+    reader::{owned::OwnedReader, Read},
+};
+
+/// An iterator of owned [`Event`]s, which releases the
+/// memory of its underlying [`OwnedReader`] as the stream
+/// is consumed.
+///
+/// Each [`Event`] is copied out of the read source before
+/// being returned, evaluating any lazy scalars in the
+/// process.
+///
+/// [`OwnedReader`]: struct@crate::reader::owned::OwnedReader
+pub struct OwnedEvents
+{
+    // Field order is important here, .events borrows from
+    // .reader and therefore must be dropped first
+    events: Events<'static, OwnedReader>,
+    reader: Box<OwnedReader>,
+}
+
+impl OwnedEvents
+{
+    /// Instantiate a new [`OwnedEvents`] from the given
+    /// read source and flags.
+    pub fn new(src: OwnedReader, flags: Flags) -> Self
+    {
+        Self::with_limits(src, flags, Limits::default())
+    }
+
+    /// Instantiate a new [`OwnedEvents`] from the given
+    /// read source, flags and limits.
+    pub fn with_limits(src: OwnedReader, flags: Flags, limits: Limits) -> Self
+    {
+        let reader = Box::new(src);
+
+        // SAFETY:
+        //
+        // .reader is boxed, and therefore has a stable address
+        // for its lifetime, which we never hand out. As .events
+        // is dropped before .reader, the extended borrow never
+        // outlives its referent.
+        let borrow: &'static OwnedReader = unsafe { &*(&*reader as *const OwnedReader) };
+        let events = Events::with_limits(borrow, flags, limits);
+
+        Self { events, reader }
+    }
+
+    /// Retrieve the next owned [`Event`], if any
+    fn next_owned(&mut self) -> Option<Result<Event<'static>>>
+    {
+        let event = self.events.iter().next()?;
+        let event = event.and_then(Event::into_owned);
+
+        // SAFETY:
+        //
+        // The event above no longer borrows from .reader, and
+        // any data still referenced by unprocessed tokens lies
+        // at or above .bound, so nothing can reference the
+        // memory released here.
+        //
+        // Note we release regardless of the event's result, as
+        // .events has no other references outstanding.
+        let _ = unsafe { self.reader.consume(self.events.bound()) };
+
+        Some(event)
+    }
+}
+
+impl Iterator for OwnedEvents
+{
+    type Item = Result<Event<'static>>;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        self.next_owned()
+    }
+}
+
+impl fmt::Debug for OwnedEvents
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        f.debug_struct("OwnedEvents")
+            .field("events", &self.events)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use std::io::{self, Cursor};
+
+    use super::*;
+    use crate::{
+        event::{
+            flag::O_NIL,
+            from_reader,
+            types::{EventData, Node, ScalarLike},
+        },
+        reader::{borrow::BorrowReader, from_read},
+    };
+
+    /// Streams .count copies of .document
+    struct Repeat
+    {
+        document: &'static [u8],
+        count:    usize,
+        pos:      usize,
+    }
+
+    impl io::Read for Repeat
+    {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize>
+        {
+            if self.count == 0
+            {
+                return Ok(0);
+            }
+
+            let rest = &self.document[self.pos..];
+            let amt = usize::min(rest.len(), buf.len());
+            buf[..amt].copy_from_slice(&rest[..amt]);
+
+            self.pos += amt;
+            if self.pos == self.document.len()
+            {
+                self.pos = 0;
+                self.count -= 1;
+            }
+
+            Ok(amt)
+        }
+    }
+
+    #[test]
+    fn owned_matches_borrowed()
+    {
+        let data = "%TAG !e! tag:example.com:2000:\n--- &a !e!map\nkey: [*a, 'quoted', \
+                    \"esc\\n\"]\nblock: |\n  text\n...\n";
+
+        let yaml = BorrowReader::new(data);
+        let expected: Vec<_> = from_reader(&yaml)
+            .into_iter()
+            .map(|e| e.and_then(Event::into_owned).unwrap())
+            .collect();
+
+        let actual: Vec<_> = OwnedEvents::new(from_read(Cursor::new(data)), O_NIL)
+            .map(Result::unwrap)
+            .collect();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn owned_bounded_memory()
+    {
+        let document = b"--- {key: value, list: [1, 2, 3], nested: {a: b}}\n";
+        let count = 10_000;
+        let src = Repeat {
+            document,
+            count,
+            pos: 0,
+        };
+
+        let mut events = OwnedEvents::new(from_read(src), O_NIL);
+        let mut documents = 0;
+        let mut peak = 0;
+
+        while let Some(event) = events.next()
+        {
+            if let EventData::DocumentStart(_) = event.unwrap().data()
+            {
+                documents += 1;
+            }
+
+            peak = usize::max(peak, events.reader.buffered());
+        }
+
+        assert_eq!(documents, count);
+        // The stream is ~500KiB, we should only ever be holding a
+        // small fraction of it
+        assert!(
+            peak < 64 * 1024,
+            "held {} bytes of a {} byte stream",
+            peak,
+            document.len() * count
+        );
+    }
+
+    #[test]
+    fn owned_large_token()
+    {
+        let scalar = "x".repeat(100 * 1024);
+        let data = format!("--- {}\n--- small\n", scalar);
+
+        let scalars: Vec<_> = OwnedEvents::new(from_read(Cursor::new(data)), O_NIL)
+            .map(Result::unwrap)
+            .filter_map(|e| match e.data()
+            {
+                EventData::Scalar(Node {
+                    content: ScalarLike::Eager(scalar),
+                    ..
+                }) => Some(scalar.data().to_string()),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(scalars, vec![scalar, "small".to_string()]);
+    }
+}
@@ -5,7 +5,7 @@
  */
 
 /// Peek the head of the .queue, returning its start and end
-/// byte and a marker representing the underlying Token, in
+/// mark and a marker representing the underlying Token, in
 /// a three item tuple (.start, .end, .marker)
 ///
 /// Modifiers
@@ -20,7 +20,7 @@ macro_rules! peek {
             .peek()
             .map_err(Into::into)
             .and_then(|maybe| maybe.ok_or($crate::event::error::ParseError::UnexpectedEOF))
-            .map(|entry| (entry.read_at(), entry.read_at(), entry.marker()))
+            .map(|entry| (entry.span().start, entry.span().end, entry.marker()))
     };
     (~ $queue:expr) => {
         $queue
@@ -118,21 +118,22 @@ macro_rules! consume {
     }};
 
     (@wrap $entry:expr, Scalar) => {{
-        let end = $entry.read_at();
+        let span = $entry.span();
+        let (start, end) = (span.start, span.end);
 
         match $entry.wrap {
             MaybeToken::Token(token) => match token {
-                Scalar(data, style) => (end, end, ScalarLike::eager(data, style)),
+                Scalar(data, style) => (start, end, ScalarLike::eager(data, style)),
                 _ => unreachable!(),
             },
-            MaybeToken::Deferred(lazy) => (end, end, ScalarLike::lazy(lazy))
+            MaybeToken::Deferred(lazy) => (start, end, ScalarLike::lazy(lazy))
         }
     }};
     (@wrap $entry:expr, $kind:tt) => {{
-        let end = $entry.read_at();
+        let span = $entry.span();
 
         match $entry.wrap {
-            MaybeToken::Token(token) => consume!(@entry $kind => end, end, token),
+            MaybeToken::Token(token) => consume!(@entry $kind => span.start, span.end, token),
             _ => unreachable!(),
         }
     }};
@@ -238,62 +239,62 @@ macro_rules! initEvent {
     (@event StreamStart => $context:expr) => {{
         let (start, end, encoding) = $context;
 
-        Event::new(start, end, EventData::StreamStart(types::StreamStart { encoding }))
+        Event::new(types::Span::new(start, end), EventData::StreamStart(types::StreamStart { encoding }))
     }};
     (@event StreamEnd => $context:expr) => {{
         let (start, end, ()) = $context;
 
-        Event::new(start, end, EventData::StreamEnd)
+        Event::new(types::Span::new(start, end), EventData::StreamEnd)
     }};
     (@event DocumentStart => $context:expr) => {{
         let (start, end, (version, tags, implicit)) = $context;
         let directives = types::Directives { version, tags };
 
-        Event::new(start, end, EventData::DocumentStart(types::DocumentStart { directives, implicit }))
+        Event::new(types::Span::new(start, end), EventData::DocumentStart(types::DocumentStart { directives, implicit }))
     }};
     (@event DocumentEnd => $context:expr) => {{
         let (start, end, implicit) = $context;
 
-        Event::new(start, end, EventData::DocumentEnd(types::DocumentEnd { implicit }))
+        Event::new(types::Span::new(start, end), EventData::DocumentEnd(types::DocumentEnd { implicit }))
     }};
     (@event SequenceEnd => $context:expr) => {{
         let (start, end, _) = $context;
 
-        Event::new(start, end, EventData::SequenceEnd)
+        Event::new(types::Span::new(start, end), EventData::SequenceEnd)
     }};
     (@event MappingEnd => $context:expr) => {{
         let (start, end, _) = $context;
 
-        Event::new(start, end, EventData::MappingEnd)
+        Event::new(types::Span::new(start, end), EventData::MappingEnd)
     }};
     (@event Alias => $context:expr) => {{
         let (start, end, name) = $context;
 
-        Event::new(start, end, EventData::Alias(types::Alias { name }))
+        Event::new(types::Span::new(start, end), EventData::Alias(types::Alias { name }))
     }};
     (@event FlowSequenceStart => $context:expr) => {{
         let (start, end, (anchor, tag, kind)) = $context;
 
-        Event::new(start, end, EventData::SequenceStart(types::Node { anchor, tag, content: types::Sequence, kind }))
+        Event::new(types::Span::new(start, end), EventData::SequenceStart(types::Node { anchor, tag, content: types::Sequence, kind, resolved: types::ResolvedTag::Question }))
     }};
     (@event FlowMappingStart => $context:expr) => {{
         let (start, end, (anchor, tag, kind)) = $context;
 
-        Event::new(start, end, EventData::MappingStart(types::Node { anchor, tag, content: types::Mapping, kind }))
+        Event::new(types::Span::new(start, end), EventData::MappingStart(types::Node { anchor, tag, content: types::Mapping, kind, resolved: types::ResolvedTag::Question }))
     }};
     (@event BlockSequenceStart => $context:expr) => {{
         let (start, end, (anchor, tag, kind)) = $context;
 
-        Event::new(start, end, EventData::SequenceStart(types::Node { anchor, tag, content: types::Sequence, kind }))
+        Event::new(types::Span::new(start, end), EventData::SequenceStart(types::Node { anchor, tag, content: types::Sequence, kind, resolved: types::ResolvedTag::Question }))
     }};
     (@event BlockMappingStart => $context:expr) => {{
         let (start, end, (anchor, tag, kind)) = $context;
 
-        Event::new(start, end, EventData::MappingStart(types::Node { anchor, tag, content: types::Mapping, kind }))
+        Event::new(types::Span::new(start, end), EventData::MappingStart(types::Node { anchor, tag, content: types::Mapping, kind, resolved: types::ResolvedTag::Question }))
     }};
     (@event Scalar => $context:expr) => {{
         let (start, end, (anchor, tag, kind, content)) = $context;
 
-        Event::new(start, end, EventData::Scalar(types::Node { anchor, tag, content, kind }))
+        Event::new(types::Span::new(start, end), EventData::Scalar(types::Node { anchor, tag, content, kind, resolved: types::ResolvedTag::Question }))
     }};
 }
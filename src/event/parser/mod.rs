@@ -39,8 +39,8 @@ use crate::{
         error::{ParseError as Error, ParseResult as Result},
         state::{Flags, State, StateMachine, O_EMPTY, O_FIRST, O_IMPLICIT, O_NIL},
         types::{
-            self, array_iterator, Directives, Event, EventData, NodeKind, TagDirectives,
-            DEFAULT_TAGS, EMPTY_SCALAR,
+            self, array_iterator, Directives, Event, EventData, Mark, NodeKind, ResolvedTag,
+            TagDirectives, VersionDirective, DEFAULT_TAGS, DEFAULT_VERSION, EMPTY_SCALAR,
         },
     },
     reader::{PeekReader, Read},
@@ -80,6 +80,7 @@ pub(crate) struct Parser
     state: StateMachine,
 
     directives: Directives<'static>,
+    version:    VersionDirective,
     done:       bool,
 }
 
@@ -88,11 +89,20 @@ impl Parser
     /// Instantiate a new [`Parser`], ready for a new token
     /// stream.
     pub fn new() -> Self
+    {
+        Self::with_version(DEFAULT_VERSION)
+    }
+
+    /// Instantiate a new [`Parser`], using the given
+    /// .version for any document without a `%YAML`
+    /// directive.
+    pub fn with_version(version: VersionDirective) -> Self
     {
         Self {
-            state:      StateMachine::default(),
+            state: StateMachine::default(),
             directives: Default::default(),
-            done:       false,
+            version,
+            done: false,
         }
     }
 
@@ -252,7 +262,7 @@ impl Parser
             // Retrieve any directives for the current document, merged
             // with the defaults
             let (start, end, directives) =
-                scan_document_directives(tokens, array_iterator(DEFAULT_TAGS))?;
+                scan_document_directives(tokens, self.version, array_iterator(DEFAULT_TAGS))?;
 
             let Directives { version, tags } = directives;
             event =
@@ -267,12 +277,12 @@ impl Parser
             // Retrieve any directives for the current document, merged
             // with the defaults
             let (start, _, directives) =
-                scan_document_directives(tokens, array_iterator(DEFAULT_TAGS))?;
+                scan_document_directives(tokens, self.version, array_iterator(DEFAULT_TAGS))?;
 
             // Ensure we have an explicit DocumentStart indicator
             let end = match peek!(~tokens)?
             {
-                Marker::DocumentStart => pop!(tokens).map(|entry| entry.read_at()),
+                Marker::DocumentStart => pop!(tokens).map(|entry| entry.span().end),
                 _ => Err(Error::MissingDocumentStart),
             }?;
 
@@ -288,7 +298,7 @@ impl Parser
         else if first
         {
             let (start, end, directives) =
-                scan_document_directives(tokens, array_iterator(DEFAULT_TAGS))?;
+                scan_document_directives(tokens, self.version, array_iterator(DEFAULT_TAGS))?;
 
             let Directives { version, tags } = directives;
             event =
@@ -342,7 +352,7 @@ impl Parser
 
         // If this the DocumentEnd was implicit then the next
         // document start must be explicit
-        opts = implicit.then(|| O_NIL).unwrap_or(O_IMPLICIT);
+        opts = if implicit { O_NIL } else { O_IMPLICIT };
         state!(~self, -> State::DocumentStart(opts));
 
         event = initEvent!(@event DocumentEnd => (start, end, implicit));
@@ -973,7 +983,7 @@ impl Parser
         T: Read,
     {
         let mut event;
-        let (mut start, mut end, token) = peek!(tokens)?;
+        let (start, mut end, token) = peek!(tokens)?;
 
         // If the node is an alias, return it
         if matches!(token, Marker::Alias)
@@ -997,20 +1007,20 @@ impl Parser
             {
                 Marker::Anchor =>
                 {
-                    anchor = fetch_anchor(tokens, &mut start, &mut end)?;
-                    tag = fetch_tag(tokens, &mut start, &mut end)?;
+                    anchor = fetch_anchor(tokens, &mut end)?;
+                    tag = fetch_tag(tokens, &mut end)?;
                 },
                 Marker::Tag =>
                 {
-                    tag = fetch_tag(tokens, &mut start, &mut end)?;
-                    anchor = fetch_anchor(tokens, &mut start, &mut end)?;
+                    tag = fetch_tag(tokens, &mut end)?;
+                    anchor = fetch_anchor(tokens, &mut end)?;
                 },
                 _ =>
                 {},
             }
 
             // Refresh our current token view
-            let (_, end, token) = peek!(tokens)?;
+            let (_, content_end, token) = peek!(tokens)?;
 
             // Handle possible node variants
             match token
@@ -1019,7 +1029,7 @@ impl Parser
                 Marker::FlowSequenceStart =>
                 {
                     event =
-                        initEvent!(@event FlowSequenceStart => (start, end, (anchor, tag, kind)))
+                        initEvent!(@event FlowSequenceStart => (start, content_end, (anchor, tag, kind)))
                             .into();
 
                     state!(~self, -> State::FlowSequenceEntry(O_FIRST));
@@ -1028,7 +1038,7 @@ impl Parser
                 Marker::FlowMappingStart =>
                 {
                     event =
-                        initEvent!(@event FlowMappingStart => (start, end, (anchor, tag, kind)))
+                        initEvent!(@event FlowMappingStart => (start, content_end, (anchor, tag, kind)))
                             .into();
 
                     state!(~self, -> State::FlowMappingKey(O_FIRST));
@@ -1037,7 +1047,7 @@ impl Parser
                 Marker::BlockSequenceStart if block =>
                 {
                     event =
-                        initEvent!(@event BlockSequenceStart => (start, end, (anchor, tag, kind)))
+                        initEvent!(@event BlockSequenceStart => (start, content_end, (anchor, tag, kind)))
                             .into();
 
                     state!(~self, -> State::BlockSequenceEntry(O_FIRST));
@@ -1046,7 +1056,7 @@ impl Parser
                 Marker::BlockMappingStart if block =>
                 {
                     event =
-                        initEvent!(@event BlockMappingStart => (start, end, (anchor, tag, kind)))
+                        initEvent!(@event BlockMappingStart => (start, content_end, (anchor, tag, kind)))
                             .into();
 
                     state!(~self, -> State::BlockMappingKey(O_FIRST));
@@ -1055,7 +1065,7 @@ impl Parser
                 Marker::Scalar =>
                 {
                     let (_, _, scalar) = consume!(tokens, Scalar)?;
-                    event = initEvent!(@event Scalar => (start, end, (anchor, tag, kind, scalar)))
+                    event = initEvent!(@event Scalar => (start, content_end, (anchor, tag, kind, scalar)))
                         .into();
 
                     state!(~self, << None);
@@ -1075,11 +1085,11 @@ impl Parser
             }
         }
 
-        // Ensure the event's tag (if it exists) is in the active
-        // directives map
+        // Resolve the event's tag through the active directives
+        // map, ensuring its handle (if it exists) is present
         if let Some(ref mut event) = event
         {
-            validate_event_tag(&self.directives.tags, event)?
+            resolve_event_tag(&self.directives.tags, event)?
         }
 
         Ok(event)
@@ -1088,7 +1098,7 @@ impl Parser
     /// Produce an empty scalar node [`Event`], always
     /// returns Ok, the Result is mostly for
     /// compose-ability
-    fn empty_scalar(&mut self, mark: usize, kind: NodeKind) -> Result<Event<'static>>
+    fn empty_scalar(&mut self, mark: Mark, kind: NodeKind) -> Result<Event<'static>>
     {
         let event =
             initEvent!(@event Scalar => (mark, mark, (NO_ANCHOR, NO_TAG, kind, EMPTY_SCALAR)));
@@ -1098,13 +1108,15 @@ impl Parser
 }
 
 /// Fetch all adjacent YAML directives from the stream,
-/// merging them with the provided default_directives,
+/// merging them with the provided default_version and
+/// default_directives,
 /// returning the the start + end stream marks, and the
 /// directives themselves.
 fn scan_document_directives<'a: 'de, 'de, I, T>(
     tokens: &mut Tokens<'de, T>,
+    default_version: VersionDirective,
     default_directives: I,
-) -> Result<(usize, usize, Directives<'de>)>
+) -> Result<(Mark, Mark, Directives<'de>)>
 where
     I: Iterator<Item = (Slice<'a>, Slice<'a>)>,
     T: Read,
@@ -1112,7 +1124,10 @@ where
     #[allow(unused_assignments)]
     let (start, mut end, mut token) = peek!(tokens)?;
 
-    let mut directives = Directives::default();
+    let mut directives = Directives {
+        version: default_version,
+        ..Directives::empty()
+    };
     let mut seen_version = false;
 
     let tags = &mut directives.tags;
@@ -1171,11 +1186,7 @@ where
 
 /// Attempt to retrieve an Anchor token's name if one exists
 /// at the head of the token stream
-fn fetch_anchor<'de, T>(
-    tokens: &mut Tokens<'de, T>,
-    start: &mut usize,
-    end: &mut usize,
-) -> Result<Option<Slice<'de>>>
+fn fetch_anchor<'de, T>(tokens: &mut Tokens<'de, T>, end: &mut Mark) -> Result<Option<Slice<'de>>>
 where
     T: Read,
 {
@@ -1184,8 +1195,7 @@ where
 
     if let Some(Marker::Anchor) = token
     {
-        let (s, e, name) = consume!(tokens, Anchor)?;
-        *start = s;
+        let (_, e, name) = consume!(tokens, Anchor)?;
         *end = e;
         anchor = Some(name);
     }
@@ -1197,8 +1207,7 @@ where
 /// one exists at the head of the token stream
 fn fetch_tag<'de, T>(
     tokens: &mut Tokens<'de, T>,
-    start: &mut usize,
-    end: &mut usize,
+    end: &mut Mark,
 ) -> Result<Option<(Slice<'de>, Slice<'de>)>>
 where
     T: Read,
@@ -1208,8 +1217,7 @@ where
 
     if let Some(Marker::Tag) = token
     {
-        let (s, e, (handle, suffix)) = consume!(tokens, Tag)?;
-        *start = s;
+        let (_, e, (handle, suffix)) = consume!(tokens, Tag)?;
         *end = e;
         tag = Some((handle, suffix));
     }
@@ -1220,8 +1228,8 @@ where
 fn fetch_sequence_end<'de, T>(
     this: &mut Parser,
     tokens: &mut Tokens<'de, T>,
-    start: usize,
-    end: usize,
+    start: Mark,
+    end: Mark,
 ) -> Result<Event<'de>>
 where
     T: Read,
@@ -1236,8 +1244,8 @@ where
 fn fetch_mapping_end<'de, T>(
     this: &mut Parser,
     tokens: &mut Tokens<'de, T>,
-    start: usize,
-    end: usize,
+    start: Mark,
+    end: Mark,
 ) -> Result<Event<'de>>
 where
     T: Read,
@@ -1249,46 +1257,71 @@ where
     Ok(initEvent!(@event MappingEnd => (start, end, ())))
 }
 
-fn validate_event_tag(tags: &TagDirectives, event: &mut Event) -> Result<()>
+fn resolve_event_tag(tags: &TagDirectives, event: &mut Event) -> Result<()>
 {
     match event.data_mut()
     {
-        EventData::Scalar(node) => validate_tag(tags, &mut node.tag, true),
-        EventData::SequenceStart(node) => validate_tag(tags, &mut node.tag, false),
-        EventData::MappingStart(node) => validate_tag(tags, &mut node.tag, false),
-        _ => Ok(()),
+        EventData::Scalar(node) =>
+        {
+            node.resolved = resolve_tag(tags, &mut node.tag, Some(node.content.is_plain()))?
+        },
+        EventData::SequenceStart(node) => node.resolved = resolve_tag(tags, &mut node.tag, None)?,
+        EventData::MappingStart(node) => node.resolved = resolve_tag(tags, &mut node.tag, None)?,
+        _ =>
+        {},
     }
+
+    Ok(())
 }
 
-fn validate_tag(tags: &TagDirectives, tag: &mut Option<(Slice, Slice)>, scalar: bool)
-    -> Result<()>
+/// Resolve the given .tag to its full URI, where .plain is
+/// Some if the tag belongs to a scalar, and whether that
+/// scalar was plain.
+///
+/// Note that any percent escapes in the tag were already
+/// decoded by the Scanner.
+fn resolve_tag<'de>(
+    tags: &TagDirectives,
+    tag: &mut Option<(Slice<'de>, Slice<'de>)>,
+    plain: Option<bool>,
+) -> Result<ResolvedTag<'de>>
 {
-    if let Some((handle, suffix)) = tag.as_ref()
+    let (handle, suffix) = match tag.as_ref()
     {
-        // A YAML scalar tag may be marked as
-        // 'non-resolvable', in which case we skip the
-        // checks
-        let resolvable = !(handle == "!" && suffix.is_empty());
+        Some(tag) => tag,
+        // Untagged plain scalars and collections are resolved by
+        // their content, any other scalars by their kind
+        None if plain.unwrap_or(true) => return Ok(ResolvedTag::Question),
+        None => return Ok(ResolvedTag::Exclamation),
+    };
 
-        match (scalar, resolvable)
+    match (handle.as_ref(), suffix.as_ref())
+    {
+        // A YAML tag may be marked as 'non-specific' with a
+        // lone '!', in which case we skip the checks
+        ("!", "") =>
         {
-            // Verify tag handle exists in the directives if we're either:
-            //
-            // 1. Not a scalar
-            // 2. Scalar and resolvable
-            (false, _) | (true, true) =>
+            // Remove any non-specific tags from scalars, they'll just
+            // confuse callers
+            if plain.is_some()
             {
-                if tags.get(handle).is_none()
-                {
-                    return Err(Error::UndefinedTag);
-                }
-            },
-            // Remove any non-resolvable tags from scalars, they'll just confuse callers
-            (true, false) => *tag = None,
-        }
-    }
+                *tag = None;
+            }
 
-    Ok(())
+            Ok(ResolvedTag::Exclamation)
+        },
+        // Verbatim tags have no handle, and are never expanded
+        ("", _) => Ok(ResolvedTag::Specific(suffix.clone())),
+        // Otherwise the handle must exist in the directives
+        (_, _) => match tags.get(handle)
+        {
+            Some(prefix) => Ok(ResolvedTag::Specific(Slice::Owned(format!(
+                "{}{}",
+                prefix, suffix
+            )))),
+            None => Err(Error::UndefinedTag),
+        },
+    }
 }
 
 fn tags_to_owned<'a>((handle, prefix): (&Slice<'a>, &Slice<'a>))
@@ -1352,6 +1385,28 @@ mod tests
 
     type TestResult<'a> = Result<Event<'a>>;
 
+    /// The tag a node with .tag should resolve to under the
+    /// default tag directives
+    fn expected_tag(
+        tag: &Option<(Slice<'static>, Slice<'static>)>,
+        plain: bool,
+    ) -> ResolvedTag<'static>
+    {
+        match tag
+        {
+            None if plain => ResolvedTag::Question,
+            None => ResolvedTag::Exclamation,
+            Some((handle, suffix)) =>
+            {
+                let (_, prefix) = array_iterator(DEFAULT_TAGS)
+                    .find(|(default, _)| default == handle)
+                    .expect("a default tag handle");
+
+                ResolvedTag::Specific(format!("{}{}", prefix, suffix).into())
+            },
+        }
+    }
+
     struct ParseIter<'de>
     {
         tokens: Tokens<'de, BorrowReader<'de>>,
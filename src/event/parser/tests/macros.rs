@@ -5,7 +5,7 @@
  */
 
 /// Generate a PeekQueue instance from the given .tokens.
-/// Note that the returned TokenEntry's read_at will be 0.
+/// Note that the returned TokenEntry's span will be empty.
 ///
 /// Usage:
 ///     /1 +[ .token, ...]
@@ -16,7 +16,7 @@ macro_rules! tokens {
 
         let tokens = vec![ $( $token ),+ ]
             .into_iter()
-            .map(|token| TokenEntry::new(token, 0));
+            .map(|token| TokenEntry::new(token, Default::default()));
 
         Queue::from_iter(tokens)
     }};
@@ -48,7 +48,7 @@ macro_rules! event {
         use types::{Event, EventData, self};
 
         let (start, end) = event!(@marks $($start ,)? 0 => $($end ,)? 0);
-        Event::new(start, end, event!(@type $args))
+        Event::new(types::Span::new(types::Mark::new(start, 0, 0), types::Mark::new(end, 0, 0)), event!(@type $args))
     }};
 
     (@type {StreamStart $( $encoding:expr )? }) => {
@@ -88,23 +88,33 @@ macro_rules! event {
         EventData::Scalar($scalar)
     };
     (@type {MappingStart @$kind:tt $(& $anchor:expr ,)? $(@ $handle:expr, $suffix:expr)? }) => {
-        EventData::MappingStart(types::Node {
-            anchor: event!(@option $( Some($anchor.into()) ,)? None),
-            tag: event!(@option $( Some(($handle.into(), $suffix.into())) ,)? None),
-            content: types::Mapping,
-            kind: event!(@kind $kind),
-        })
+        {
+            let tag = event!(@option $( Some(($handle.into(), $suffix.into())) ,)? None);
+
+            EventData::MappingStart(types::Node {
+                anchor: event!(@option $( Some($anchor.into()) ,)? None),
+                resolved: expected_tag(&tag, true),
+                tag,
+                content: types::Mapping,
+                kind: event!(@kind $kind),
+            })
+        }
     };
     (@type {MappingEnd}) => {
         EventData::MappingEnd
     };
     (@type {SequenceStart @$kind:tt $(& $anchor:expr ,)? $(@ $handle:expr, $suffix:expr)? }) => {
-        EventData::SequenceStart(types::Node {
-            anchor: event!(@option $( Some($anchor.into()) ,)? None),
-            tag: event!(@option $( Some(($handle.into(), $suffix.into())) ,)? None),
-            content: types::Sequence,
-            kind: event!(@kind $kind),
-        })
+        {
+            let tag = event!(@option $( Some(($handle.into(), $suffix.into())) ,)? None);
+
+            EventData::SequenceStart(types::Node {
+                anchor: event!(@option $( Some($anchor.into()) ,)? None),
+                resolved: expected_tag(&tag, true),
+                tag,
+                content: types::Sequence,
+                kind: event!(@kind $kind),
+            })
+        }
     };
     (@type {SequenceEnd}) => {
         EventData::SequenceEnd
@@ -136,43 +146,35 @@ macro_rules! event {
 ///         := /1 .content, @ .kind & None, @ None
 macro_rules! node {
     ($content:expr, @$kind:tt) => {
-        types::Node {
-            anchor:  None,
-            tag:     None,
-            content: $content,
-            kind: node!(@kind $kind),
-        }
+        node!(@node $content, @$kind, None, None)
     };
     ($content:expr, @$kind:tt @ $handle:expr, $suffix:expr) => {
-        types::Node {
-            anchor:  None,
-            tag:     Some((
-                $crate::token::Slice::from($handle),
-                $crate::token::Slice::from($suffix),
-            )),
-            content: $content,
-            kind: node!(@kind $kind),
-        }
+        node!(@node $content, @$kind, None, Some((
+            $crate::token::Slice::from($handle),
+            $crate::token::Slice::from($suffix),
+        )))
     };
     ($content:expr, @$kind:tt & $alias:expr) => {
-        types::Node {
-            anchor:  Some($crate::token::Slice::from($alias)),
-            tag:     None,
-            content: $content,
-            kind: node!(@kind $kind),
-        }
+        node!(@node $content, @$kind, Some($crate::token::Slice::from($alias)), None)
     };
     ($content:expr, @$kind:tt & $alias:expr, @ $handle:expr, $suffix:expr) => {
+        node!(@node $content, @$kind, Some($crate::token::Slice::from($alias)), Some((
+            $crate::token::Slice::from($handle),
+            $crate::token::Slice::from($suffix),
+        )))
+    };
+
+    (@node $content:expr, @$kind:tt, $anchor:expr, $tag:expr) => {{
+        let (content, tag): (types::ScalarLike, _) = ($content, $tag);
+
         types::Node {
-            anchor:  Some($crate::token::Slice::from($alias)),
-            tag:     Some((
-                $crate::token::Slice::from($handle),
-                $crate::token::Slice::from($suffix),
-            )),
-            content: $content,
+            anchor:   $anchor,
+            resolved: expected_tag(&tag, content.is_plain()),
+            tag,
+            content,
             kind: node!(@kind $kind),
         }
-    };
+    }};
 
     (@kind Root) => { types::NodeKind::Root };
     (@kind Entry) => { types::NodeKind::Entry };
@@ -249,8 +251,6 @@ macro_rules! events {
        if let Err(e) = __events(ParseIter::new(iter)) {
            panic!("events! error: {}", e)
        }
-
-       ()
     }};
 
 
@@ -0,0 +1,639 @@
+/*
+ * This Source Code Form is subject to the terms of the
+ * Mozilla Public License, v. 2.0. If a copy of the MPL
+ * was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+//! This module exposes [`Paths`], an [`Event`] adapter
+//! which pairs each event with the [`Path`] of the node it
+//! belongs to, relative to its document's root.
+//!
+//! Paths are displayed as [JSON Pointers], for example
+//! `/spec/containers/0/image`, and are built from the
+//! [`NodeKind`] of each node and the content of scalar
+//! keys:
+//!
+//! - Sequence entries are identified by their index
+//! - Mapping values, and their keys, are identified by the
+//!   key's content, with `~` and `/` escaped as `~0` and
+//!   `~1` respectively
+//! - Non scalar keys (collections and aliases) have no
+//!   textual form, so they are instead identified by the
+//!   index of their entry in the mapping, displayed as
+//!   `~{index}`. As `~{` is not a valid JSON Pointer
+//!   escape, this can never be confused with a scalar key
+//!
+//! Collection start and end events carry the path of the
+//! collection, and document and stream events carry the
+//! empty (root) path. Every event inside a non scalar key
+//! carries the path of the key's entry.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use yary::{
+//!     event::{from_reader, path::paths, types::EventData},
+//!     reader::borrow::BorrowReader,
+//! };
+//!
+//! let yaml = BorrowReader::new("spec: {containers: [{image: nginx}]}");
+//!
+//! let path = paths(from_reader(&yaml))
+//!     .filter_map(Result::ok)
+//!     .filter(|(_, event)| matches!(event.data(), EventData::Scalar(_)))
+//!     .map(|(path, _)| path.to_string())
+//!     .last();
+//!
+//! assert_eq!(path.as_deref(), Some("/spec/containers/0/image"));
+//! ```
+//!
+//! [JSON Pointers]: https://datatracker.ietf.org/doc/html/rfc6901
+//! [`NodeKind`]: enum@super::types::NodeKind
+
+use std::fmt;
+
+use crate::{
+    error::Result,
+    event::types::{Event, EventData},
+};
+
+/// Wraps the given events, pairing each with its [`Path`].
+///
+/// See the [module](self) documentation for more
+/// information.
+pub fn paths<'de, I>(events: I) -> Paths<I::IntoIter>
+where
+    I: IntoIterator<Item = Result<Event<'de>>>,
+{
+    Paths::new(events.into_iter())
+}
+
+/// The location of a node in a YAML document, relative to
+/// the document's root.
+///
+/// The [`Display`](fmt::Display) implementation of this
+/// type produces a JSON Pointer, see the [module](self)
+/// documentation for more information.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Path
+{
+    segments: Vec<Segment>,
+}
+
+impl Path
+{
+    /// Instantiate a new, root [`Path`]
+    pub fn new() -> Self
+    {
+        Self::default()
+    }
+
+    /// The segments of this path, from the root down
+    pub fn segments(&self) -> &[Segment]
+    {
+        &self.segments
+    }
+
+    /// Check whether this path refers to the document root
+    pub fn is_root(&self) -> bool
+    {
+        self.segments.is_empty()
+    }
+
+    /// Create a new path, with the given .segment appended
+    /// to this one
+    pub fn join(&self, segment: Segment) -> Self
+    {
+        let mut this = self.clone();
+        this.segments.push(segment);
+
+        this
+    }
+}
+
+impl fmt::Display for Path
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        self.segments
+            .iter()
+            .try_for_each(|segment| write!(f, "/{}", segment))
+    }
+}
+
+/// A single step in a [`Path`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Segment
+{
+    /// A mapping value, identified by the content of its
+    /// scalar key
+    Key(String),
+    /// A sequence entry, identified by its index
+    Index(usize),
+    /// A mapping value whose key is not a scalar,
+    /// identified by the index of its entry in the mapping
+    Complex(usize),
+}
+
+impl fmt::Display for Segment
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
+        {
+            Segment::Key(key) => key.chars().try_for_each(|c| match c
+            {
+                '~' => f.write_str("~0"),
+                '/' => f.write_str("~1"),
+                c => fmt::Write::write_char(f, c),
+            }),
+            Segment::Index(index) => write!(f, "{}", index),
+            Segment::Complex(entry) => write!(f, "~{{{}}}", entry),
+        }
+    }
+}
+
+/// An [`Event`] adapter which pairs each event with the
+/// [`Path`] of the node it belongs to.
+///
+/// Any lazy scalar keys are evaluated in order to build
+/// their [`Segment`], which may error. Iteration stops at
+/// the first error, this iterator is fused.
+#[derive(Debug)]
+pub struct Paths<I>
+{
+    events: I,
+    /// Stack of open collections
+    levels: Vec<Level>,
+    done:   bool,
+}
+
+impl<'de, I> Paths<I>
+where
+    I: Iterator<Item = Result<Event<'de>>>,
+{
+    /// Instantiate a new [`Paths`] adapter over the given
+    /// events
+    pub fn new(events: I) -> Self
+    {
+        Self {
+            events,
+            levels: Vec::new(),
+            done: false,
+        }
+    }
+
+    /// Consume the adapter, returning the underlying
+    /// events
+    pub fn into_inner(self) -> I
+    {
+        self.events
+    }
+
+    fn next_event(&mut self) -> Option<Result<(Path, Event<'de>)>>
+    {
+        let mut event = match self.events.next()?
+        {
+            Ok(event) => event,
+            Err(e) => return Some(Err(e)),
+        };
+
+        Some(self.path_of(&mut event).map(|path| (path, event)))
+    }
+
+    /// Determine the path of .event, updating the open
+    /// collections
+    fn path_of(&mut self, event: &mut Event<'de>) -> Result<Path>
+    {
+        let span = event.span();
+        let mapping = matches!(event.data(), EventData::MappingStart(_));
+
+        let path = match event.data_mut()
+        {
+            EventData::DocumentStart(_) | EventData::DocumentEnd(_) =>
+            {
+                self.levels.clear();
+
+                Path::new()
+            },
+            EventData::MappingStart(_) | EventData::SequenceStart(_) =>
+            {
+                let path = self.next_node(None);
+
+                let level = match self.levels.last()
+                {
+                    Some(Level::Key { .. }) | Some(Level::InKey { .. }) =>
+                    {
+                        Level::InKey { path: path.clone() }
+                    },
+                    _ if mapping => Level::Mapping {
+                        path:    path.clone(),
+                        entries: 0,
+                        key:     None,
+                    },
+                    _ => Level::Sequence {
+                        path:    path.clone(),
+                        entries: 0,
+                    },
+                };
+                self.levels.push(level);
+
+                path
+            },
+            EventData::MappingEnd | EventData::SequenceEnd =>
+            {
+                let path = match self.levels.pop()
+                {
+                    Some(level) => level.into_path(),
+                    None => Path::new(),
+                };
+
+                // A complex key has finished, its value follows
+                if let Some(Level::Key { .. }) = self.levels.last()
+                {
+                    self.end_key();
+                }
+
+                path
+            },
+            EventData::Scalar(node) =>
+            {
+                let key = match self.levels.last()
+                {
+                    Some(Level::Mapping { key: None, .. }) =>
+                    {
+                        let content = node
+                            .content
+                            .evaluate_by_ref()
+                            .map_err(|e| e.with_mark(span.start))?;
+
+                        Some(content.data().to_string())
+                    },
+                    _ => None,
+                };
+
+                self.next_node(Some(key))
+            },
+            EventData::Alias(_) => self.next_node(Some(None)),
+            _ => self
+                .levels
+                .last()
+                .map_or_else(Path::new, |level| level.path().clone()),
+        };
+
+        Ok(path)
+    }
+
+    /// Determine the path of the next node in the current
+    /// collection.
+    ///
+    /// Leaf nodes should provide their key content (if any)
+    /// as .leaf, while collections provide None.
+    fn next_node(&mut self, leaf: Option<Option<String>>) -> Path
+    {
+        let level = match self.levels.last_mut()
+        {
+            Some(level) => level,
+            None => return Path::new(),
+        };
+
+        match level
+        {
+            Level::Sequence { path, entries } =>
+            {
+                *entries += 1;
+
+                path.join(Segment::Index(*entries - 1))
+            },
+            Level::Mapping { path, entries, key } => match (key.take(), leaf)
+            {
+                // Mapping value
+                (Some(segment), _) =>
+                {
+                    *entries += 1;
+
+                    path.join(segment)
+                },
+                // Scalar key, await its value
+                (None, Some(Some(content))) =>
+                {
+                    let segment = Segment::Key(content);
+                    *key = Some(segment.clone());
+
+                    path.join(segment)
+                },
+                // Alias key, await its value
+                (None, Some(None)) =>
+                {
+                    let segment = Segment::Complex(*entries);
+                    *key = Some(segment.clone());
+
+                    path.join(segment)
+                },
+                // Collection key, await the end of the key
+                (None, None) =>
+                {
+                    let segment = Segment::Complex(*entries);
+                    let path = path.join(segment.clone());
+
+                    *level = Level::Key {
+                        parent: Box::new(level.clone()),
+                        segment,
+                    };
+
+                    path
+                },
+            },
+            Level::Key { parent, segment } => parent.path().join(segment.clone()),
+            Level::InKey { path } => path.clone(),
+        }
+    }
+
+    /// Finish the complex key at the top of the stack,
+    /// awaiting its value
+    fn end_key(&mut self)
+    {
+        if let Some(level) = self.levels.last_mut()
+        {
+            if let Level::Key { parent, segment } = level
+            {
+                let mut parent = (**parent).clone();
+
+                if let Level::Mapping { key, .. } = &mut parent
+                {
+                    *key = Some(segment.clone());
+                }
+
+                *level = parent;
+            }
+        }
+    }
+}
+
+impl<'de, I> Iterator for Paths<I>
+where
+    I: Iterator<Item = Result<Event<'de>>>,
+{
+    type Item = Result<(Path, Event<'de>)>;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        if self.done
+        {
+            return None;
+        }
+
+        let next = self.next_event();
+
+        if matches!(next, None | Some(Err(_)))
+        {
+            self.done = true;
+        }
+
+        next
+    }
+}
+
+impl<'de, I> std::iter::FusedIterator for Paths<I> where I: Iterator<Item = Result<Event<'de>>> {}
+
+/// An open collection
+#[derive(Debug, Clone)]
+enum Level
+{
+    Sequence
+    {
+        path: Path, entries: usize
+    },
+    Mapping
+    {
+        path:    Path,
+        entries: usize,
+        /// The segment of the key awaiting its value, if
+        /// any
+        key:     Option<Segment>,
+    },
+    /// A mapping whose current key is a collection, which
+    /// has not yet ended
+    Key
+    {
+        parent:  Box<Level>,
+        segment: Segment,
+    },
+    /// A collection nested inside of a complex key
+    InKey
+    {
+        path: Path
+    },
+}
+
+impl Level
+{
+    fn path(&self) -> &Path
+    {
+        match self
+        {
+            Level::Sequence { path, .. } | Level::Mapping { path, .. } | Level::InKey { path } =>
+            {
+                path
+            },
+            Level::Key { parent, .. } => parent.path(),
+        }
+    }
+
+    fn into_path(self) -> Path
+    {
+        match self
+        {
+            Level::Sequence { path, .. } | Level::Mapping { path, .. } | Level::InKey { path } =>
+            {
+                path
+            },
+            Level::Key { parent, .. } => parent.into_path(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        event::{flag::O_LAZY, from_reader, from_reader_with},
+        reader::borrow::BorrowReader,
+    };
+
+    /// The (path, event kind) of every event in .data
+    fn paths_of(data: &str) -> Vec<(String, &'static str)>
+    {
+        let yaml = BorrowReader::new(data);
+
+        paths(from_reader(&yaml))
+            .map(Result::unwrap)
+            .map(|(path, event)| (path.to_string(), name(event.data())))
+            .collect()
+    }
+
+    fn name(data: &EventData<'_>) -> &'static str
+    {
+        match data
+        {
+            EventData::StreamStart(_) => "StreamStart",
+            EventData::StreamEnd => "StreamEnd",
+            EventData::DocumentStart(_) => "DocumentStart",
+            EventData::DocumentEnd(_) => "DocumentEnd",
+            EventData::Alias(_) => "Alias",
+            EventData::Scalar(_) => "Scalar",
+            EventData::MappingStart(_) => "MappingStart",
+            EventData::MappingEnd => "MappingEnd",
+            EventData::SequenceStart(_) => "SequenceStart",
+            EventData::SequenceEnd => "SequenceEnd",
+            EventData::Comment(_) => "Comment",
+        }
+    }
+
+    fn expected(paths: &[(&str, &'static str)]) -> Vec<(String, &'static str)>
+    {
+        paths.iter().map(|(p, e)| (p.to_string(), *e)).collect()
+    }
+
+    #[test]
+    fn nested()
+    {
+        let data = "spec:\n  containers:\n    - image: nginx\n      ports: [80, 443]\n";
+
+        assert_eq!(
+            paths_of(data),
+            expected(&[
+                ("", "StreamStart"),
+                ("", "DocumentStart"),
+                ("", "MappingStart"),
+                ("/spec", "Scalar"),
+                ("/spec", "MappingStart"),
+                ("/spec/containers", "Scalar"),
+                ("/spec/containers", "SequenceStart"),
+                ("/spec/containers/0", "MappingStart"),
+                ("/spec/containers/0/image", "Scalar"),
+                ("/spec/containers/0/image", "Scalar"),
+                ("/spec/containers/0/ports", "Scalar"),
+                ("/spec/containers/0/ports", "SequenceStart"),
+                ("/spec/containers/0/ports/0", "Scalar"),
+                ("/spec/containers/0/ports/1", "Scalar"),
+                ("/spec/containers/0/ports", "SequenceEnd"),
+                ("/spec/containers/0", "MappingEnd"),
+                ("/spec/containers", "SequenceEnd"),
+                ("/spec", "MappingEnd"),
+                ("", "MappingEnd"),
+                ("", "DocumentEnd"),
+                ("", "StreamEnd"),
+            ])
+        );
+    }
+
+    #[test]
+    fn escaped_keys()
+    {
+        let data = "{a/b: 1, ~c: 2, '': 3}";
+
+        let scalars: Vec<_> = paths_of(data)
+            .into_iter()
+            .filter(|(_, e)| *e == "Scalar")
+            .map(|(p, _)| p)
+            .collect();
+
+        assert_eq!(scalars, vec!["/a~1b", "/a~1b", "/~0c", "/~0c", "/", "/"]);
+    }
+
+    #[test]
+    fn complex_keys()
+    {
+        let data = "base: &x k\n? [a, b]\n: 1\n*x : 2\n? {c: d}\n: 3\ne: 4\n";
+
+        assert_eq!(
+            paths_of(data)[2..],
+            expected(&[
+                ("", "MappingStart"),
+                ("/base", "Scalar"),
+                ("/base", "Scalar"),
+                ("/~{1}", "SequenceStart"),
+                ("/~{1}", "Scalar"),
+                ("/~{1}", "Scalar"),
+                ("/~{1}", "SequenceEnd"),
+                ("/~{1}", "Scalar"),
+                ("/~{2}", "Alias"),
+                ("/~{2}", "Scalar"),
+                ("/~{3}", "MappingStart"),
+                ("/~{3}", "Scalar"),
+                ("/~{3}", "Scalar"),
+                ("/~{3}", "MappingEnd"),
+                ("/~{3}", "Scalar"),
+                ("/e", "Scalar"),
+                ("/e", "Scalar"),
+                ("", "MappingEnd"),
+                ("", "DocumentEnd"),
+                ("", "StreamEnd"),
+            ])[..]
+        );
+    }
+
+    #[test]
+    fn documents_reset()
+    {
+        let data = "--- [a]\n--- {b: c}\n";
+
+        let scalars: Vec<_> = paths_of(data)
+            .into_iter()
+            .filter(|(_, e)| *e == "Scalar")
+            .map(|(p, _)| p)
+            .collect();
+
+        assert_eq!(scalars, vec!["/0", "/b", "/b"]);
+    }
+
+    #[test]
+    fn lazy_keys()
+    {
+        let yaml = BorrowReader::new("{\"a\\tb\": c}");
+
+        let paths: Vec<_> = paths(from_reader_with(&yaml, O_LAZY))
+            .map(|r| r.unwrap().0.to_string())
+            .collect();
+
+        assert!(paths.contains(&"/a\tb".to_string()));
+    }
+
+    #[test]
+    fn fused_after_error()
+    {
+        let invalid = BorrowReader::new("[a}");
+        let error = from_reader(&invalid)
+            .into_iter()
+            .find_map(Result::err)
+            .expect("an error");
+
+        let yaml = BorrowReader::new("[a, b]");
+        let mut events: Vec<_> = from_reader(&yaml).into_iter().collect();
+        events.insert(4, Err(error));
+
+        let mut paths = paths(events);
+
+        assert!(paths.by_ref().take(4).all(|r| r.is_ok()));
+        assert!(matches!(paths.next(), Some(Err(_))));
+        assert!(paths.next().is_none());
+        assert!(paths.next().is_none());
+    }
+
+    #[test]
+    fn segments()
+    {
+        let path = Path::new()
+            .join(Segment::Key("spec".into()))
+            .join(Segment::Index(0))
+            .join(Segment::Complex(1));
+
+        assert!(Path::new().is_root());
+        assert_eq!(path.segments().len(), 3);
+        assert_eq!(path.to_string(), "/spec/0/~{1}");
+    }
+}
@@ -0,0 +1,941 @@
+/*
+ * This Source Code Form is subject to the terms of the
+ * Mozilla Public License, v. 2.0. If a copy of the MPL
+ * was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+//! This module exposes [`Select`], which runs a [`Query`]
+//! over an [`Events`] stream, returning the events of each
+//! matching node.
+//!
+//! Queries are a small subset of jq and JSONPath, made up
+//! of the following steps, applied in order from the
+//! document root:
+//!
+//! ```text
+//! .key            => the value of mapping key 'key'
+//! ."key"          => as above, allowing any character
+//! ["key"]         => as above
+//! [2]             => the entry at index 2 of a sequence
+//! .*  [*]  []     => every mapping value or sequence entry
+//! .               => the document root, on its own
+//! ```
+//!
+//! An optional leading `$` is ignored, and `.` may precede
+//! a bracketed step, so `.items[*].spec`, `.items.[].spec`
+//! and `$.items[*].spec` are all equivalent.
+//!
+//! The query is applied to each document in the stream in
+//! turn. Nodes which cannot match are skipped with
+//! [`Events::skip_node`], so when the [`Events`] were
+//! created with [`O_LAZY`] only the scalars of matching
+//! nodes, and of any mapping keys along the way, are ever
+//! evaluated. Aliases are not expanded, a matching alias
+//! is returned as is.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use yary::{
+//!     event::{
+//!         flag::O_LAZY,
+//!         from_reader_with,
+//!         query::{select, Query},
+//!         types::{EventData, ScalarLike},
+//!     },
+//!     reader::borrow::BorrowReader,
+//! };
+//!
+//! let yaml = BorrowReader::new("items:\n- {spec: {replicas: 2}}\n- {spec: {replicas: 3}}\n");
+//! let events = from_reader_with(&yaml, O_LAZY);
+//! let query: Query = ".items[*].spec.replicas".parse().unwrap();
+//!
+//! let replicas: Vec<_> = select(events, &query)
+//!     .map(|m| m.unwrap())
+//!     .map(|m| match m.events()[0].data()
+//!     {
+//!         EventData::Scalar(node) => match &node.content
+//!         {
+//!             ScalarLike::Eager(scalar) => scalar.data().to_string(),
+//!             ScalarLike::Lazy(_) => unreachable!("matches are evaluated"),
+//!         },
+//!         _ => unreachable!(),
+//!     })
+//!     .collect();
+//!
+//! assert_eq!(replicas, ["2", "3"]);
+//! ```
+//!
+//! [`O_LAZY`]: super::flag::O_LAZY
+
+use std::{fmt, str::FromStr};
+
+use crate::{
+    error::{internal::ErrorCode, mkError, Error, Result},
+    event::{
+        nesting,
+        path::{Path, Segment},
+        types::{Event, EventData, Mark},
+        Events,
+    },
+    reader::Read,
+};
+
+/// Run the given .query over .events, returning the events
+/// of every node it matches.
+///
+/// See the [module](self) documentation for more
+/// information.
+pub fn select<'de, R>(events: Events<'de, R>, query: &Query) -> Select<'de, R>
+where
+    R: Read,
+{
+    Select::new(events, query.clone())
+}
+
+/// A parsed query expression.
+///
+/// The [`Display`](fmt::Display) implementation of this
+/// type produces an expression which parses back to the
+/// same [`Query`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Query
+{
+    selectors: Vec<Selector>,
+}
+
+impl Query
+{
+    /// Parse the given query .expr, see the
+    /// [module](self) documentation for the syntax
+    /// accepted.
+    ///
+    /// The returned error's column refers to the byte in
+    /// .expr at which parsing failed.
+    pub fn parse(expr: &str) -> Result<Self>
+    {
+        let mut parser = QueryParser::new(expr);
+        let mut selectors = Vec::new();
+
+        parser.eat(b'$');
+        if parser.rest() == "."
+        {
+            parser.pos += 1;
+        }
+
+        while !parser.is_empty()
+        {
+            selectors.push(parser.selector()?);
+        }
+
+        Ok(Self { selectors })
+    }
+
+    /// The selectors of this query, from the root down
+    pub fn selectors(&self) -> &[Selector]
+    {
+        &self.selectors
+    }
+}
+
+impl FromStr for Query
+{
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self>
+    {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for Query
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        if self.selectors.is_empty()
+        {
+            return f.write_str(".");
+        }
+
+        self.selectors
+            .iter()
+            .try_for_each(|selector| write!(f, "{}", selector))
+    }
+}
+
+/// A single step in a [`Query`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Selector
+{
+    /// The value of the mapping key with this content
+    Key(String),
+    /// The sequence entry at this index
+    Index(usize),
+    /// Every mapping value or sequence entry
+    Wildcard,
+}
+
+impl Selector
+{
+    /// Check whether this selector matches the node at
+    /// .segment
+    fn matches(&self, segment: &Segment) -> bool
+    {
+        match (self, segment)
+        {
+            (Selector::Wildcard, _) => true,
+            (Selector::Key(key), Segment::Key(other)) => key == other,
+            (Selector::Index(index), Segment::Index(other)) => index == other,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Selector
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
+        {
+            Selector::Key(key) if is_bare(key) => write!(f, ".{}", key),
+            Selector::Key(key) =>
+            {
+                f.write_str(".\"")?;
+                key.chars().try_for_each(|c| match c
+                {
+                    '"' | '\\' => write!(f, "\\{}", c),
+                    c => fmt::Write::write_char(f, c),
+                })?;
+                f.write_str("\"")
+            },
+            Selector::Index(index) => write!(f, "[{}]", index),
+            Selector::Wildcard => f.write_str("[*]"),
+        }
+    }
+}
+
+/// A node matched by a [`Query`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Match<'de>
+{
+    path:   Path,
+    events: Vec<Event<'de>>,
+}
+
+impl<'de> Match<'de>
+{
+    /// The [`Path`] of the matched node
+    pub fn path(&self) -> &Path
+    {
+        &self.path
+    }
+
+    /// The events making up the matched node, every scalar
+    /// of which has been evaluated
+    pub fn events(&self) -> &[Event<'de>]
+    {
+        &self.events
+    }
+
+    /// Retrieve the matched node's [`Path`] and events
+    pub fn into_parts(self) -> (Path, Vec<Event<'de>>)
+    {
+        (self.path, self.events)
+    }
+}
+
+/// An iterator over the nodes of an [`Events`] stream
+/// matched by a [`Query`].
+///
+/// See the [module](self) documentation for more
+/// information.
+#[derive(Debug)]
+pub struct Select<'de, R>
+{
+    events: Events<'de, R>,
+    query:  Query,
+    /// Stack of open collections that the query may still
+    /// match inside of
+    stack:  Vec<Frame>,
+    done:   bool,
+}
+
+impl<'de, R> Select<'de, R>
+where
+    R: Read,
+{
+    /// Instantiate a new [`Select`] running .query over
+    /// .events
+    pub fn new(events: Events<'de, R>, query: Query) -> Self
+    {
+        Self {
+            events,
+            query,
+            stack: Vec::new(),
+            done: false,
+        }
+    }
+
+    /// Retrieve the underlying [`Events`], discarding this
+    /// iterator
+    pub fn into_inner(self) -> Events<'de, R>
+    {
+        self.events
+    }
+
+    /// Find the next node matching our query
+    fn next_match(&mut self) -> Result<Option<Match<'de>>>
+    {
+        loop
+        {
+            let (path, event) = match self.step()?
+            {
+                Step::Node(path, event) => (path, event),
+                Step::Skipped => continue,
+                Step::End => return Ok(None),
+            };
+
+            // Each selector consumes one path segment
+            if path.segments().len() == self.query.selectors.len()
+            {
+                return self.collect(path, event).map(Some);
+            }
+
+            match event.data()
+            {
+                EventData::MappingStart(_) => self.stack.push(Frame::Mapping { path, entries: 0 }),
+                EventData::SequenceStart(_) =>
+                {
+                    self.stack.push(Frame::Sequence { path, entries: 0 })
+                },
+                _ =>
+                {},
+            }
+        }
+    }
+
+    /// Advance past the next node in the innermost open
+    /// collection, or at the document root
+    fn step(&mut self) -> Result<Step<'de>>
+    {
+        let Self {
+            events,
+            query,
+            stack,
+            ..
+        } = self;
+
+        let frame = match stack.last_mut()
+        {
+            Some(frame) => frame,
+            None =>
+            {
+                return Ok(match pull(events)?
+                {
+                    Some(event) if is_node(&event) => Step::Node(Path::new(), event),
+                    Some(_) => Step::Skipped,
+                    None => Step::End,
+                })
+            },
+        };
+
+        match frame
+        {
+            Frame::Sequence { path, entries } =>
+            {
+                let selector = &query.selectors[path.segments().len()];
+                let segment = Segment::Index(*entries);
+
+                // Skipping returns None at the end of the sequence,
+                // which we must then consume
+                if !selector.matches(&segment) && events.skip_node()?.is_some()
+                {
+                    *entries += 1;
+
+                    return Ok(Step::Skipped);
+                }
+
+                match pull(events)?
+                {
+                    Some(event) if is_node(&event) =>
+                    {
+                        *entries += 1;
+
+                        Ok(Step::Node(path.join(segment), event))
+                    },
+                    Some(_) =>
+                    {
+                        stack.pop();
+
+                        Ok(Step::Skipped)
+                    },
+                    None => Ok(Step::End),
+                }
+            },
+            Frame::Mapping { path, entries } =>
+            {
+                let selector = &query.selectors[path.segments().len()];
+
+                let mut key = match pull(events)?
+                {
+                    Some(event) if is_node(&event) => event,
+                    Some(_) =>
+                    {
+                        stack.pop();
+
+                        return Ok(Step::Skipped);
+                    },
+                    None => return Ok(Step::End),
+                };
+
+                let start = key.span().start;
+                let segment = match key.data_mut()
+                {
+                    EventData::Scalar(node) =>
+                    {
+                        let content = node
+                            .content
+                            .evaluate_by_ref()
+                            .map_err(|e| e.with_mark(start))?;
+
+                        Segment::Key(content.data().to_string())
+                    },
+                    _ =>
+                    {
+                        discard(events, key)?;
+
+                        Segment::Complex(*entries)
+                    },
+                };
+                *entries += 1;
+
+                if !selector.matches(&segment)
+                {
+                    events.skip_node()?;
+
+                    return Ok(Step::Skipped);
+                }
+
+                Ok(match pull(events)?
+                {
+                    Some(value) => Step::Node(path.join(segment), value),
+                    None => Step::End,
+                })
+            },
+        }
+    }
+
+    /// Gather the events of the node starting with .event,
+    /// evaluating every scalar
+    fn collect(&mut self, path: Path, mut event: Event<'de>) -> Result<Match<'de>>
+    {
+        let mut depth = nested(&event);
+
+        evaluate(&mut event)?;
+        let mut events = vec![event];
+
+        while depth > 0
+        {
+            let mut event = match pull(&mut self.events)?
+            {
+                Some(event) => event,
+                None => break,
+            };
+
+            depth = match event.data()
+            {
+                EventData::MappingEnd | EventData::SequenceEnd => depth - 1,
+                _ => depth + nested(&event),
+            };
+
+            evaluate(&mut event)?;
+            events.push(event);
+        }
+
+        Ok(Match { path, events })
+    }
+}
+
+impl<'de, R> Iterator for Select<'de, R>
+where
+    R: Read,
+{
+    type Item = Result<Match<'de>>;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        if self.done
+        {
+            return None;
+        }
+
+        let next = self.next_match().transpose();
+
+        if !matches!(next, Some(Ok(_)))
+        {
+            self.done = true;
+        }
+
+        next
+    }
+}
+
+/// An open collection that a [`Query`] may still match
+/// inside of
+#[derive(Debug)]
+enum Frame
+{
+    Mapping
+    {
+        path: Path, entries: usize
+    },
+    Sequence
+    {
+        path: Path, entries: usize
+    },
+}
+
+/// The result of advancing a [`Select`] by one node
+// Only ever lives on the stack, so its size is unimportant
+#[allow(clippy::large_enum_variant)]
+enum Step<'de>
+{
+    /// A node which the next selector matched
+    Node(Path, Event<'de>),
+    /// Anything else, which has been consumed
+    Skipped,
+    /// The end of the stream
+    End,
+}
+
+/// Retrieve the next non comment event
+fn pull<'de, R>(events: &mut Events<'de, R>) -> Result<Option<Event<'de>>>
+where
+    R: Read,
+{
+    for event in events.iter()
+    {
+        let event = event?;
+
+        if !matches!(event.data(), EventData::Comment(_))
+        {
+            return Ok(Some(event));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Consume the remainder of the node started by .event
+fn discard<'de, R>(events: &mut Events<'de, R>, event: Event<'de>) -> Result<()>
+where
+    R: Read,
+{
+    let mut depth = nested(&event);
+
+    while depth > 0
+    {
+        depth = match pull(events)?.as_ref().map(Event::data)
+        {
+            Some(EventData::MappingEnd) | Some(EventData::SequenceEnd) => depth - 1,
+            Some(EventData::MappingStart(_)) | Some(EventData::SequenceStart(_)) => depth + 1,
+            Some(_) => depth,
+            None => break,
+        };
+    }
+
+    Ok(())
+}
+
+/// Evaluate .event's scalar, if it has one
+fn evaluate(event: &mut Event<'_>) -> Result<()>
+{
+    let start = event.span().start;
+
+    if let EventData::Scalar(node) = event.data_mut()
+    {
+        node.content
+            .evaluate_by_ref()
+            .map_err(|e| e.with_mark(start))?;
+    }
+
+    Ok(())
+}
+
+/// Check whether .event begins a node
+fn is_node(event: &Event<'_>) -> bool
+{
+    nesting(event.data()).is_some()
+}
+
+/// The change in nesting depth caused by .event
+fn nested(event: &Event<'_>) -> usize
+{
+    nesting(event.data()).unwrap_or(0)
+}
+
+/// Check whether .key can be displayed without quoting
+fn is_bare(key: &str) -> bool
+{
+    !key.is_empty()
+        && key
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-')
+}
+
+/// Parser for [`Query`] expressions
+struct QueryParser<'a>
+{
+    expr: &'a str,
+    pos:  usize,
+}
+
+impl<'a> QueryParser<'a>
+{
+    fn new(expr: &'a str) -> Self
+    {
+        Self { expr, pos: 0 }
+    }
+
+    fn rest(&self) -> &'a str
+    {
+        &self.expr[self.pos..]
+    }
+
+    fn is_empty(&self) -> bool
+    {
+        self.pos >= self.expr.len()
+    }
+
+    fn peek(&self) -> Option<u8>
+    {
+        self.expr.as_bytes().get(self.pos).copied()
+    }
+
+    /// Consume .byte if it is next
+    fn eat(&mut self, byte: u8) -> bool
+    {
+        let found = self.peek() == Some(byte);
+
+        if found
+        {
+            self.pos += 1;
+        }
+
+        found
+    }
+
+    /// An error at the current position
+    fn error(&self) -> Error
+    {
+        let at = Mark::new(self.pos, 0, self.pos);
+
+        mkError!(ErrorCode::InvalidQuery, CODE).with_mark(at)
+    }
+
+    fn selector(&mut self) -> Result<Selector>
+    {
+        if self.eat(b'[')
+        {
+            return self.bracketed();
+        }
+
+        if !self.eat(b'.')
+        {
+            return Err(self.error());
+        }
+
+        match self.peek()
+        {
+            Some(b'[') =>
+            {
+                self.pos += 1;
+
+                self.bracketed()
+            },
+            Some(b'*') =>
+            {
+                self.pos += 1;
+
+                Ok(Selector::Wildcard)
+            },
+            Some(b'"') => self.quoted().map(Selector::Key),
+            _ =>
+            {
+                let start = self.pos;
+                while !matches!(self.peek(), None | Some(b'.') | Some(b'['))
+                {
+                    self.pos += 1;
+                }
+
+                // We only stop at an ASCII byte or the end of the
+                // expression, so this is always a char boundary
+                match self.pos > start
+                {
+                    true => Ok(Selector::Key(self.expr[start..self.pos].to_string())),
+                    false => Err(self.error()),
+                }
+            },
+        }
+    }
+
+    /// Parse the remainder of a `[...]` step
+    fn bracketed(&mut self) -> Result<Selector>
+    {
+        let selector = match self.peek()
+        {
+            Some(b']') => Selector::Wildcard,
+            Some(b'*') =>
+            {
+                self.pos += 1;
+
+                Selector::Wildcard
+            },
+            Some(b'"') => Selector::Key(self.quoted()?),
+            Some(b) if b.is_ascii_digit() =>
+            {
+                let mut index: usize = 0;
+                while let Some(digit) = self.peek().filter(u8::is_ascii_digit)
+                {
+                    index = index
+                        .checked_mul(10)
+                        .and_then(|index| index.checked_add((digit - b'0') as usize))
+                        .ok_or_else(|| self.error())?;
+                    self.pos += 1;
+                }
+
+                Selector::Index(index)
+            },
+            _ => return Err(self.error()),
+        };
+
+        match self.eat(b']')
+        {
+            true => Ok(selector),
+            false => Err(self.error()),
+        }
+    }
+
+    /// Parse a double quoted key, handling `\"` and `\\`
+    /// escapes
+    fn quoted(&mut self) -> Result<String>
+    {
+        let start = self.pos;
+        let mut key = Vec::new();
+
+        // Skip the opening quote
+        self.pos += 1;
+
+        loop
+        {
+            match self.peek()
+            {
+                Some(b'"') =>
+                {
+                    self.pos += 1;
+
+                    break;
+                },
+                Some(b'\\') => match self.expr.as_bytes().get(self.pos + 1)
+                {
+                    Some(&c @ b'"') | Some(&c @ b'\\') =>
+                    {
+                        key.push(c);
+                        self.pos += 2;
+                    },
+                    _ => return Err(self.error()),
+                },
+                Some(b) =>
+                {
+                    key.push(b);
+                    self.pos += 1;
+                },
+                None =>
+                {
+                    self.pos = start;
+
+                    return Err(self.error());
+                },
+            }
+        }
+
+        // Only ASCII escapes were removed, so .key is still
+        // valid UTF8
+        Ok(String::from_utf8_lossy(&key).into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        event::{flag::O_LAZY, from_reader_with, types::ScalarLike},
+        reader::borrow::BorrowReader,
+    };
+
+    /// Run .query over .yaml, rendering each match as its
+    /// path and the source text it covers
+    fn matches(yaml: &str, query: &str) -> Vec<(String, String)>
+    {
+        let reader = BorrowReader::new(yaml);
+        let events = from_reader_with(&reader, O_LAZY);
+        let query = Query::parse(query).expect("a valid query");
+
+        select(events, &query)
+            .map(|m| {
+                let m = m.expect("valid YAML");
+                let (first, last) = (&m.events()[0], &m.events()[m.events().len() - 1]);
+
+                (
+                    m.path().to_string(),
+                    yaml[first.span().start.offset..last.span().end.offset].to_string(),
+                )
+            })
+            .collect()
+    }
+
+    fn m(path: &str, text: &str) -> (String, String)
+    {
+        (path.to_string(), text.to_string())
+    }
+
+    #[test]
+    fn parse()
+    {
+        use Selector::*;
+
+        let selectors = |expr: &str| Query::parse(expr).unwrap().selectors().to_vec();
+
+        assert_eq!(selectors("."), []);
+        assert_eq!(selectors("$"), []);
+        assert_eq!(selectors(".a.b"), [Key("a".into()), Key("b".into())]);
+        assert_eq!(
+            selectors(".items[*].spec[0]"),
+            [Key("items".into()), Wildcard, Key("spec".into()), Index(0)]
+        );
+        assert_eq!(selectors("$.a.[].*"), [Key("a".into()), Wildcard, Wildcard]);
+        assert_eq!(
+            selectors(r#"."a.b"["c\"d"]"#),
+            [Key("a.b".into()), Key("c\"d".into())]
+        );
+        assert_eq!(selectors("[12]"), [Index(12)]);
+    }
+
+    #[test]
+    fn parse_errors()
+    {
+        let column = |expr: &str| {
+            let err = Query::parse(expr).expect_err("an invalid query");
+            assert_eq!(err.kind(), crate::error::ErrorKind::InvalidQuery);
+
+            err.column()
+        };
+
+        assert_eq!(column("a"), 1);
+        assert_eq!(column(".a."), 4);
+        assert_eq!(column(".a[x]"), 4);
+        assert_eq!(column(".a[1"), 5);
+        assert_eq!(column(r#"."a"#), 2);
+        assert_eq!(column("[99999999999999999999999]"), 21);
+    }
+
+    #[test]
+    fn display()
+    {
+        for expr in &[".", ".a[*][3]", r#"."a b"."c\"d""#, ".a-b_c"]
+        {
+            let query = Query::parse(expr).unwrap();
+
+            assert_eq!(&query.to_string(), expr);
+            assert_eq!(Query::parse(&query.to_string()).unwrap(), query);
+        }
+    }
+
+    #[test]
+    fn select_keys()
+    {
+        let yaml = "metadata:\n  name: web\n  labels: {app: web}\nspec: {}\n";
+
+        assert_eq!(
+            matches(yaml, ".metadata.name"),
+            [m("/metadata/name", "web")]
+        );
+        assert_eq!(
+            matches(yaml, ".metadata.labels"),
+            [m("/metadata/labels", "{app: web}")]
+        );
+        assert_eq!(matches(yaml, ".metadata.missing"), []);
+        assert_eq!(matches(yaml, ".spec.name"), []);
+        assert_eq!(matches(yaml, ".metadata[0]"), []);
+    }
+
+    #[test]
+    fn select_wildcards()
+    {
+        let yaml = "items:\n- spec: {replicas: 1}\n- other: x\n- spec: {replicas: 3}\n";
+
+        assert_eq!(
+            matches(yaml, ".items[*].spec.replicas"),
+            [
+                m("/items/0/spec/replicas", "1"),
+                m("/items/2/spec/replicas", "3")
+            ]
+        );
+        assert_eq!(matches(yaml, ".items[1].other"), [m("/items/1/other", "x")]);
+        assert_eq!(
+            matches("{a: 1, b: [2]}", ".*"),
+            [m("/a", "1"), m("/b", "[2]")]
+        );
+    }
+
+    #[test]
+    fn select_documents()
+    {
+        let yaml = "--- {name: a}\n--- [name]\n--- {name: c}\n";
+
+        assert_eq!(matches(yaml, ".name"), [m("/name", "a"), m("/name", "c")]);
+        assert_eq!(matches(yaml, ".").len(), 3);
+    }
+
+    #[test]
+    fn select_complex_keys()
+    {
+        let yaml = "? [a]\n: skipped\nb: &x kept\nc: *x\n";
+
+        assert_eq!(matches(yaml, ".b"), [m("/b", "&x kept")]);
+        assert_eq!(matches(yaml, ".c"), [m("/c", "*x")]);
+        assert_eq!(matches(yaml, ".*").len(), 3);
+    }
+
+    #[test]
+    fn select_lazy()
+    {
+        // The invalid escape is skipped, and never evaluated
+        let yaml = "{bad: \"\\q\", good: \"\\t\"}";
+        let reader = BorrowReader::new(yaml);
+        let events = from_reader_with(&reader, O_LAZY);
+        let query = Query::parse(".good").unwrap();
+
+        let found: Vec<_> = select(events, &query)
+            .map(|m| m.unwrap().into_parts().1)
+            .collect();
+
+        assert!(matches!(
+            found[0][0].data(),
+            EventData::Scalar(node) if matches!(&node.content, ScalarLike::Eager(s) if s.data() == "\t")
+        ));
+
+        let reader = BorrowReader::new(yaml);
+        let events = from_reader_with(&reader, O_LAZY);
+        let query = Query::parse(".bad").unwrap();
+
+        let err = select(events, &query)
+            .find_map(Result::err)
+            .expect("an invalid escape");
+
+        assert_eq!(err.kind(), crate::error::ErrorKind::UnknownEscape);
+    }
+}
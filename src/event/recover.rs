@@ -0,0 +1,1185 @@
+/*
+ * This Source Code Form is subject to the terms of the
+ * Mozilla Public License, v. 2.0. If a copy of the MPL
+ * was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+//! Error recovering parsing of YAML streams.
+//!
+//! An [`Events`] stream ends at the first error it
+//! encounters, which is the right behavior for consumers of
+//! the stream's data, but is unhelpful to tools like
+//! editors that would like to report every error in a
+//! stream at once.
+//!
+//! [`recover`] instead records each error, resynchronizes
+//! the stream and continues parsing, returning every error
+//! found alongside a best-effort stream of [`Event`]s.
+//!
+//! ## Resynchronization
+//!
+//! After an error, the offending text is skipped up to the
+//! first of:
+//!
+//! - The next flow delimiter (`,`, `]` or `}`), if the
+//!   error occurred inside of a flow collection
+//! - The next line that is indented no deeper than the line
+//!   the error occurred on
+//! - The next document marker (`---` or `...`)
+//!
+//! Flow collections left open at the end of a document are
+//! closed, and mismatched closing delimiters replaced (or,
+//! if they close an outer collection, the collections
+//! nested in it closed), so that the entries parsed before
+//! the error are retained. An error directly following a
+//! replaced or inserted delimiter is a consequence of the
+//! repair, and is not reported.
+//!
+//! Skipped text is replaced by whitespace, and parsing
+//! resumed from the start of the root collection's entry
+//! preceding the error, with the positions of every
+//! [`Event`] and error mapped back to the original stream.
+//! Errors from a resource limit are never recovered from.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use yary::event::{flag::O_NIL, recover::recover};
+//!
+//! let yaml = "\
+//! first: [a, b}
+//! second: ok
+//! third: {e: f g: h}
+//! ";
+//!
+//! let recovered = recover(yaml, O_NIL);
+//!
+//! let lines: Vec<_> = recovered.errors().iter().map(|e| e.line()).collect();
+//! assert_eq!(lines, vec![1, 3]);
+//! ```
+//!
+//! [`Events`]: struct@super::Events
+//! [`Event`]:  struct@super::types::Event
+
+use std::{iter::Peekable, ops::Range, rc::Rc};
+
+use crate::{
+    error::{Category, Error, Result},
+    event::{
+        flag::Flags,
+        limit::Limits,
+        types::{
+            Directives, Event, EventData, Mark, NodeKind, Span, VersionDirective, DEFAULT_TAGS,
+        },
+        Events,
+    },
+    reader::borrow::BorrowReader,
+};
+
+/// Parse .src without any resource limits, recovering from
+/// any errors encountered.
+///
+/// See the [module](self) documentation for more
+/// information.
+pub fn recover(src: &str, flags: Flags) -> Recovered
+{
+    recover_with(src, flags, Limits::unlimited())
+}
+
+/// Parse .src with the given resource .limits, recovering
+/// from any errors encountered.
+///
+/// See the [module](self) documentation for more
+/// information.
+pub fn recover_with(src: &str, flags: Flags, limits: Limits) -> Recovered
+{
+    let mut text = Patched::new(src);
+    let mut errors = Vec::new();
+    // Every event parsed so far, positioned in the patched
+    // stream
+    let mut events = Vec::new();
+    let mut resume = Resume::new();
+    let mut from = None;
+    // The end of the last delimiter inserted or replaced
+    let mut repaired = None;
+    let mut attempts = 0;
+
+    loop
+    {
+        let mut pass = Pass::parse(&text.data, from.as_ref(), flags, limits);
+
+        for event in pass.events.drain(..)
+        {
+            resume.push(&text.data, &event, events.len());
+            events.push(event);
+        }
+
+        let error = match pass.error.take()
+        {
+            Some(error) => error,
+            None => return Recovered::new(text.restore_events(events), errors),
+        };
+
+        let fix = pass.resync(&text.data, &error, &events);
+
+        // An error directly after a repaired delimiter is a
+        // consequence of the repair, rather than a new mistake
+        if !repaired.map_or(false, |end| follows(&text.data, end, &error))
+        {
+            errors.push(text.restore_error(error));
+        }
+
+        // Every fix removes or repairs at least one character of
+        // the stream, so this should never be hit, but guard
+        // against looping forever regardless
+        attempts += 1;
+        if attempts > src.len()
+        {
+            return Recovered::new(text.restore_events(events), errors);
+        }
+
+        let fix = match fix
+        {
+            Some(fix) => fix,
+            // Unrecoverable, return what we have
+            None => return Recovered::new(text.restore_events(events), errors),
+        };
+
+        repaired = match &fix
+        {
+            Fix::Blank(_) => None,
+            Fix::Replace(at, c) => Some(at + c.len_utf8()),
+            Fix::Insert(at, s) => Some(at + s.len()),
+        };
+
+        // Resume from the last point unaffected by the fix,
+        // keeping every event parsed before it
+        from = resume.rewind(fix.start());
+        events.truncate(from.as_ref().map_or(0, |point| point.index));
+
+        text.apply(fix);
+    }
+}
+
+/// The result of parsing a YAML stream with [`recover`],
+/// containing a best-effort stream of [`Event`]s, and every
+/// error encountered.
+///
+/// Iterating over this type produces the [`Event`]s in
+/// stream order, with each error placed at its position in
+/// the stream, before the first [`Event`] starting at or
+/// after it.
+///
+/// [`Event`]: struct@super::types::Event
+#[derive(Debug)]
+pub struct Recovered
+{
+    events: Vec<Event<'static>>,
+    errors: Vec<Error>,
+}
+
+impl Recovered
+{
+    fn new(events: Vec<Event<'static>>, errors: Vec<Error>) -> Self
+    {
+        Self { events, errors }
+    }
+
+    /// The recovered [`Event`]s.
+    ///
+    /// Note that if an error could not be recovered from,
+    /// this stream will end before its `StreamEnd` event.
+    ///
+    /// [`Event`]: struct@super::types::Event
+    pub fn events(&self) -> &[Event<'static>]
+    {
+        &self.events
+    }
+
+    /// The errors encountered, in the order they were found
+    pub fn errors(&self) -> &[Error]
+    {
+        &self.errors
+    }
+
+    /// Check whether the YAML stream was error free
+    pub fn is_ok(&self) -> bool
+    {
+        self.errors.is_empty()
+    }
+
+    /// Consume this [`Recovered`], returning its events and
+    /// errors
+    pub fn into_parts(self) -> (Vec<Event<'static>>, Vec<Error>)
+    {
+        (self.events, self.errors)
+    }
+}
+
+impl IntoIterator for Recovered
+{
+    type Item = Result<Event<'static>>;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter
+    {
+        let Self { events, mut errors } = self;
+        let mut out = Vec::with_capacity(events.len() + errors.len());
+
+        errors.sort_by_key(Error::at);
+        let mut errors: Peekable<_> = errors.into_iter().peekable();
+
+        for event in events
+        {
+            while let Some(error) = errors.next_if(|e| e.at() <= event.span().start.offset as u64)
+            {
+                out.push(Err(error));
+            }
+
+            out.push(Ok(event));
+        }
+
+        out.extend(errors.map(Err));
+
+        out.into_iter()
+    }
+}
+
+/// A single attempt at parsing the YAML stream
+struct Pass
+{
+    events: Vec<Event<'static>>,
+    error:  Option<Error>,
+    /// Collections open at the point .error occurred, with
+    /// flow collections recording their closing delimiter
+    open:   Vec<Option<char>>,
+}
+
+impl Pass
+{
+    /// Parse .text, starting .from the given point, or the
+    /// start of the stream
+    fn parse(text: &str, from: Option<&Point>, flags: Flags, limits: Limits) -> Self
+    {
+        let buffer;
+        let (src, skip, shift, limits) = match from
+        {
+            Some(point) =>
+            {
+                let (prefix, skip) = point.prefix();
+                let shift = Shift {
+                    prefix: prefix.len(),
+                    lines:  prefix.matches('\n').count(),
+                    offset: point.offset,
+                    line:   point.line,
+                };
+                // The events before .point are counted again by the
+                // prefix's events
+                let limits = Limits {
+                    max_events: limits
+                        .max_events
+                        .saturating_sub(point.index)
+                        .saturating_add(skip),
+                    max_documents: limits
+                        .max_documents
+                        .saturating_sub(point.documents)
+                        .saturating_add(1),
+                    ..limits
+                };
+
+                buffer = prefix + &text[point.offset..];
+                (buffer.as_str(), skip, shift, limits)
+            },
+            None => (text, 0, Shift::default(), limits),
+        };
+
+        let reader = BorrowReader::new(src);
+        let mut events = Events::with_limits(&reader, flags, limits);
+        let mut this = Self {
+            events: Vec::new(),
+            error:  None,
+            open:   Vec::new(),
+        };
+
+        for (i, event) in events.iter().enumerate()
+        {
+            let start = event.as_ref().map(|e| e.span().start).ok();
+
+            let event = match event.and_then(Event::into_owned)
+            {
+                Ok(event) => event,
+                Err(e) =>
+                {
+                    // Lazy scalars may fail to evaluate, attribute
+                    // these errors to the failing event
+                    let e = match (e.has_context(), start)
+                    {
+                        (false, Some(mark)) => e.with_mark(mark),
+                        _ => e,
+                    };
+                    this.error = Some(shift.error(e));
+
+                    break;
+                },
+            };
+
+            let end = event.span().end.offset;
+            match event.data()
+            {
+                EventData::SequenceStart(_) if src[..end].ends_with('[') =>
+                {
+                    this.open.push(Some(']'))
+                },
+                EventData::MappingStart(_) if src[..end].ends_with('{') =>
+                {
+                    this.open.push(Some('}'))
+                },
+                EventData::SequenceStart(_) | EventData::MappingStart(_) => this.open.push(None),
+                EventData::SequenceEnd | EventData::MappingEnd =>
+                {
+                    this.open.pop();
+                },
+                _ =>
+                {},
+            }
+
+            // Skip the events produced by the prefix
+            if i >= skip
+            {
+                this.events.push(shift.event(event));
+            }
+        }
+
+        this
+    }
+
+    /// Decide how to skip past the given .error, returning
+    /// None if the stream cannot be recovered. .events are
+    /// every event parsed before the error
+    fn resync(&self, text: &str, error: &Error, events: &[Event<'_>]) -> Option<Fix>
+    {
+        if !error.has_context() || error.classify() == Category::Limit
+        {
+            return None;
+        }
+
+        let at = usize::min(error.at() as usize, text.len());
+
+        if let Some(Some(closer)) = self.open.last()
+        {
+            let rest = &text[at..];
+            let flows = || self.open.iter().rev().take_while(|c| c.is_some()).flatten();
+
+            // Close any flow collections left open at the end of
+            // the stream or document
+            if rest.trim().is_empty() || (line_start(text, at) == at && is_marker(rest))
+            {
+                return Some(Fix::Insert(
+                    content_end(text, at, events),
+                    flows().collect(),
+                ));
+            }
+
+            if let Some(c @ ']') | Some(c @ '}') = rest.chars().next()
+            {
+                // Close the collections nested inside of the one
+                // the delimiter closes, or otherwise swap a
+                // mismatched delimiter for the correct one
+                if flows().any(|&closer| closer == c)
+                {
+                    let closers = flows().take_while(|&&closer| closer != c).collect();
+
+                    return Some(Fix::Insert(content_end(text, at, events), closers));
+                }
+
+                return Some(Fix::Replace(at, *closer));
+            }
+
+            let region = flow_region(text, at);
+            if has_content(text, &region)
+            {
+                return Some(Fix::Blank(region));
+            }
+        }
+
+        let region = block_region(text, at);
+        if has_content(text, &region)
+        {
+            return Some(Fix::Blank(region));
+        }
+
+        // The error was reported past the offending text (e.g an
+        // unterminated quote), skip from the last node parsed to
+        // the end of the stream
+        let last = events
+            .last()
+            .map_or(0, |event| line_start(text, event.span().start.offset));
+        let region = last..text.len();
+        if has_content(text, &region)
+        {
+            return Some(Fix::Blank(region));
+        }
+
+        None
+    }
+}
+
+/// Find the end of the content preceding .at, ignoring
+/// any whitespace and comments
+fn content_end(text: &str, at: usize, events: &[Event<'_>]) -> usize
+{
+    let end = text[..at].trim_end().len();
+    let start = line_start(text, end);
+
+    // Comments can only appear after the last parsed node
+    let after = events
+        .last()
+        .map_or(start, |event| event.span().end.offset)
+        .clamp(start, end);
+
+    let comment = text[after..end]
+        .char_indices()
+        .find(|&(i, c)| {
+            c == '#' && (after + i == start || text[..after + i].ends_with(&[' ', '\t'][..]))
+        })
+        .map(|(i, _)| after + i);
+
+    match comment
+    {
+        Some(comment) => text[..comment].trim_end().len(),
+        None => end,
+    }
+}
+
+/// Check whether .error is the first thing after .end,
+/// ignoring whitespace
+fn follows(text: &str, end: usize, error: &Error) -> bool
+{
+    let at = error.at() as usize;
+
+    error.has_context() && (end..=text.len()).contains(&at) && text[end..at].trim().is_empty()
+}
+
+/// A position in the patched stream which parsing can be
+/// resumed from: the start of a line beginning an entry of
+/// a document's root block collection
+#[derive(Debug)]
+struct Point
+{
+    /// Index of the entry's first event
+    index:      usize,
+    /// Offset of the start of the entry's line
+    offset:     usize,
+    /// Line number of .offset
+    line:       usize,
+    /// Number of documents started at or before .offset
+    documents:  usize,
+    /// The entry's document's directives, as YAML
+    directives: Rc<str>,
+    /// The indentation of the root collection
+    indent:     usize,
+    /// Whether the root collection is a mapping or sequence
+    kind:       NodeKind,
+}
+
+impl Point
+{
+    /// Text restoring the parser's state at this point,
+    /// alongside the number of events it produces
+    fn prefix(&self) -> (String, usize)
+    {
+        let indent = " ".repeat(self.indent);
+
+        // StreamStart, DocumentStart, the root collection, and a
+        // placeholder entry
+        match self.kind
+        {
+            NodeKind::Key => (format!("{}{}x: x\n", self.directives, indent), 5),
+            _ => (format!("{}{}- x\n", self.directives, indent), 4),
+        }
+    }
+}
+
+/// Records every [`Point`] in the events parsed so far
+struct Resume
+{
+    points:     Vec<Point>,
+    depth:      usize,
+    documents:  usize,
+    directives: Rc<str>,
+    /// The kind of entry in the current document's root
+    /// collection, if it is a block collection
+    root:       Option<NodeKind>,
+}
+
+impl Resume
+{
+    fn new() -> Self
+    {
+        Self {
+            points:     Vec::new(),
+            depth:      0,
+            documents:  0,
+            directives: Rc::from(""),
+            root:       None,
+        }
+    }
+
+    /// Record the .index'th .event parsed from .text
+    fn push(&mut self, text: &str, event: &Event<'_>, index: usize)
+    {
+        let depth = self.depth;
+        let (kind, collection) = match event.data()
+        {
+            EventData::DocumentStart(start) =>
+            {
+                self.documents += 1;
+                self.directives = directives(&start.directives).into();
+                self.root = None;
+
+                return;
+            },
+            EventData::SequenceEnd | EventData::MappingEnd =>
+            {
+                self.depth = self.depth.saturating_sub(1);
+
+                return;
+            },
+            EventData::Scalar(node) => (node.kind, None),
+            EventData::SequenceStart(node) => (node.kind, Some(NodeKind::Entry)),
+            EventData::MappingStart(node) => (node.kind, Some(NodeKind::Key)),
+            _ => return,
+        };
+
+        if let Some(entry) = collection
+        {
+            self.depth += 1;
+
+            if depth == 0
+            {
+                let end = event.span().end.offset;
+                let flow = text[..end].ends_with(&['[', '{'][..]);
+
+                self.root = Some(entry).filter(|_| !flow);
+            }
+        }
+
+        if depth != 1 || self.root != Some(kind)
+        {
+            return;
+        }
+
+        // Only entries starting their line can be resumed from
+        let start = event.span().start;
+        let offset = start.offset - start.column;
+        let lead = &text[offset..start.offset];
+        let indent = indent_of(lead);
+        let starts_line = match kind
+        {
+            NodeKind::Key => lead.len() == indent,
+            _ => lead[indent..].starts_with('-') && lead[indent + 1..].trim().is_empty(),
+        };
+
+        if starts_line
+        {
+            self.points.push(Point {
+                index,
+                offset,
+                line: start.line,
+                documents: self.documents,
+                directives: Rc::clone(&self.directives),
+                indent,
+                kind,
+            });
+        }
+    }
+
+    /// Find the last point at or before .at, discarding any
+    /// after it
+    fn rewind(&mut self, at: usize) -> Option<Point>
+    {
+        while let Some(point) = self.points.pop()
+        {
+            if point.offset <= at
+            {
+                self.depth = 1;
+                self.documents = point.documents;
+                self.directives = Rc::clone(&point.directives);
+                self.root = Some(point.kind);
+
+                return Some(point);
+            }
+        }
+
+        *self = Self::new();
+
+        None
+    }
+}
+
+/// Reproduce .directives as the YAML which declares them,
+/// up to and including the document start marker
+fn directives(directives: &Directives<'_>) -> String
+{
+    let VersionDirective { major, minor } = directives.version;
+    let mut text = format!("%YAML {}.{}\n", major, minor);
+
+    for (handle, prefix) in directives.tags.iter()
+    {
+        if !DEFAULT_TAGS.iter().any(|(h, p)| h == handle && p == prefix)
+        {
+            text.push_str(&format!("%TAG {} {}\n", handle, prefix));
+        }
+    }
+
+    text.push_str("---\n");
+
+    text
+}
+
+/// Maps positions in a resumed [`Pass`] to the patched
+/// stream
+#[derive(Debug, Default)]
+struct Shift
+{
+    /// Length of the prefix the pass started with
+    prefix: usize,
+    /// Lines in the prefix
+    lines:  usize,
+    /// Offset the pass resumed from
+    offset: usize,
+    /// Line the pass resumed from
+    line:   usize,
+}
+
+impl Shift
+{
+    fn mark(&self, mark: Mark) -> Mark
+    {
+        Mark::new(
+            (mark.offset + self.offset).saturating_sub(self.prefix),
+            (mark.line + self.line).saturating_sub(self.lines),
+            mark.column,
+        )
+    }
+
+    fn event(&self, event: Event<'static>) -> Event<'static>
+    {
+        if self.prefix == 0
+        {
+            return event;
+        }
+
+        let span = Span::new(self.mark(event.span().start), self.mark(event.span().end));
+
+        Event::new(span, event.into())
+    }
+
+    fn error(&self, error: Error) -> Error
+    {
+        if self.prefix == 0 || !error.has_context()
+        {
+            return error;
+        }
+
+        let mark = Mark::new(
+            error.at() as usize,
+            error.line() as usize - 1,
+            error.column() as usize - 1,
+        );
+
+        error.with_mark(self.mark(mark))
+    }
+}
+
+/// A modification of the YAML stream that skips past an
+/// error
+enum Fix
+{
+    /// Replace the given range with whitespace
+    Blank(Range<usize>),
+    /// Replace the character at the given offset
+    Replace(usize, char),
+    /// Insert the given text at an offset
+    Insert(usize, String),
+}
+
+impl Fix
+{
+    /// The first offset this fix modifies
+    fn start(&self) -> usize
+    {
+        match self
+        {
+            Self::Blank(range) => range.start,
+            Self::Replace(at, _) | Self::Insert(at, _) => *at,
+        }
+    }
+}
+
+/// The YAML stream, with every [`Fix`] applied so far
+struct Patched
+{
+    data:    String,
+    /// The (offset, length, line) of each insertion made,
+    /// in order
+    inserts: Vec<(usize, usize, usize)>,
+}
+
+impl Patched
+{
+    fn new(src: &str) -> Self
+    {
+        Self {
+            data:    src.to_string(),
+            inserts: Vec::new(),
+        }
+    }
+
+    fn apply(&mut self, fix: Fix)
+    {
+        match fix
+        {
+            Fix::Blank(range) => blank(&mut self.data, range),
+            Fix::Replace(at, c) =>
+            {
+                let len = self.data[at..].chars().next().map_or(0, char::len_utf8);
+
+                self.data
+                    .replace_range(at..at + len, c.encode_utf8(&mut [0; 4]));
+            },
+            Fix::Insert(at, text) =>
+            {
+                let line = self.data[..at].matches('\n').count();
+
+                self.data.insert_str(at, &text);
+                self.inserts.push((at, text.len(), line));
+            },
+        }
+    }
+
+    /// Map a .mark in the patched stream back to the
+    /// original stream
+    fn restore(&self, mut mark: Mark) -> Mark
+    {
+        for &(at, len, line) in self.inserts.iter().rev()
+        {
+            if mark.offset >= at + len
+            {
+                mark.offset -= len;
+
+                if mark.line == line
+                {
+                    mark.column -= len;
+                }
+            }
+            else if mark.offset > at
+            {
+                // Inside of the inserted text
+                mark.column -= mark.offset - at;
+                mark.offset = at;
+            }
+        }
+
+        mark
+    }
+
+    fn restore_events(&self, events: Vec<Event<'static>>) -> Vec<Event<'static>>
+    {
+        if self.inserts.is_empty()
+        {
+            return events;
+        }
+
+        events
+            .into_iter()
+            .map(|event| {
+                let span = Span::new(
+                    self.restore(event.span().start),
+                    self.restore(event.span().end),
+                );
+
+                Event::new(span, event.into())
+            })
+            .collect()
+    }
+
+    fn restore_error(&self, error: Error) -> Error
+    {
+        if self.inserts.is_empty() || !error.has_context()
+        {
+            return error;
+        }
+
+        let mark = Mark::new(
+            error.at() as usize,
+            error.line() as usize - 1,
+            error.column() as usize - 1,
+        );
+
+        error.with_mark(self.restore(mark))
+    }
+}
+
+/// The range from .at to the next flow delimiter in the
+/// same collection, or document marker
+fn flow_region(text: &str, at: usize) -> Range<usize>
+{
+    let mut depth = 0usize;
+
+    for (i, c) in text[at..].char_indices()
+    {
+        match c
+        {
+            '[' | '{' => depth += 1,
+            ',' | ']' | '}' if depth == 0 => return at..at + i,
+            ']' | '}' => depth -= 1,
+            '\n' if is_marker(&text[at + i + 1..]) => return at..at + i,
+            _ =>
+            {},
+        }
+    }
+
+    at..text.len()
+}
+
+/// The range from the start of the line containing .at, to
+/// the next line indented no deeper than it, or document
+/// marker
+fn block_region(text: &str, at: usize) -> Range<usize>
+{
+    let start = line_start(text, at);
+    let indent = indent_of(&text[start..]);
+    let mut end = next_line(text, start);
+
+    while end < text.len()
+    {
+        let line = &text[end..next_line(text, end)];
+        let content = line.trim_start_matches(' ');
+        let blank = content.trim().is_empty() || content.starts_with('#');
+
+        if is_marker(line) || (!blank && indent_of(line) <= indent)
+        {
+            break;
+        }
+
+        end = next_line(text, end);
+    }
+
+    start..end
+}
+
+/// Replace the given .range of .text with whitespace,
+/// preserving line breaks and byte offsets
+fn blank(text: &mut String, range: Range<usize>)
+{
+    let spaces: String = text[range.clone()]
+        .chars()
+        .flat_map(|c| {
+            let (c, len) = match c
+            {
+                '\r' | '\n' => (c, 1),
+                c => (' ', c.len_utf8()),
+            };
+
+            std::iter::repeat(c).take(len)
+        })
+        .collect();
+
+    text.replace_range(range, &spaces);
+}
+
+/// Check whether .range of .text contains anything other
+/// than spaces and line breaks
+fn has_content(text: &str, range: &Range<usize>) -> bool
+{
+    text[range.clone()]
+        .chars()
+        .any(|c| !matches!(c, ' ' | '\r' | '\n'))
+}
+
+/// Check whether .line starts with a document marker
+fn is_marker(line: &str) -> bool
+{
+    (line.starts_with("---") || line.starts_with("..."))
+        && matches!(
+            line.as_bytes().get(3),
+            None | Some(b' ' | b'\t' | b'\r' | b'\n')
+        )
+}
+
+fn indent_of(line: &str) -> usize
+{
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+fn line_start(text: &str, at: usize) -> usize
+{
+    text[..at].rfind('\n').map_or(0, |i| i + 1)
+}
+
+fn next_line(text: &str, at: usize) -> usize
+{
+    text[at..].find('\n').map_or(text.len(), |i| at + i + 1)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        error::ErrorKind,
+        event::{flag::O_NIL, from_reader, types::ScalarLike},
+    };
+
+    /// The evaluated content of each scalar in .events
+    fn scalars(events: &[Event<'_>]) -> Vec<String>
+    {
+        events
+            .iter()
+            .filter_map(|event| match event.data()
+            {
+                EventData::Scalar(node) => Some(node.content.clone()),
+                _ => None,
+            })
+            .map(|scalar: ScalarLike| scalar.evaluate().unwrap().data().to_string())
+            .collect()
+    }
+
+    /// The (line, kind) of each error in .recovered
+    fn errors(recovered: &Recovered) -> Vec<(u64, ErrorKind)>
+    {
+        recovered
+            .errors()
+            .iter()
+            .map(|e| (e.line(), e.kind()))
+            .collect()
+    }
+
+    fn is_balanced(events: &[Event<'_>]) -> bool
+    {
+        matches!(
+            events.first().map(Event::data),
+            Some(EventData::StreamStart(_))
+        ) && matches!(events.last().map(Event::data), Some(EventData::StreamEnd))
+    }
+
+    #[test]
+    fn clean()
+    {
+        let data = "key: [a, {b: c}]\nblock: |\n  text\n";
+        let yaml = BorrowReader::new(data);
+
+        let expected: Vec<_> = from_reader(&yaml)
+            .into_iter()
+            .map(|e| e.and_then(Event::into_owned).unwrap())
+            .collect();
+
+        let recovered = recover(data, O_NIL);
+
+        assert!(recovered.is_ok());
+        assert_eq!(recovered.events(), &expected[..]);
+    }
+
+    #[test]
+    fn multiple_errors()
+    {
+        let data = "\
+one: [a, b}
+two: ok
+three: {e: f g: h}
+four: x
+  y: z
+five: 5
+";
+        let recovered = recover(data, O_NIL);
+
+        assert_eq!(
+            errors(&recovered),
+            vec![
+                (1, ErrorKind::MissingFlowSequenceEntryOrEnd),
+                (3, ErrorKind::MissingFlowMappingEntryOrEnd),
+                (5, ErrorKind::InvalidValue),
+            ]
+        );
+        assert!(is_balanced(recovered.events()));
+        assert_eq!(
+            scalars(recovered.events()),
+            vec!["one", "a", "b", "two", "ok", "three", "e", "f g", "four", "x", "five", "5"]
+        );
+    }
+
+    #[test]
+    fn cascaded_errors()
+    {
+        let recovered = recover("- [a, {b: c]\n- fine\n", O_NIL);
+
+        assert_eq!(
+            errors(&recovered),
+            vec![(1, ErrorKind::MissingFlowMappingEntryOrEnd)]
+        );
+        assert!(is_balanced(recovered.events()));
+        assert_eq!(scalars(recovered.events()), vec!["a", "b", "c", "fine"]);
+
+        // The surplus delimiter is only an error because of the
+        // replaced one
+        let recovered = recover("key: [a, b}]\nnext: 1\n", O_NIL);
+
+        assert_eq!(
+            errors(&recovered),
+            vec![(1, ErrorKind::MissingFlowSequenceEntryOrEnd)]
+        );
+        assert_eq!(scalars(recovered.events()), vec!["next", "1"]);
+    }
+
+    #[test]
+    fn resumed_positions()
+    {
+        let mapping = "\
+%TAG !e! tag:example.com:2000:
+---
+a: [1, 2}
+b: !e!x 3
+c: {d: [e]]
+";
+        let sequence = "- [f}\n- !!str g\n- {h: i]\n";
+
+        let cases = [
+            (
+                mapping,
+                mapping.replace("2}", "2]").replace("e]]", "e]}"),
+                [3, 5],
+            ),
+            (
+                sequence,
+                sequence.replace("f}", "f]").replace("i]", "i}"),
+                [1, 3],
+            ),
+        ];
+
+        for (data, fixed, lines) in cases.iter()
+        {
+            let yaml = BorrowReader::new(fixed);
+            let expected: Vec<_> = from_reader(&yaml)
+                .into_iter()
+                .map(|e| e.and_then(Event::into_owned).unwrap())
+                .collect();
+
+            let recovered = recover(data, O_NIL);
+            let errors: Vec<_> = recovered.errors().iter().map(Error::line).collect();
+
+            assert_eq!(&errors, lines);
+            assert_eq!(recovered.events(), &expected[..]);
+        }
+    }
+
+    #[test]
+    fn many_errors()
+    {
+        let data: String = (0..2000).map(|i| format!("k{}: [a, b}}\n", i)).collect();
+        let recovered = recover(&data, O_NIL);
+
+        assert_eq!(recovered.errors().len(), 2000);
+        assert!(is_balanced(recovered.events()));
+        assert_eq!(scalars(recovered.events()).len(), 6000);
+    }
+
+    #[test]
+    fn document_marker()
+    {
+        let data = "--- {a: b c: d\n--- {e: f}\n";
+        let recovered = recover(data, O_NIL);
+
+        assert_eq!(
+            errors(&recovered),
+            vec![
+                (1, ErrorKind::MissingFlowMappingEntryOrEnd),
+                (2, ErrorKind::MissingFlowMappingEntryOrEnd),
+            ]
+        );
+        assert!(is_balanced(recovered.events()));
+        assert_eq!(scalars(recovered.events()), vec!["a", "b c", "e", "f"]);
+    }
+
+    #[test]
+    fn unterminated_flow()
+    {
+        let data = "--- [a, {b: c # comment\n--- {e: f}\n";
+        let recovered = recover(data, O_NIL);
+
+        assert_eq!(recovered.errors().len(), 1);
+        assert!(is_balanced(recovered.events()));
+        assert_eq!(scalars(recovered.events()), vec!["a", "b", "c", "e", "f"]);
+
+        // Positions refer to the original stream, regardless of
+        // the closing delimiters inserted
+        let starts: Vec<_> = recovered
+            .events()
+            .iter()
+            .filter(|e| matches!(e.data(), EventData::MappingStart(_) | EventData::MappingEnd))
+            .map(|e| e.span().start.offset)
+            .collect();
+        assert_eq!(starts, vec![8, 13, 28, 33]);
+    }
+
+    #[test]
+    fn unterminated_quote()
+    {
+        let recovered = recover("key: \"value\n", O_NIL);
+
+        assert_eq!(errors(&recovered), vec![(1, ErrorKind::UnexpectedEOF)]);
+        assert!(is_balanced(recovered.events()));
+    }
+
+    #[test]
+    fn limits_not_recovered()
+    {
+        let limits = Limits {
+            max_depth: 1,
+            ..Limits::new()
+        };
+        let recovered = recover_with("[[a]]\n", O_NIL, limits);
+
+        assert_eq!(errors(&recovered), vec![(1, ErrorKind::DepthLimit)]);
+        assert!(!is_balanced(recovered.events()));
+    }
+
+    #[test]
+    fn error_nodes()
+    {
+        let recovered = recover("a: {b: c d: e}\nf: g\n", O_NIL);
+        let error_at = recovered.errors()[0].at() as usize;
+
+        let items: Vec<_> = recovered.into_iter().collect();
+        let position = items.iter().position(Result::is_err).unwrap();
+
+        // Every event before the error starts before it, and
+        // every event after starts at or after it
+        for (i, item) in items.iter().enumerate()
+        {
+            if let Ok(event) = item
+            {
+                assert_eq!(
+                    event.span().start.offset < error_at,
+                    i < position,
+                    "{:?}",
+                    event
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn blank_preserves_offsets()
+    {
+        let mut text = String::from("a: ünï\r\nb");
+        let len = text.len();
+
+        blank(&mut text, 0..len - 1);
+
+        assert_eq!(text.len(), "a: ünï\r\nb".len());
+        assert_eq!(text, "        \r\nb");
+    }
+}
@@ -7,7 +7,7 @@
 //! Contains the various types used by [Event]s to represent
 //! YAML.
 
-use std::{array::IntoIter as ArrayIter, borrow::Cow, collections::HashMap};
+use std::{array::IntoIter as ArrayIter, borrow::Cow, collections::HashMap, fmt};
 
 use crate::{
     scanner::{entry::Lazy, error::ScanResult},
@@ -50,6 +50,16 @@ pub const DEFAULT_TAGS: [(Slice<'static>, Slice<'static>); 2] = [
 /// ```
 pub const DEFAULT_VERSION: VersionDirective = VersionDirective { major: 1, minor: 2 };
 
+/// YAML 1.1 stream version, documents with this version
+/// follow the 1.1 spec's rules.
+///
+/// Equivalent of:
+///
+/// ```yaml
+/// %YAML 1.1
+/// ```
+pub const YAML11_VERSION: VersionDirective = VersionDirective { major: 1, minor: 1 };
+
 /// An empty YAML scalar.
 ///
 /// In many circumstances, a YAML node is implied by the
@@ -58,41 +68,100 @@ pub const DEFAULT_VERSION: VersionDirective = VersionDirective { major: 1, minor
 /// nodes.
 pub const EMPTY_SCALAR: ScalarLike<'static> = ScalarLike::empty();
 
+/// A position in the underlying YAML stream.
+///
+/// All fields are zero based, and the [`column`] is counted
+/// in bytes from the last line break.
+///
+/// [`column`]: Mark::column
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Mark
+{
+    /// Byte offset from the start of the stream
+    pub offset: usize,
+    /// Number of line breaks preceding this mark
+    pub line:   usize,
+    /// Byte offset from the last line break
+    pub column: usize,
+}
+
+impl Mark
+{
+    /// Instantiate a new [`Mark`] at the given position
+    pub const fn new(offset: usize, line: usize, column: usize) -> Self
+    {
+        Self {
+            offset,
+            line,
+            column,
+        }
+    }
+}
+
+/// A range in the underlying YAML stream, delimited by a
+/// start and end [`Mark`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Span
+{
+    /// Position this span starts at
+    pub start: Mark,
+    /// Position this span ends at, exclusive
+    pub end:   Mark,
+}
+
+impl Span
+{
+    /// Instantiate a new [`Span`] covering .start to .end
+    pub const fn new(start: Mark, end: Mark) -> Self
+    {
+        Self { start, end }
+    }
+
+    /// Instantiate a new zero width [`Span`] at the given
+    /// .mark
+    pub const fn at(mark: Mark) -> Self
+    {
+        Self::new(mark, mark)
+    }
+}
+
 /// Specific YAML productions found in the YAML stream. Each
-/// Event has a start and end mark indicating an approximate
-/// range that is represented by the given Event. See
-/// [EventData] for all of the possible Event variants.
+/// Event has a [`Span`] indicating an approximate range
+/// that is represented by the given Event. See [EventData]
+/// for all of the possible Event variants.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Event<'de>
 {
-    start_mark: usize,
-    end_mark:   usize,
-    inner:      EventData<'de>,
+    span:  Span,
+    inner: EventData<'de>,
 }
 
 impl<'de> Event<'de>
 {
-    /// Instantiate a new [`Event`] with the given marks and
+    /// Instantiate a new [`Event`] with the given span and
     /// data
-    pub fn new(start_mark: usize, end_mark: usize, event: EventData<'de>) -> Self
+    pub fn new(span: Span, event: EventData<'de>) -> Self
     {
-        Self {
-            start_mark,
-            end_mark,
-            inner: event,
-        }
+        Self { span, inner: event }
     }
 
-    /// Retrieve the start mark of this [`Event`]
+    /// Retrieve the start byte offset of this [`Event`]
     pub fn start(&self) -> usize
     {
-        self.start_mark
+        self.span.start.offset
     }
 
-    /// Retrieve the end mark of this [`Event`]
+    /// Retrieve the end byte offset of this [`Event`]
     pub fn end(&self) -> usize
     {
-        self.end_mark
+        self.span.end.offset
+    }
+
+    /// Retrieve the [`Span`] of this [`Event`], containing
+    /// the line and column of its start and end marks
+    pub fn span(&self) -> Span
+    {
+        self.span
     }
 
     /// Retrieve the data associated with this [`Event`]
@@ -107,6 +176,29 @@ impl<'de> Event<'de>
     {
         &mut self.inner
     }
+
+    /// Consume this [`Event`], copying any data it borrows
+    /// from the YAML stream into an owned [`Event`].
+    ///
+    /// ## Errors
+    ///
+    /// Any lazy [`Scalar`]s are evaluated, and this method
+    /// may error if they are invalid.
+    pub fn into_owned(self) -> Result<Event<'static>, crate::Error>
+    {
+        Ok(Event {
+            span:  self.span,
+            inner: self.inner.into_owned()?,
+        })
+    }
+}
+
+impl<'de> From<Event<'de>> for EventData<'de>
+{
+    fn from(event: Event<'de>) -> Self
+    {
+        event.inner
+    }
 }
 
 /// The possible variants of an [Event]. See the
@@ -137,7 +229,9 @@ pub enum EventData<'de>
     /// An alias point connected to a previously seen
     /// `Scalar`, `MappingStart`, or `SequenceStart`
     /// [Node].anchor, the caller must keep track of
-    /// this information
+    /// this information, or use the [expand] adapter
+    ///
+    /// [expand]: mod@super::expand
     Alias(Alias<'de>),
     /// A scalar leaf node, containing (perhaps lazy)
     /// unicode slice content
@@ -161,6 +255,12 @@ pub enum EventData<'de>
     SequenceStart(Node<'de, Sequence>),
     /// End of a YAML array production
     SequenceEnd,
+
+    /// A comment in the YAML stream, only produced if
+    /// requested via [O_COMMENTS]
+    ///
+    /// [O_COMMENTS]: super::flag::O_COMMENTS
+    Comment(Comment<'de>),
 }
 
 /// Wrapper around [Event] variants that correspond to a
@@ -174,14 +274,103 @@ pub enum EventData<'de>
 pub struct Node<'de, T: 'de>
 {
     /// The alias applied to this node (if any)
-    pub anchor:  Option<Slice<'de>>,
+    pub anchor:   Option<Slice<'de>>,
     /// The tag applied to this node (if any)
-    pub tag:     Option<(Slice<'de>, Slice<'de>)>,
+    pub tag:      Option<(Slice<'de>, Slice<'de>)>,
     /// The node's content if simple, or a hint about the
     /// complex structure type
-    pub content: T,
+    pub content:  T,
     /// Contextual information about this Node
-    pub kind:    NodeKind,
+    pub kind:     NodeKind,
+    /// This node's tag, resolved against the tag directives
+    /// in scope. Unlike .tag, every node has one
+    pub resolved: ResolvedTag<'de>,
+}
+
+impl<'de, T> Node<'de, T>
+{
+    /// Copy this node's properties into an owned [`Node`],
+    /// converting its content with .f
+    fn map_owned<U, F>(self, f: F) -> Result<Node<'static, U>, crate::Error>
+    where
+        F: FnOnce(T) -> Result<U, crate::Error>,
+    {
+        Ok(Node {
+            anchor:   self.anchor.map(owned),
+            tag:      self
+                .tag
+                .map(|(handle, suffix)| (owned(handle), owned(suffix))),
+            content:  f(self.content)?,
+            kind:     self.kind,
+            resolved: self.resolved.into_owned(),
+        })
+    }
+}
+
+/// A node's tag, after any handle has been expanded
+/// through its `%TAG` directive.
+///
+/// Nodes without a specific tag are given one of the two
+/// non-specific tags, `?` or `!`, which YAML uses to decide
+/// how the node should be resolved by an application.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ResolvedTag<'de>
+{
+    /// The `?` non-specific tag, given to plain scalars and
+    /// collections without a tag. These are typically
+    /// resolved by content, for example `true` as a boolean
+    Question,
+    /// The `!` non-specific tag, given to non plain scalars
+    /// without a tag, and to nodes tagged with a lone `!`.
+    /// These are resolved by kind alone, for example a
+    /// quoted scalar is always a string
+    Exclamation,
+    /// A specific tag's full URI, such as
+    /// `tag:yaml.org,2002:str` for `!!str`. Local tags keep
+    /// their leading `!`, and verbatim tags are passed
+    /// through as written
+    Specific(Slice<'de>),
+}
+
+impl<'de> ResolvedTag<'de>
+{
+    /// This tag as a string, either its URI or the
+    /// non-specific `?` or `!`
+    pub fn as_str(&self) -> &str
+    {
+        match self
+        {
+            Self::Question => "?",
+            Self::Exclamation => "!",
+            Self::Specific(uri) => uri,
+        }
+    }
+
+    /// Check whether this is a specific tag, rather than
+    /// one of the non-specific tags
+    pub fn is_specific(&self) -> bool
+    {
+        matches!(self, Self::Specific(_))
+    }
+
+    /// Copy this tag into an owned [`ResolvedTag`]
+    pub fn into_owned(self) -> ResolvedTag<'static>
+    {
+        match self
+        {
+            Self::Question => ResolvedTag::Question,
+            Self::Exclamation => ResolvedTag::Exclamation,
+            Self::Specific(uri) => ResolvedTag::Specific(owned(uri)),
+        }
+    }
+}
+
+impl fmt::Display for ResolvedTag<'_>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        f.write_str(self.as_str())
+    }
 }
 
 /// Representation of a YAML scalar node, either eagerly
@@ -296,6 +485,17 @@ impl<'de> ScalarLike<'de>
     {
         matches!(self, Self::Lazy(_))
     }
+
+    /// Check whether this scalar was written in the plain
+    /// style, without evaluating it
+    pub(crate) fn is_plain(&self) -> bool
+    {
+        match self
+        {
+            Self::Eager(scalar) => scalar.style() == ScalarStyle::Plain,
+            Self::Lazy(lazy) => lazy.inner.is_plain(),
+        }
+    }
 }
 
 impl ScalarLike<'static>
@@ -345,8 +545,15 @@ pub struct Scalar<'de>
 
 impl<'de> Scalar<'de>
 {
+    /// Instantiate a new [`Scalar`] from the given data and
+    /// style.
+    pub fn new(data: Slice<'de>, style: ScalarStyle) -> Self
+    {
+        Self { data, style }
+    }
+
     /// Retrieve the associated data of this [`Scalar`].
-    pub fn data(&self) -> &Slice
+    pub fn data(&self) -> &Slice<'_>
     {
         &self.data
     }
@@ -363,13 +570,20 @@ impl<'de> Scalar<'de>
     {
         self.style
     }
+
+    /// Consume this [`Scalar`], returning the associated
+    /// data.
+    pub fn into_data(self) -> Slice<'de>
+    {
+        self.data
+    }
 }
 
 impl<'de> AsRef<str> for Scalar<'de>
 {
     fn as_ref(&self) -> &str
     {
-        &*self.data
+        &self.data
     }
 }
 
@@ -379,7 +593,7 @@ impl<'de> std::ops::Deref for Scalar<'de>
 
     fn deref(&self) -> &Self::Target
     {
-        &*self.data
+        &self.data
     }
 }
 
@@ -448,31 +662,81 @@ pub enum NodeKind
     ///
     /// Example:
     ///
-    ///   ---
-    ///   - 'A YAML scalar in a sequence'
-    ///   ...
+    /// ```yaml
+    /// ---
+    /// - 'A YAML scalar in a sequence'
+    /// ...
+    /// ```
     Entry,
 
     /// A key in a YAML mapping
     ///
     /// Example:
     ///
-    ///   ---
-    ///   A YAML key: "..."
-    /// # ^^^^^^^^^^
-    ///   ...
+    /// ```yaml
+    /// ---
+    /// A YAML key: "..."
+    /// #^^^^^^^^^
+    /// ...
+    /// ```
     Key,
     /// A value in a YAML mapping
     ///
     /// Example:
     ///
-    ///   ---
-    ///   "...": "A YAML value"
-    /// #         ^^^^^^^^^^^^
-    ///   ...
+    /// ```yaml
+    /// ---
+    /// "...": "A YAML value"
+    /// #       ^^^^^^^^^^^^
+    /// ...
+    /// ```
     Value,
 }
 
+impl<'de> EventData<'de>
+{
+    /// Consume this [`EventData`], copying any data it
+    /// borrows from the YAML stream. See
+    /// [`Event::into_owned`].
+    pub fn into_owned(self) -> Result<EventData<'static>, crate::Error>
+    {
+        use EventData::*;
+
+        let data = match self
+        {
+            StreamStart(start) => StreamStart(start),
+            StreamEnd => StreamEnd,
+            DocumentStart(start) => DocumentStart(self::DocumentStart {
+                directives: start.directives.into_owned(),
+                implicit:   start.implicit,
+            }),
+            DocumentEnd(end) => DocumentEnd(end),
+            Alias(alias) => Alias(self::Alias {
+                name: owned(alias.name),
+            }),
+            Scalar(node) => Scalar(node.map_owned(|content| {
+                let scalar = content.evaluate()?;
+                let style = scalar.style();
+
+                Ok(ScalarLike::Eager(self::Scalar::new(
+                    owned(scalar.into_data()),
+                    style,
+                )))
+            })?),
+            MappingStart(node) => MappingStart(node.map_owned(Ok)?),
+            MappingEnd => MappingEnd,
+            SequenceStart(node) => SequenceStart(node.map_owned(Ok)?),
+            SequenceEnd => SequenceEnd,
+            Comment(comment) => Comment(self::Comment {
+                text:      owned(comment.text),
+                placement: comment.placement,
+            }),
+        };
+
+        Ok(data)
+    }
+}
+
 /// StreamStart [Event] contents
 #[derive(Debug, Clone, PartialEq)]
 pub struct StreamStart
@@ -507,6 +771,38 @@ pub struct Alias<'de>
     pub name: Slice<'de>,
 }
 
+/// Comment [Event] contents
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comment<'de>
+{
+    /// The comment's text, excluding the leading `#` and
+    /// any line break
+    pub text:      Slice<'de>,
+    /// The comment's placement relative to the nodes
+    /// around it
+    pub placement: CommentPlacement,
+}
+
+/// Where a [`Comment`] sits in the YAML stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CommentPlacement
+{
+    /// The comment is the only content on its line
+    ///
+    /// ```yaml
+    /// # like this
+    /// key: value
+    /// ```
+    OwnLine,
+    /// The comment follows other content on its line,
+    /// typically a node
+    ///
+    /// ```yaml
+    /// key: value # like this
+    /// ```
+    Trailing,
+}
+
 /// MappingStart [Event] stub
 #[derive(Debug, Clone, PartialEq)]
 pub struct Mapping;
@@ -535,6 +831,19 @@ impl<'de> Directives<'de>
             tags:    TagDirectives::new(),
         }
     }
+
+    /// Copy these directives into an owned [`Directives`]
+    pub fn into_owned(self) -> Directives<'static>
+    {
+        Directives {
+            version: self.version,
+            tags:    self
+                .tags
+                .into_iter()
+                .map(|(handle, prefix)| (owned(handle), owned(prefix)))
+                .collect(),
+        }
+    }
 }
 
 impl Default for Directives<'_>
@@ -566,13 +875,22 @@ pub type TagDirectives<'de> = HashMap<Slice<'de>, Slice<'de>>;
 
 /// The encoding of the underlying byte stream.
 ///
-/// Currently, and for the forseeable future only `UTF8`
-/// will be supported, though this may change eventually.
+/// This is detected from the start of the byte stream, and
+/// any stream not encoded as UTF8 is transcoded to UTF8
+/// before being parsed.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum StreamEncoding
 {
     /// The byte stream is encoded as UTF8
     UTF8,
+    /// The byte stream is encoded as little endian UTF16
+    UTF16LE,
+    /// The byte stream is encoded as big endian UTF16
+    UTF16BE,
+    /// The byte stream is encoded as little endian UTF32
+    UTF32LE,
+    /// The byte stream is encoded as big endian UTF32
+    UTF32BE,
 }
 
 /// The style of a [`Scalar`], corresponding to the possible
@@ -593,6 +911,12 @@ pub enum ScalarStyle
     Folded,
 }
 
+/// Copy the given [`Slice`], if borrowed
+fn owned(slice: Slice<'_>) -> Slice<'static>
+{
+    Slice::Owned(slice.into_owned())
+}
+
 /// Wrapper around IntoIterator::into_iter that works around
 /// the hack in `std` which makes our Rust edition's
 /// ARRAY.into_iter() postfix call take the array by
@@ -0,0 +1,580 @@
+/*
+ * This Source Code Form is subject to the terms of the
+ * Mozilla Public License, v. 2.0. If a copy of the MPL
+ * was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+//! This module exposes [`Unique`], an [`Event`] adapter
+//! which detects duplicate keys in mappings.
+//!
+//! The YAML spec requires that the keys of a mapping are
+//! unique, but as a parser only sees each key's text, it
+//! emits duplicate keys without complaint. [`Unique`]
+//! instead compares keys by their value:
+//!
+//! - Scalars are resolved with the schema for their
+//!   document's `%YAML` version, typically the YAML 1.2
+//!   core schema, so `1` and `0x1` are the same key, but
+//!   `1` and `'1'` are not
+//! - Collections are compared structurally, with mappings
+//!   compared regardless of the order of their entries
+//! - Aliases are compared as the node they refer to
+//! - Nodes with an application specific tag are only equal
+//!   to nodes with the same tag
+//!
+//! Every duplicate is recorded as a [`Duplicate`], holding
+//! the [`Span`]s of both occurrences of the key. Depending
+//! on its [`OnDuplicate`] policy, the adapter then either
+//! stops with an error, or continues.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use yary::{
+//!     error::ErrorKind,
+//!     event::{from_reader, unique::unique},
+//!     reader::borrow::BorrowReader,
+//! };
+//!
+//! let yaml = BorrowReader::new("replicas: 1\nimage: alpine\n0x1: 2\nreplicas: 3\n");
+//! let mut events = unique(from_reader(&yaml));
+//!
+//! let err = events
+//!     .by_ref()
+//!     .find_map(Result::err)
+//!     .expect("a duplicate key");
+//!
+//! assert_eq!(err.kind(), ErrorKind::DuplicateKey);
+//! assert_eq!(err.line(), 4);
+//!
+//! let duplicate = &events.duplicates()[0];
+//! assert_eq!(duplicate.first().start.line, 0);
+//! assert_eq!(duplicate.second().start.line, 3);
+//! ```
+//!
+//! [`Span`]: super::types::Span
+
+use std::collections::HashMap;
+
+use crate::{
+    error::{internal::ErrorCode, mkError, Result},
+    event::types::{Event, EventData, ResolvedTag, Slice, Span, VersionDirective, DEFAULT_VERSION},
+    schema::{self, Resolved},
+};
+
+/// Wraps the given events, returning an error at the first
+/// duplicate key.
+///
+/// See [`unique_with`] for more information.
+pub fn unique<'de, I>(events: I) -> Unique<'de, I::IntoIter>
+where
+    I: IntoIterator<Item = Result<Event<'de>>>,
+{
+    unique_with(events, OnDuplicate::Error)
+}
+
+/// Wraps the given events, recording any duplicate keys,
+/// and handling them according to the given policy.
+///
+/// Detection stops at the first error, either from the
+/// underlying events or a duplicate key under
+/// [`OnDuplicate::Error`], after which the returned
+/// iterator is fused.
+pub fn unique_with<'de, I>(events: I, policy: OnDuplicate) -> Unique<'de, I::IntoIter>
+where
+    I: IntoIterator<Item = Result<Event<'de>>>,
+{
+    Unique::new(events.into_iter(), policy)
+}
+
+/// What [`Unique`] does after finding a duplicate key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OnDuplicate
+{
+    /// Return an error, positioned at the second
+    /// occurrence of the key
+    Error,
+    /// Continue, duplicates can be retrieved with
+    /// [`Unique::duplicates`]
+    Report,
+}
+
+/// A key which occurred more than once in the same mapping
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Duplicate
+{
+    first:  Span,
+    second: Span,
+}
+
+impl Duplicate
+{
+    /// The span of the key's first occurrence
+    pub fn first(&self) -> Span
+    {
+        self.first
+    }
+
+    /// The span of the key's duplicate occurrence
+    pub fn second(&self) -> Span
+    {
+        self.second
+    }
+}
+
+/// An [`Event`] adapter which detects duplicate mapping
+/// keys.
+///
+/// Events are passed through unchanged, except that the
+/// content of scalars which must be compared is evaluated.
+#[derive(Debug)]
+pub struct Unique<'de, I>
+{
+    events: I,
+    policy: OnDuplicate,
+
+    /// Version of the current document, selecting the
+    /// schema keys are resolved with
+    version:    VersionDirective,
+    /// Values of the anchored nodes in the current document
+    anchors:    HashMap<Slice<'de>, Key>,
+    /// Stack of open collections
+    open:       Vec<Open<'de>>,
+    duplicates: Vec<Duplicate>,
+    done:       bool,
+}
+
+impl<'de, I> Unique<'de, I>
+where
+    I: Iterator<Item = Result<Event<'de>>>,
+{
+    /// Instantiate a new [`Unique`] adapter over the given
+    /// events
+    pub fn new(events: I, policy: OnDuplicate) -> Self
+    {
+        Self {
+            events,
+            policy,
+            version: DEFAULT_VERSION,
+            anchors: HashMap::new(),
+            open: Vec::new(),
+            duplicates: Vec::new(),
+            done: false,
+        }
+    }
+
+    /// Every duplicate key found so far, in stream order
+    pub fn duplicates(&self) -> &[Duplicate]
+    {
+        &self.duplicates
+    }
+
+    /// Consume the adapter, returning the underlying
+    /// events
+    pub fn into_inner(self) -> I
+    {
+        self.events
+    }
+
+    fn next_event(&mut self) -> Option<Result<Event<'de>>>
+    {
+        let mut event = match self.events.next()?
+        {
+            Ok(event) => event,
+            Err(e) => return Some(Err(e)),
+        };
+
+        match self.check(&mut event)
+        {
+            Ok(()) => Some(Ok(event)),
+            Err(e) => Some(Err(e)),
+        }
+    }
+
+    /// Update the open collections with the given .event,
+    /// checking any key it completes
+    fn check(&mut self, event: &mut Event<'de>) -> Result<()>
+    {
+        let span = event.span();
+        let build = self
+            .open
+            .last()
+            .map_or(false, |open| open.key() || open.build.is_some());
+
+        match event.data_mut()
+        {
+            EventData::DocumentStart(doc) =>
+            {
+                self.version = doc.directives.version;
+                self.anchors.clear();
+            },
+            EventData::Scalar(node) =>
+            {
+                let key = match build || node.anchor.is_some()
+                {
+                    true =>
+                    {
+                        let scalar = node
+                            .content
+                            .evaluate_by_ref()
+                            .map_err(|e| e.with_mark(span.start))?;
+
+                        let resolved = schema::for_version(self.version)
+                            .resolve(&node.resolved, scalar)
+                            .unwrap_or(Resolved::Str);
+
+                        let key = Key::resolved(resolved, scalar.data());
+
+                        Some(Key::tagged(&node.resolved, key))
+                    },
+                    false => None,
+                };
+
+                return self.complete(key, node.anchor.clone(), span);
+            },
+            EventData::Alias(alias) =>
+            {
+                let key = self
+                    .anchors
+                    .get(&alias.name)
+                    .cloned()
+                    .unwrap_or_else(|| Key::Alias(alias.name.to_string()));
+
+                return self.complete(Some(key), None, span);
+            },
+            EventData::MappingStart(node) => self.open.push(Open {
+                anchor: node.anchor.clone(),
+                tag:    node.resolved.clone(),
+                start:  span,
+                kind:   Kind::Mapping(HashMap::new(), true),
+                build:  (build || node.anchor.is_some()).then(Vec::new),
+            }),
+            EventData::SequenceStart(node) => self.open.push(Open {
+                anchor: node.anchor.clone(),
+                tag:    node.resolved.clone(),
+                start:  span,
+                kind:   Kind::Sequence,
+                build:  (build || node.anchor.is_some()).then(Vec::new),
+            }),
+            EventData::MappingEnd | EventData::SequenceEnd =>
+            {
+                if let Some(open) = self.open.pop()
+                {
+                    let span = Span::new(open.start.start, span.end);
+                    let kind = open.kind;
+                    let tag = open.tag;
+                    let key = open.build.map(|children| {
+                        let key = match kind
+                        {
+                            Kind::Sequence => Key::Sequence(children),
+                            Kind::Mapping(..) => Key::mapping(children),
+                        };
+
+                        Key::tagged(&tag, key)
+                    });
+
+                    return self.complete(key, open.anchor, span);
+                }
+            },
+            _ =>
+            {},
+        }
+
+        Ok(())
+    }
+
+    /// Add a completed node, and its .key if it was built,
+    /// to its parent collection, registering its
+    /// .anchor
+    fn complete(&mut self, key: Option<Key>, anchor: Option<Slice<'de>>, span: Span) -> Result<()>
+    {
+        if let (Some(name), Some(key)) = (anchor, key.as_ref())
+        {
+            self.anchors.insert(name, key.clone());
+        }
+
+        let parent = match self.open.last_mut()
+        {
+            Some(parent) => parent,
+            None => return Ok(()),
+        };
+
+        if let (Some(children), Some(key)) = (parent.build.as_mut(), key.as_ref())
+        {
+            children.push(key.clone());
+        }
+
+        let (keys, next_key) = match parent.kind
+        {
+            Kind::Mapping(ref mut keys, ref mut next_key) => (keys, next_key),
+            Kind::Sequence => return Ok(()),
+        };
+
+        let is_key = *next_key;
+        *next_key = !is_key;
+
+        if !is_key
+        {
+            return Ok(());
+        }
+
+        let key = match key
+        {
+            Some(key) => key,
+            None => return Ok(()),
+        };
+
+        let first = match keys.get(&key)
+        {
+            Some(&first) => first,
+            None =>
+            {
+                keys.insert(key, span);
+
+                return Ok(());
+            },
+        };
+
+        self.duplicates.push(Duplicate {
+            first,
+            second: span,
+        });
+
+        match self.policy
+        {
+            OnDuplicate::Error =>
+            {
+                Err(mkError!(ErrorCode::DuplicateKey, CODE).with_mark(span.start))
+            },
+            OnDuplicate::Report => Ok(()),
+        }
+    }
+}
+
+impl<'de, I> Iterator for Unique<'de, I>
+where
+    I: Iterator<Item = Result<Event<'de>>>,
+{
+    type Item = Result<Event<'de>>;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        if self.done
+        {
+            return None;
+        }
+
+        let next = self.next_event();
+
+        if !matches!(next, Some(Ok(_)))
+        {
+            self.done = true;
+        }
+
+        next
+    }
+}
+
+impl<'de, I> std::iter::FusedIterator for Unique<'de, I> where I: Iterator<Item = Result<Event<'de>>>
+{}
+
+/// A collection whose end has not yet been seen
+#[derive(Debug)]
+struct Open<'de>
+{
+    anchor: Option<Slice<'de>>,
+    tag:    ResolvedTag<'de>,
+    start:  Span,
+    kind:   Kind,
+    /// Values of the collection's children, if the
+    /// collection's own value is needed
+    build:  Option<Vec<Key>>,
+}
+
+impl<'de> Open<'de>
+{
+    /// Is the next child of this collection a mapping key
+    fn key(&self) -> bool
+    {
+        matches!(self.kind, Kind::Mapping(_, true))
+    }
+}
+
+#[derive(Debug)]
+enum Kind
+{
+    Sequence,
+    /// A mapping, the span of each key seen so far, and
+    /// whether its next child is a key
+    Mapping(HashMap<Key, Span>, bool),
+}
+
+/// The value of a node, for comparison with other keys
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum Key
+{
+    Null,
+    Bool(bool),
+    Int(i128),
+    /// The bits of a float, normalized so that equal floats
+    /// have equal bits
+    Float(u64),
+    Str(String),
+    /// An alias to an undefined anchor
+    Alias(String),
+    /// A node with an application specific tag
+    Tagged(String, Box<Key>),
+    Sequence(Vec<Key>),
+    /// A mapping's entries, sorted
+    Mapping(Vec<(Key, Key)>),
+}
+
+impl Key
+{
+    fn resolved(resolved: Resolved, content: &str) -> Self
+    {
+        match resolved
+        {
+            Resolved::Null => Self::Null,
+            Resolved::Bool(b) => Self::Bool(b),
+            Resolved::Int(i) => Self::Int(i.into()),
+            Resolved::UInt(u) => Self::Int(u.into()),
+            Resolved::Float(f) if f.is_nan() => Self::Float(f64::NAN.to_bits()),
+            // Adding zero normalizes -0.0 to 0.0
+            Resolved::Float(f) => Self::Float((f + 0.0).to_bits()),
+            Resolved::Str => Self::Str(content.to_owned()),
+        }
+    }
+
+    /// Mark .key with its node's .tag, if it is application
+    /// specific
+    fn tagged(tag: &ResolvedTag<'_>, key: Self) -> Self
+    {
+        match tag
+        {
+            ResolvedTag::Specific(uri) if !uri.starts_with("tag:yaml.org,2002:") =>
+            {
+                Self::Tagged(uri.to_string(), Box::new(key))
+            },
+            _ => key,
+        }
+    }
+
+    /// Pair up a mapping's .children into its entries,
+    /// ignoring their order
+    fn mapping(children: Vec<Key>) -> Self
+    {
+        let mut children = children.into_iter();
+        let mut entries = Vec::new();
+
+        while let (Some(key), Some(value)) = (children.next(), children.next())
+        {
+            entries.push((key, value));
+        }
+
+        entries.sort();
+
+        Self::Mapping(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{error::ErrorKind, event::from_reader, reader::borrow::BorrowReader};
+
+    /// Find the duplicate keys of the given YAML, as the
+    /// (line, column) of the first and second occurrences
+    fn duplicates(yaml: &str) -> Vec<((usize, usize), (usize, usize))>
+    {
+        let reader = BorrowReader::new(yaml);
+        let mut events = unique_with(from_reader(&reader), OnDuplicate::Report);
+
+        for event in events.by_ref()
+        {
+            event.expect("a valid YAML stream");
+        }
+
+        events
+            .duplicates()
+            .iter()
+            .map(|d| {
+                (
+                    (d.first().start.line, d.first().start.column),
+                    (d.second().start.line, d.second().start.column),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn scalar_keys()
+    {
+        assert_eq!(duplicates("a: 1\nb: 2\na: 3\n"), [((0, 0), (2, 0))]);
+        assert_eq!(duplicates("1: a\n0x1: b\n"), [((0, 0), (1, 0))]);
+        assert_eq!(duplicates("{true: a, True: b}"), [((0, 1), (0, 10))]);
+        assert_eq!(duplicates("{.inf: a, .Inf: b, 0.0: c, -0.0: d}").len(), 2);
+        assert_eq!(duplicates("{~: a, null: b, '': c}").len(), 1);
+
+        assert!(duplicates("1: a\n'1': b\n").is_empty());
+        assert!(duplicates("1: a\n1.0: b\n").is_empty());
+        assert!(duplicates("!!str 1: a\n'1': b\n").len() == 1);
+    }
+
+    #[test]
+    fn schema_by_version()
+    {
+        assert_eq!(duplicates("%YAML 1.1\n---\nyes: a\ntrue: b\n").len(), 1);
+        assert!(duplicates("%YAML 1.2\n---\nyes: a\ntrue: b\n").is_empty());
+    }
+
+    #[test]
+    fn complex_keys()
+    {
+        let yaml = "? [a, b]\n: 1\n? [a, b]\n: 2\n? [b, a]\n: 3\n";
+        assert_eq!(duplicates(yaml), [((0, 2), (2, 2))]);
+
+        let yaml = "? {a: 1, b: [2]}\n: x\n? {b: [2], a: 1}\n: y\n";
+        assert_eq!(duplicates(yaml), [((0, 2), (2, 2))]);
+    }
+
+    #[test]
+    fn alias_keys()
+    {
+        let yaml = "- &k 0x10\n- &s [a]\n- 16: a\n  *k : b\n  ? [a]\n  : c\n  *s : d\n";
+
+        assert_eq!(duplicates(yaml), [((2, 2), (3, 2)), ((4, 4), (6, 2))]);
+    }
+
+    #[test]
+    fn tagged_keys()
+    {
+        assert!(duplicates("!a x: 1\n!b x: 2\nx: 3\n").is_empty());
+        assert_eq!(duplicates("!a x: 1\n!a x: 2\n").len(), 1);
+    }
+
+    #[test]
+    fn scoped_to_mapping()
+    {
+        assert!(duplicates("a: {x: 1}\nb: {x: 2}\nc: [x, x]\n").is_empty());
+        assert!(duplicates("--- {a: 1}\n--- {a: 2}\n").is_empty());
+        assert_eq!(duplicates("a: {x: 1, x: 2}\na: 3\n").len(), 2);
+    }
+
+    #[test]
+    fn error_policy()
+    {
+        let reader = BorrowReader::new("a: 1\nb:\n  c: 2\n  c: 3\n");
+        let mut events = unique(from_reader(&reader));
+
+        let err = events.by_ref().find_map(Result::err).expect("a duplicate");
+
+        assert_eq!(err.kind(), ErrorKind::DuplicateKey);
+        assert_eq!((err.line(), err.column()), (4, 3));
+        assert_eq!(events.duplicates().len(), 1);
+        assert!(events.next().is_none());
+    }
+}
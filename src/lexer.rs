@@ -0,0 +1,509 @@
+/*
+ * This Source Code Form is subject to the terms of the
+ * Mozilla Public License, v. 2.0. If a copy of the MPL
+ * was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+//! This module exposes the lexical tokens of a YAML stream,
+//! the layer beneath [`Events`].
+//!
+//! Where events describe the structure of a YAML document,
+//! tokens describe its syntax: every indicator, directive,
+//! property and scalar in the stream is reported with the
+//! [`Span`] it was read from, making them suitable for
+//! tools like syntax highlighters and lexical linters.
+//!
+//! Note that the token stream is not validated beyond what
+//! is required to tokenize it, for example a stream of
+//! tokens may describe a mapping with a missing value.
+//! Parse the stream as [`Events`] to validate it.
+//!
+//! ## Virtual Tokens
+//!
+//! Some tokens do not correspond to any text in the stream,
+//! and have a zero width [`Span`]:
+//!
+//! - [`StreamStart`] and [`StreamEnd`]
+//! - [`BlockSequenceStart`], [`BlockMappingStart`] and
+//!   [`BlockEnd`], which mark changes in indentation
+//! - [`Key`], for implicit keys (those without a `?`)
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use yary::{
+//!     lexer::{from_reader, TokenData},
+//!     reader::borrow::BorrowReader,
+//! };
+//!
+//! let yaml = BorrowReader::new("key: [a, b]");
+//!
+//! let scalars = from_reader(&yaml)
+//!     .filter_map(|token| match token.unwrap().into_data()
+//!     {
+//!         TokenData::Scalar(scalar) => Some(scalar.evaluate().unwrap().data().to_string()),
+//!         _ => None,
+//!     })
+//!     .collect::<Vec<_>>();
+//!
+//! assert_eq!(scalars, vec!["key", "a", "b"]);
+//! ```
+//!
+//! [`Events`]:             struct@crate::event::Events
+//! [`StreamStart`]:        TokenData::StreamStart
+//! [`StreamEnd`]:          TokenData::StreamEnd
+//! [`BlockSequenceStart`]: TokenData::BlockSequenceStart
+//! [`BlockMappingStart`]:  TokenData::BlockMappingStart
+//! [`BlockEnd`]:           TokenData::BlockEnd
+//! [`Key`]:                TokenData::Key
+
+use crate::{
+    error::{Error, Result},
+    event::{
+        flag::{as_scanner, Flags, O_NIL},
+        limit::Limits,
+        types::{Comment, ScalarLike, Slice, Span, StreamEncoding, VersionDirective},
+    },
+    reader::{Read, Reader},
+    scanner::entry::{MaybeToken, TokenEntry},
+    token::Token as RawToken,
+};
+
+/// Instantiate a new [`Lexer`] from the given read
+/// source, with the default [`Flags`] and no resource
+/// [`Limits`].
+///
+/// ## Examples
+///
+/// ```rust
+/// use yary::{lexer::from_reader, reader::borrow::BorrowReader};
+///
+/// let yaml = BorrowReader::new("[a, b]");
+/// let tokens = from_reader(&yaml).count();
+///
+/// // StreamStart [ a , b ] StreamEnd
+/// assert_eq!(tokens, 7);
+/// ```
+pub fn from_reader<R>(src: &R) -> Lexer<'_, R>
+where
+    R: Read,
+{
+    from_reader_with(src, O_NIL)
+}
+
+/// Instantiate a new [`Lexer`] from the given read source
+/// and [`Flags`], with no resource [`Limits`].
+///
+/// The flags have the same meaning as for an [`Events`]
+/// stream, in particular [`O_LAZY`] defers the processing
+/// of scalars until they are evaluated, and [`O_COMMENTS`]
+/// produces tokens for comments.
+///
+/// [`Events`]:     struct@crate::event::Events
+/// [`O_LAZY`]:     crate::event::flag::O_LAZY
+/// [`O_COMMENTS`]: crate::event::flag::O_COMMENTS
+pub fn from_reader_with<R>(src: &R, flags: Flags) -> Lexer<'_, R>
+where
+    R: Read,
+{
+    Lexer::new(src, flags)
+}
+
+/// Instantiate a new [`Lexer`] from the given read source,
+/// [`Flags`] and [`Limits`].
+///
+/// The lexer fails with an error of [`Category::Limit`]
+/// once the stream exceeds any of the .limits.
+///
+/// [`Category::Limit`]: enum@crate::error::Category
+pub fn from_reader_limited<R>(src: &R, flags: Flags, limits: Limits) -> Lexer<'_, R>
+where
+    R: Read,
+{
+    Lexer::with_limits(src, flags, limits)
+}
+
+/// An iterator over the [`Token`]s of a YAML stream.
+///
+/// The iterator finishes after producing either a
+/// [`StreamEnd`](TokenData::StreamEnd) token, or an error.
+#[derive(Debug)]
+pub struct Lexer<'de, R>
+{
+    reader: Reader<'de, R>,
+    done:   bool,
+}
+
+impl<'de, R> Lexer<'de, R>
+where
+    R: Read,
+{
+    /// Instantiate a new [`Lexer`] from the given read
+    /// source and flags, without any resource limits.
+    pub fn new(src: &'de R, flags: Flags) -> Self
+    {
+        Self::with_limits(src, flags, Limits::unlimited())
+    }
+
+    /// Instantiate a new [`Lexer`] from the given read
+    /// source, flags and limits.
+    pub fn with_limits(src: &'de R, flags: Flags, limits: Limits) -> Self
+    {
+        Self {
+            reader: Reader::with_limits(src, as_scanner(flags), limits),
+            done:   false,
+        }
+    }
+
+    fn next_token(&mut self) -> Result<Option<Token<'de>>>
+    {
+        if self.reader.queue().is_empty()
+        {
+            // Scanner errors occur at the scanner's current position
+            if let Err(e) = self.reader.scan_tokens()
+            {
+                return Err(Error::from(e).with_mark(self.reader.mark()));
+            }
+        }
+
+        let token = match self.reader.queue_mut().pop()
+        {
+            Some(entry) => Token::from_entry(entry)?,
+            None => return Ok(None),
+        };
+
+        if let TokenData::StreamEnd = token.data
+        {
+            self.done = true;
+        }
+
+        Ok(Some(token))
+    }
+}
+
+impl<'de, R> Iterator for Lexer<'de, R>
+where
+    R: Read,
+{
+    type Item = Result<Token<'de>>;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        if self.done
+        {
+            return None;
+        }
+
+        let next = self.next_token().transpose();
+
+        if let Some(Err(_)) | None = next
+        {
+            self.done = true;
+        }
+
+        next
+    }
+}
+
+impl<'de, R> std::iter::FusedIterator for Lexer<'de, R> where R: Read {}
+
+/// A single lexical token, and the [`Span`] of the stream
+/// it was read from.
+#[derive(Debug, Clone)]
+pub struct Token<'de>
+{
+    span: Span,
+    data: TokenData<'de>,
+}
+
+impl<'de> Token<'de>
+{
+    /// The position of this token in the stream
+    pub fn span(&self) -> Span
+    {
+        self.span
+    }
+
+    /// This token's data
+    pub fn data(&self) -> &TokenData<'de>
+    {
+        &self.data
+    }
+
+    /// Mutable access to this token's data, for example to
+    /// evaluate a lazy scalar in place
+    pub fn data_mut(&mut self) -> &mut TokenData<'de>
+    {
+        &mut self.data
+    }
+
+    /// Consume this token, returning its data
+    pub fn into_data(self) -> TokenData<'de>
+    {
+        self.data
+    }
+
+    fn from_entry(entry: TokenEntry<'de>) -> Result<Self>
+    {
+        let span = entry.span();
+
+        let data = match entry.wrap
+        {
+            MaybeToken::Token(token) => TokenData::from(token),
+            // Only scalars can be deferred
+            MaybeToken::Deferred(lazy) => TokenData::Scalar(ScalarLike::lazy(lazy)),
+        };
+
+        Ok(Self { span, data })
+    }
+}
+
+/// The possible tokens of a YAML stream.
+///
+/// New variants may be added in future releases, so
+/// matches over this type must include a wildcard arm.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum TokenData<'de>
+{
+    /// The stream's start, and its encoding {virtual}
+    StreamStart(StreamEncoding),
+    /// The stream's end {virtual}
+    StreamEnd,
+    /// A `%YAML` directive
+    VersionDirective(VersionDirective),
+    /// A `%TAG` directive, its handle and prefix
+    TagDirective(Slice<'de>, Slice<'de>),
+    /// A `---`
+    DocumentStart,
+    /// A `...`
+    DocumentEnd,
+    /// Indentation increase for a block sequence {virtual}
+    BlockSequenceStart,
+    /// Indentation increase for a block mapping {virtual}
+    BlockMappingStart,
+    /// Indentation decrease for a block collection
+    /// {virtual}
+    BlockEnd,
+    /// A `[`
+    FlowSequenceStart,
+    /// A `]`
+    FlowSequenceEnd,
+    /// A `{`
+    FlowMappingStart,
+    /// A `}`
+    FlowMappingEnd,
+    /// A `-`
+    BlockEntry,
+    /// A `,`
+    FlowEntry,
+    /// Either a `?` or nothing {virtual}
+    Key,
+    /// A `:`
+    Value,
+    /// An alias (`*name`), and its name
+    Alias(Slice<'de>),
+    /// An anchor (`&name`), and its name
+    Anchor(Slice<'de>),
+    /// A tag (`!handle!suffix`), its handle and suffix
+    Tag(Slice<'de>, Slice<'de>),
+    /// A scalar, which may be lazily evaluated
+    Scalar(ScalarLike<'de>),
+    /// A comment, only produced if requested via
+    /// [`O_COMMENTS`](crate::event::flag::O_COMMENTS)
+    Comment(Comment<'de>),
+}
+
+impl<'de> From<RawToken<'de>> for TokenData<'de>
+{
+    fn from(token: RawToken<'de>) -> Self
+    {
+        match token
+        {
+            RawToken::StreamStart(encoding) => Self::StreamStart(encoding),
+            RawToken::StreamEnd => Self::StreamEnd,
+            RawToken::VersionDirective(major, minor) =>
+            {
+                Self::VersionDirective(VersionDirective { major, minor })
+            },
+            RawToken::TagDirective(handle, prefix) => Self::TagDirective(handle, prefix),
+            RawToken::DocumentStart => Self::DocumentStart,
+            RawToken::DocumentEnd => Self::DocumentEnd,
+            RawToken::BlockSequenceStart => Self::BlockSequenceStart,
+            RawToken::BlockMappingStart => Self::BlockMappingStart,
+            RawToken::BlockEnd => Self::BlockEnd,
+            RawToken::FlowSequenceStart => Self::FlowSequenceStart,
+            RawToken::FlowSequenceEnd => Self::FlowSequenceEnd,
+            RawToken::FlowMappingStart => Self::FlowMappingStart,
+            RawToken::FlowMappingEnd => Self::FlowMappingEnd,
+            RawToken::BlockEntry => Self::BlockEntry,
+            RawToken::FlowEntry => Self::FlowEntry,
+            RawToken::Key => Self::Key,
+            RawToken::Value => Self::Value,
+            RawToken::Alias(name) => Self::Alias(name),
+            RawToken::Anchor(name) => Self::Anchor(name),
+            RawToken::Tag(handle, suffix) => Self::Tag(handle, suffix),
+            RawToken::Scalar(data, style) => Self::Scalar(ScalarLike::eager(data, style)),
+            RawToken::Comment(text, placement) => Self::Comment(Comment { text, placement }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        event::{
+            flag::{O_COMMENTS, O_LAZY},
+            types::{CommentPlacement, ScalarStyle},
+        },
+        reader::borrow::BorrowReader,
+    };
+
+    /// Summarizes each token of .yaml as a short string
+    fn tokens(yaml: &str, flags: Flags) -> Vec<String>
+    {
+        let reader = BorrowReader::new(yaml);
+
+        Lexer::new(&reader, flags)
+            .map(|token| match token.unwrap().into_data()
+            {
+                TokenData::Scalar(scalar) => format!("={}", scalar.evaluate().unwrap().data()),
+                TokenData::Comment(c) => format!("#{}", c.text),
+                TokenData::Anchor(name) => format!("&{}", name),
+                TokenData::Alias(name) => format!("*{}", name),
+                TokenData::Tag(handle, suffix) => format!("!{}{}", handle, suffix),
+                other => format!("{:?}", other),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn block_mapping()
+    {
+        let expected = vec![
+            "StreamStart(UTF8)",
+            "BlockMappingStart",
+            "Key",
+            "=key",
+            "Value",
+            "&a",
+            "!!!str",
+            "=value",
+            "Key",
+            "=alias",
+            "Value",
+            "*a",
+            "BlockEnd",
+            "StreamEnd",
+        ];
+
+        assert_eq!(tokens("key: &a !!str value\nalias: *a\n", O_NIL), expected);
+    }
+
+    #[test]
+    fn flow_sequence_spans()
+    {
+        let reader = BorrowReader::new("[a, 'b']");
+        let spans: Vec<_> = from_reader(&reader)
+            .map(|token| token.unwrap().span())
+            .map(|span| (span.start.offset, span.end.offset))
+            .collect();
+
+        assert_eq!(
+            spans,
+            vec![(0, 0), (0, 1), (1, 2), (2, 3), (4, 7), (7, 8), (8, 8)]
+        );
+    }
+
+    #[test]
+    fn lazy_scalars()
+    {
+        let reader = BorrowReader::new("- \"lazy\\tscalar\"\n- |\n  block\n- plain\n");
+        let mut scalars = Vec::new();
+
+        for token in Lexer::new(&reader, O_LAZY)
+        {
+            if let TokenData::Scalar(mut scalar) = token.unwrap().into_data()
+            {
+                assert!(scalar.is_unevaluated());
+
+                let evaluated = scalar.evaluate_by_ref().unwrap();
+                scalars.push((evaluated.data().to_string(), evaluated.style()));
+            }
+        }
+
+        assert_eq!(
+            scalars,
+            vec![
+                ("lazy\tscalar".to_string(), ScalarStyle::DoubleQuote),
+                ("block\n".to_string(), ScalarStyle::Literal),
+                ("plain".to_string(), ScalarStyle::Plain)
+            ]
+        );
+    }
+
+    #[test]
+    fn comments()
+    {
+        let reader = BorrowReader::new("# own\nkey: value # trailing\n");
+
+        let comments: Vec<_> = Lexer::new(&reader, O_COMMENTS)
+            .filter_map(|token| match token.unwrap().into_data()
+            {
+                TokenData::Comment(c) => Some((c.text.to_string(), c.placement)),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(
+            comments,
+            vec![
+                (" own".to_string(), CommentPlacement::OwnLine),
+                (" trailing".to_string(), CommentPlacement::Trailing)
+            ]
+        );
+
+        assert!(!tokens("# own\nkey: value\n", O_NIL)
+            .iter()
+            .any(|t| t.starts_with('#')));
+    }
+
+    #[test]
+    fn directives()
+    {
+        let expected = vec![
+            "StreamStart(UTF8)",
+            "VersionDirective(VersionDirective { major: 1, minor: 2 })",
+            "TagDirective(\"!e!\", \"tag:example.com:\")",
+            "DocumentStart",
+            "=doc",
+            "DocumentEnd",
+            "StreamEnd",
+        ];
+
+        assert_eq!(
+            tokens(
+                "%YAML 1.2\n%TAG !e! tag:example.com:\n--- doc\n...\n",
+                O_NIL
+            ),
+            expected
+        );
+    }
+
+    #[test]
+    fn error_fuses()
+    {
+        let reader = BorrowReader::new("key: \"unterminated");
+        let mut lexer = from_reader(&reader);
+
+        let err = lexer.find_map(Result::err).expect("a scanner error");
+
+        assert!(err.has_context());
+        assert_eq!(err.line(), 1);
+        assert!(lexer.next().is_none());
+    }
+}
@@ -21,9 +21,19 @@
 
 pub use error::Error;
 
+pub mod cst;
+#[cfg(feature = "serde")]
+pub mod de;
+pub mod diagnostic;
+pub mod emit;
 pub mod error;
 pub mod event;
+pub mod lexer;
 pub mod reader;
+pub mod schema;
+#[cfg(feature = "serde")]
+pub mod ser;
+pub mod tree;
 
 mod queue;
 mod scanner;
@@ -88,6 +88,13 @@ where
         self.heap.reserve(additional)
     }
 
+    /// Iterate over the queued items, in no particular
+    /// order
+    pub fn iter(&self) -> impl Iterator<Item = &T>
+    {
+        self.heap.iter().map(|Reverse(item)| &item.entry)
+    }
+
     fn increment(&mut self) -> usize
     {
         self.increment += 1;
@@ -366,7 +373,7 @@ mod tests
     {
         fn partial_cmp(&self, other: &T) -> Option<std::cmp::Ordering>
         {
-            self.ord.partial_cmp(&other.ord)
+            Some(self.cmp(other))
         }
     }
 
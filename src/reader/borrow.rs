@@ -7,17 +7,28 @@
 //! Contains an implementation of [`Read`](super::Read) for
 //! borrowed UTF8 slices (`&str`s).
 
+use std::borrow::Cow;
+
 use super::{
+    encoding,
     error::{ReadError, ReaderResult},
     private, Read, ReadContext, Reader,
 };
-use crate::scanner::flag::{Flags, O_EXTENDABLE};
+use crate::{
+    event::types::StreamEncoding,
+    scanner::flag::{Flags, O_EXTENDABLE},
+};
 
 /// A [`Read`](super::Read) implementor for borrows.
+///
+/// Byte slices that are not UTF8 encoded are transcoded
+/// into an owned buffer, otherwise the slice is borrowed
+/// directly.
 #[derive(Debug, Clone)]
 pub struct BorrowReader<'de>
 {
-    data: &'de str,
+    data:     Cow<'de, str>,
+    encoding: StreamEncoding,
 }
 
 impl<'de> BorrowReader<'de>
@@ -26,21 +37,31 @@ impl<'de> BorrowReader<'de>
     /// UTF8 slice
     pub fn new(data: &'de str) -> Self
     {
-        Self { data }
+        Self {
+            data:     Cow::Borrowed(data),
+            encoding: StreamEncoding::UTF8,
+        }
     }
 
     /// Instantiate a new [`BorrowReader`] from the given
     /// bytes, returning an error if they are not
-    /// valid UTF8.
+    /// validly encoded.
     ///
-    /// This is a small wrapper around [`BorrowReader::new`]
-    /// which attempts to assert that the underlying
-    /// byte slice is UTF8.
+    /// The bytes' encoding is detected from their byte
+    /// order mark, or initial characters, and any non
+    /// UTF8 bytes are transcoded.
     pub(crate) fn try_from_bytes(data: &'de [u8]) -> ReaderResult<Self>
     {
-        let this = std::str::from_utf8(data).map(Self::new)?;
+        let (encoding, bom) = encoding::detect(data);
+        let data = &data[bom..];
 
-        Ok(this)
+        let data = match encoding
+        {
+            StreamEncoding::UTF8 => std::str::from_utf8(data).map(Cow::Borrowed)?,
+            _ => encoding::decode_all(encoding, data).map(Cow::Owned)?,
+        };
+
+        Ok(Self { data, encoding })
     }
 
     pub(crate) fn new_reader(&'de self, opts: Flags) -> Reader<'de, Self>
@@ -53,10 +74,12 @@ impl<'a> Read for BorrowReader<'a>
 {
     fn drive<'de>(&'de self, cxt: ReadContext<'_, '_, 'de>) -> Result<(), ReadError>
     {
+        cxt.scanner.set_encoding(self.encoding);
+
         // This implementation is never extendable, so we remove the
         // option from the set if it exists
         cxt.scanner
-            .scan_tokens(cxt.flags & !O_EXTENDABLE, self.data, cxt.queue)?;
+            .scan_tokens(cxt.flags & !O_EXTENDABLE, &self.data, cxt.queue)?;
 
         Ok(())
     }
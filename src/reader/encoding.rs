@@ -0,0 +1,339 @@
+/*
+ * This Source Code Form is subject to the terms of the
+ * Mozilla Public License, v. 2.0. If a copy of the MPL
+ * was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+//! Detection and transcoding of the byte encodings a YAML
+//! stream may use.
+//!
+//! YAML streams may be encoded as UTF8, UTF16 or UTF32,
+//! with the latter two in either endianness. The encoding
+//! is detected from the stream's byte order mark (BOM), or
+//! failing that the pattern of null bytes in its first
+//! character, as described in [YAML 1.2 §5.2].
+//!
+//! Non UTF8 streams are transcoded to UTF8 before they are
+//! scanned, therefore any offsets reported in [`Mark`]s
+//! refer to the transcoded stream.
+//!
+//! [YAML 1.2 §5.2]: https://yaml.org/spec/1.2.2/#52-character-encodings
+//! [`Mark`]: struct@crate::event::types::Mark
+
+use crate::{
+    event::types::StreamEncoding,
+    reader::error::{ReaderError, ReaderResult},
+};
+
+/// Detect the encoding of the stream starting with .bytes,
+/// returning it and the length of its byte order mark (if
+/// any).
+///
+/// Callers should provide at least 4 bytes, unless the
+/// stream is shorter than this.
+pub(crate) fn detect(bytes: &[u8]) -> (StreamEncoding, usize)
+{
+    use StreamEncoding::*;
+
+    match bytes
+    {
+        [0x00, 0x00, 0xFE, 0xFF, ..] => (UTF32BE, 4),
+        [0x00, 0x00, 0x00, _, ..] => (UTF32BE, 0),
+        [0xFF, 0xFE, 0x00, 0x00, ..] => (UTF32LE, 4),
+        [_, 0x00, 0x00, 0x00, ..] => (UTF32LE, 0),
+        [0xFE, 0xFF, ..] => (UTF16BE, 2),
+        [0x00, _, ..] => (UTF16BE, 0),
+        [0xFF, 0xFE, ..] => (UTF16LE, 2),
+        [_, 0x00, ..] => (UTF16LE, 0),
+        [0xEF, 0xBB, 0xBF, ..] => (UTF8, 3),
+        _ => (UTF8, 0),
+    }
+}
+
+/// Decode as many complete characters from .bytes as
+/// possible, appending them to .out, and returning the
+/// number of bytes decoded.
+///
+/// Any trailing bytes which form an incomplete character
+/// are left undecoded, and should be provided again once
+/// the rest of the character is available.
+pub(crate) fn decode(
+    encoding: StreamEncoding,
+    bytes: &[u8],
+    out: &mut String,
+) -> ReaderResult<usize>
+{
+    use StreamEncoding::*;
+
+    match encoding
+    {
+        UTF8 => decode_utf8(bytes, out),
+        UTF16LE => decode_utf16(bytes, out, encoding, u16::from_le_bytes),
+        UTF16BE => decode_utf16(bytes, out, encoding, u16::from_be_bytes),
+        UTF32LE => decode_utf32(bytes, out, encoding, u32::from_le_bytes),
+        UTF32BE => decode_utf32(bytes, out, encoding, u32::from_be_bytes),
+    }
+}
+
+/// Decode the entirety of .bytes, with the given
+/// .encoding, erroring if the bytes end with an incomplete
+/// character.
+pub(crate) fn decode_all(encoding: StreamEncoding, bytes: &[u8]) -> ReaderResult<String>
+{
+    let mut out = String::with_capacity(bytes.len());
+    let amt = decode(encoding, bytes, &mut out)?;
+
+    incomplete(encoding, &bytes[amt..])?;
+
+    Ok(out)
+}
+
+/// Check that no .trailing bytes are left over once a
+/// stream is exhausted.
+pub(crate) fn incomplete(encoding: StreamEncoding, trailing: &[u8]) -> ReaderResult<()>
+{
+    match (encoding, trailing.is_empty())
+    {
+        (_, true) => Ok(()),
+        // Let std produce the UTF8 error for us
+        (StreamEncoding::UTF8, false) =>
+        {
+            std::str::from_utf8(trailing).map(drop).map_err(Into::into)
+        },
+        (encoding, false) => Err(ReaderError::Encoding(encoding)),
+    }
+}
+
+fn decode_utf8(bytes: &[u8], out: &mut String) -> ReaderResult<usize>
+{
+    let valid = match std::str::from_utf8(bytes)
+    {
+        Ok(s) => s,
+        // An error without a length is an incomplete character at the end of .bytes
+        Err(e) if e.error_len().is_none() =>
+        {
+            // SAFETY: std has validated .bytes up to this point
+            unsafe { std::str::from_utf8_unchecked(&bytes[..e.valid_up_to()]) }
+        },
+        Err(e) => return Err(e.into()),
+    };
+
+    out.push_str(valid);
+
+    Ok(valid.len())
+}
+
+fn decode_utf16(
+    bytes: &[u8],
+    out: &mut String,
+    encoding: StreamEncoding,
+    unit: fn([u8; 2]) -> u16,
+) -> ReaderResult<usize>
+{
+    let err = || ReaderError::Encoding(encoding);
+    let unit_at = |i: usize| unit([bytes[i], bytes[i + 1]]) as u32;
+    let mut i = 0;
+
+    while i + 2 <= bytes.len()
+    {
+        let high = unit_at(i);
+
+        let (c, len) = match high
+        {
+            // Leading surrogate, the following unit must be a trailing surrogate
+            0xD800..=0xDBFF =>
+            {
+                if i + 4 > bytes.len()
+                {
+                    break;
+                }
+
+                let low = unit_at(i + 2);
+                if !(0xDC00..=0xDFFF).contains(&low)
+                {
+                    return Err(err());
+                }
+
+                (0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00), 4)
+            },
+            // Unpaired trailing surrogate
+            0xDC00..=0xDFFF => return Err(err()),
+            c => (c, 2),
+        };
+
+        out.push(std::char::from_u32(c).ok_or_else(err)?);
+        i += len;
+    }
+
+    Ok(i)
+}
+
+fn decode_utf32(
+    bytes: &[u8],
+    out: &mut String,
+    encoding: StreamEncoding,
+    unit: fn([u8; 4]) -> u32,
+) -> ReaderResult<usize>
+{
+    let mut i = 0;
+
+    while i + 4 <= bytes.len()
+    {
+        let c = unit([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]]);
+
+        out.push(std::char::from_u32(c).ok_or(ReaderError::Encoding(encoding))?);
+        i += 4;
+    }
+
+    Ok(i)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const YAML: &str = "key: [värde, 🦀]\n";
+
+    fn utf16(data: &str, bom: bool, be: bool) -> Vec<u8>
+    {
+        let data = match bom
+        {
+            true => format!("\u{FEFF}{}", data),
+            false => data.to_string(),
+        };
+
+        data.encode_utf16()
+            .flat_map(|u| match be
+            {
+                true => u.to_be_bytes(),
+                false => u.to_le_bytes(),
+            })
+            .collect()
+    }
+
+    fn utf32(data: &str, bom: bool, be: bool) -> Vec<u8>
+    {
+        std::iter::once('\u{FEFF}')
+            .filter(|_| bom)
+            .chain(data.chars())
+            .flat_map(|c| match be
+            {
+                true => (c as u32).to_be_bytes(),
+                false => (c as u32).to_le_bytes(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn detect_bom()
+    {
+        use StreamEncoding::*;
+
+        let tests: &[(Vec<u8>, (StreamEncoding, usize))] = &[
+            (utf16(YAML, true, false), (UTF16LE, 2)),
+            (utf16(YAML, true, true), (UTF16BE, 2)),
+            (utf32(YAML, true, false), (UTF32LE, 4)),
+            (utf32(YAML, true, true), (UTF32BE, 4)),
+            ([b"\xEF\xBB\xBF", YAML.as_bytes()].concat(), (UTF8, 3)),
+        ];
+
+        for (bytes, expected) in tests
+        {
+            assert_eq!(detect(bytes), *expected);
+        }
+    }
+
+    #[test]
+    fn detect_null_pattern()
+    {
+        use StreamEncoding::*;
+
+        let tests: &[(Vec<u8>, StreamEncoding)] = &[
+            (utf16(YAML, false, false), UTF16LE),
+            (utf16(YAML, false, true), UTF16BE),
+            (utf32(YAML, false, false), UTF32LE),
+            (utf32(YAML, false, true), UTF32BE),
+            (YAML.as_bytes().to_vec(), UTF8),
+            (Vec::new(), UTF8),
+        ];
+
+        for (bytes, expected) in tests
+        {
+            assert_eq!(detect(bytes), (*expected, 0));
+        }
+    }
+
+    #[test]
+    fn decode_encodings()
+    {
+        let tests = [
+            utf16(YAML, false, false),
+            utf16(YAML, false, true),
+            utf32(YAML, false, false),
+            utf32(YAML, false, true),
+            YAML.as_bytes().to_vec(),
+        ];
+
+        for bytes in tests.iter()
+        {
+            let (encoding, _) = detect(bytes);
+
+            assert_eq!(decode_all(encoding, bytes).unwrap(), YAML);
+        }
+    }
+
+    #[test]
+    fn decode_split()
+    {
+        let tests = [
+            (StreamEncoding::UTF8, YAML.as_bytes().to_vec()),
+            (StreamEncoding::UTF16LE, utf16(YAML, false, false)),
+            (StreamEncoding::UTF32BE, utf32(YAML, false, true)),
+        ];
+
+        // Split at every byte, including those inside a character
+        for (encoding, bytes) in tests.iter()
+        {
+            for at in 0..bytes.len()
+            {
+                let mut out = String::new();
+                let amt = decode(*encoding, &bytes[..at], &mut out).unwrap();
+
+                assert_eq!(incomplete(*encoding, &bytes[amt..at]).is_err(), amt != at);
+
+                let rest = decode(*encoding, &bytes[amt..], &mut out).unwrap();
+
+                assert_eq!(amt + rest, bytes.len());
+                assert_eq!(out, YAML);
+            }
+        }
+    }
+
+    #[test]
+    fn decode_invalid()
+    {
+        // Unpaired surrogates
+        assert!(decode(
+            StreamEncoding::UTF16LE,
+            &[0x00, 0xDC, b'a', 0x00],
+            &mut String::new()
+        )
+        .is_err());
+        assert!(decode(
+            StreamEncoding::UTF16BE,
+            &[0xD8, 0x00, 0x00, b'a'],
+            &mut String::new()
+        )
+        .is_err());
+        // Out of range code point
+        assert!(decode(
+            StreamEncoding::UTF32LE,
+            &[0x00, 0x00, 0x11, 0x00],
+            &mut String::new()
+        )
+        .is_err());
+    }
+}
@@ -11,6 +11,7 @@ use std::{error::Error as StdError, fmt, io, str::Utf8Error};
 
 use crate::{
     error::internal::{ErrorCode, ErrorKind, SourceError},
+    event::types::StreamEncoding,
     scanner::error::ScanError,
 };
 
@@ -27,6 +28,9 @@ pub(crate) enum ReaderError
     /// Catch all wrapper for any underlying IO errors
     /// reported to us
     IO(io::Error),
+    /// Encountered an invalid sequence in a non UTF8 byte
+    /// stream
+    Encoding(StreamEncoding),
     Scanner(ScanError),
 }
 
@@ -46,6 +50,7 @@ impl StdError for ReaderError
         {
             ReaderError::UTF8(ref e) => Some(e),
             ReaderError::IO(ref e) => Some(e),
+            ReaderError::Encoding(_) => None,
             ReaderError::Scanner(ref e) => Some(e),
         }
     }
@@ -83,6 +88,7 @@ impl From<ReaderError> for ErrorKind
         {
             ReaderError::UTF8(e) => SourceError::UTF8(e).into(),
             ReaderError::IO(e) => SourceError::IO(e).into(),
+            ReaderError::Encoding(_) => ErrorCode::InvalidEncoding.into(),
             ReaderError::Scanner(e) => ErrorCode::from(e).into(),
         }
     }
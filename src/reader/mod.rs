@@ -21,10 +21,14 @@
 pub mod borrow;
 pub mod owned;
 
+pub(crate) mod encoding;
 pub(crate) mod error;
 
+use std::collections::VecDeque;
+
 use crate::{
     error::Error,
+    event::{limit::Limits, types::Mark},
     queue::Queue,
     reader::{
         borrow::BorrowReader,
@@ -33,6 +37,7 @@ use crate::{
         private::Sealed,
     },
     scanner::{entry::TokenEntry, flag::Flags as ScannerFlags, Scanner},
+    token::Marker,
 };
 
 /// Instantiate a new [`Read`]er from the given UTF8 string
@@ -55,6 +60,9 @@ pub fn from_utf8(utf8: &str) -> BorrowReader<'_>
 /// Instantiate a new [`Read`]er from the given
 /// [`std::io::Read`] source.
 ///
+/// The source may be encoded as UTF8, UTF16 or UTF32, which
+/// is detected from the first bytes read.
+///
 /// ## Examples
 ///
 /// ```no_run
@@ -77,10 +85,15 @@ where
 /// Try instantiate a new [`Read`]er from the given byte
 /// slice.
 ///
+/// The slice may be encoded as UTF8, UTF16 or UTF32, which
+/// is detected from its byte order mark or initial
+/// characters. UTF16 and UTF32 slices are transcoded to
+/// UTF8.
+///
 /// ## Errors
 ///
 /// This function will error if the provided byte slice is
-/// not valid UTF8
+/// not validly encoded
 ///
 /// ## Examples
 ///
@@ -171,10 +184,17 @@ impl<'de, T> Reader<'de, T>
 where
     T: Read,
 {
+    /// Instantiate a new Reader, without any resource
+    /// limits
     pub fn new(read: &'de T, opts: ScannerFlags) -> Self
+    {
+        Self::with_limits(read, opts, Limits::unlimited())
+    }
+
+    pub fn with_limits(read: &'de T, opts: ScannerFlags, limits: Limits) -> Self
     {
         Self {
-            scanner:   Scanner::new(),
+            scanner:   Scanner::with_limits(limits),
             queue:     Queue::new(),
             options:   opts,
             exhausted: false,
@@ -202,6 +222,35 @@ where
         self.exhausted && self.queue.is_empty()
     }
 
+    /// The current position of the underlying Scanner
+    pub fn mark(&self) -> Mark
+    {
+        self.scanner.mark()
+    }
+
+    /// The lowest stream offset that may still be
+    /// referenced by any queued token
+    pub fn bound(&self) -> usize
+    {
+        self.queue
+            .iter()
+            .map(|entry| entry.span().start.offset)
+            .fold(self.scanner.mark().offset, usize::min)
+    }
+
+    /// The flags passed to the Scanner on each scan
+    pub fn options(&self) -> ScannerFlags
+    {
+        self.options
+    }
+
+    /// Replace the flags used for any future scans, tokens
+    /// already in the queue are unaffected
+    pub fn set_options(&mut self, opts: ScannerFlags)
+    {
+        self.options = opts
+    }
+
     pub fn queue_mut(&mut self) -> &mut Queue<TokenEntry<'de>>
     {
         &mut self.queue
@@ -232,8 +281,11 @@ where
 #[derive(Debug)]
 pub(crate) struct PeekReader<'de, T: 'de>
 {
-    peek:   Option<TokenEntry<'de>>,
-    reader: Reader<'de, T>,
+    peek:     Option<TokenEntry<'de>>,
+    reader:   Reader<'de, T>,
+    /// Comments found while retrieving tokens, which are
+    /// kept out of the token stream
+    comments: VecDeque<TokenEntry<'de>>,
 }
 
 impl<'de, T> PeekReader<'de, T>
@@ -242,7 +294,11 @@ where
 {
     pub fn new(reader: Reader<'de, T>) -> Self
     {
-        Self { peek: None, reader }
+        Self {
+            peek: None,
+            reader,
+            comments: VecDeque::new(),
+        }
     }
 
     pub fn pop(&mut self) -> ReaderResult<Option<TokenEntry<'de>>>
@@ -280,9 +336,61 @@ where
         self.peek.take().is_some()
     }
 
+    /// Take the oldest comment found, if any, and if it
+    /// starts before the given .offset
+    pub fn pop_comment(&mut self, before: Option<usize>) -> Option<TokenEntry<'de>>
+    {
+        let start = self.comments.front()?.span().start.offset;
+
+        match before
+        {
+            Some(offset) if start >= offset => None,
+            _ => self.comments.pop_front(),
+        }
+    }
+
+    /// The current position of the underlying Scanner
+    pub fn mark(&self) -> Mark
+    {
+        self.reader.mark()
+    }
+
+    /// The position of the peeked token if one exists,
+    /// otherwise the current position of the underlying
+    /// Scanner
+    pub fn position(&self) -> Mark
+    {
+        self.peek
+            .as_ref()
+            .map_or_else(|| self.reader.mark(), |entry| entry.span().start)
+    }
+
+    /// The flags passed to the Scanner on each scan
+    pub fn options(&self) -> ScannerFlags
+    {
+        self.reader.options()
+    }
+
+    /// Replace the flags used for any future scans
+    pub fn set_options(&mut self, opts: ScannerFlags)
+    {
+        self.reader.set_options(opts)
+    }
+
+    /// The lowest stream offset that may still be
+    /// referenced by any live token
+    pub fn bound(&self) -> usize
+    {
+        self.peek
+            .iter()
+            .chain(self.comments.iter())
+            .map(|entry| entry.span().start.offset)
+            .fold(self.reader.bound(), usize::min)
+    }
+
     pub fn into_inner(self) -> (Reader<'de, T>, Option<TokenEntry<'de>>)
     {
-        let Self { peek, reader } = self;
+        let Self { peek, reader, .. } = self;
 
         (reader, peek)
     }
@@ -300,16 +408,28 @@ where
             return Ok(());
         }
 
-        // If the queue is empty, make an attempt to retrieve more
-        // tokens from the Reader
-        if self.reader.queue_mut().is_empty()
+        loop
         {
-            self.reader.scan_tokens()?;
-        }
+            // If the queue is empty, make an attempt to retrieve more
+            // tokens from the Reader
+            if self.reader.queue_mut().is_empty()
+            {
+                self.reader.scan_tokens()?;
+            }
 
-        self.peek = self.reader.queue_mut().pop();
+            match self.reader.queue_mut().pop()
+            {
+                // Divert comments, they are not part of the token
+                // stream the Parser expects
+                Some(entry) if entry.marker() == Marker::Comment => self.comments.push_back(entry),
+                entry =>
+                {
+                    self.peek = entry;
 
-        Ok(())
+                    return Ok(());
+                },
+            }
+        }
     }
 }
 
@@ -6,15 +6,21 @@
 
 //! Contains an implementation of [`Read`](super::Read) for
 //! [`std::io::Read`] objects.
+//!
+//! The encoding of the byte stream is detected from its
+//! first bytes, and any non UTF8 bytes are transcoded as
+//! they are read.
 
-use std::{cell::UnsafeCell, fmt, io};
+use std::{cell::UnsafeCell, collections::VecDeque, fmt, io};
 
 use super::{
+    encoding,
     error::{ReadError, ReaderResult},
     private::Sealed,
     Read, ReadContext, Reader,
 };
 use crate::{
+    event::types::StreamEncoding,
     queue::Queue,
     scanner::{
         entry::TokenEntry,
@@ -52,6 +58,15 @@ impl OwnedReader
         Reader::new(self, opts)
     }
 
+    /// Total bytes currently held in memory
+    #[cfg(test)]
+    pub(crate) fn buffered(&self) -> usize
+    {
+        let inner: &Impl = unsafe { &*self.inner.inner.get() };
+
+        inner.head.len() + inner.tail.iter().map(|t| t.data.len()).sum::<usize>()
+    }
+
     fn drive_scanner<'de>(
         &'de self,
         scanner: &mut Scanner,
@@ -59,6 +74,20 @@ impl OwnedReader
         mut opts: Flags,
     ) -> ReaderResult<()>
     {
+        // We must detect the stream's encoding before scanning
+        // the stream start
+        let encoding = match self.inner.encoding()
+        {
+            Some(encoding) => encoding,
+            None =>
+            {
+                self.inner.read_next_chunk(None)?;
+
+                self.inner.encoding().unwrap_or(StreamEncoding::UTF8)
+            },
+        };
+        scanner.set_encoding(encoding);
+
         loop
         {
             match self.inner.is_exhausted()
@@ -93,8 +122,10 @@ impl Read for OwnedReader
             .map_err(Into::into)
     }
 
-    unsafe fn consume(&self, _bound: usize) -> Result<(), ReadError>
+    unsafe fn consume(&self, bound: usize) -> Result<(), ReadError>
     {
+        self.inner.release_below(bound);
+
         Ok(())
     }
 }
@@ -139,8 +170,8 @@ impl ReadHolder
         // This section REQUIRES the following invariants:
         //
         //  1. Impl's .head and .tail(s) never perform any operation
-        //     that could invalidate references
-        //     (realloc of any kind)
+        //     that could invalidate references (realloc of any
+        //     kind)
         //  2. Impl must not drop any of the allocated data before
         //     ReadHolder (ourselves) is dropped
         let inner: &Impl = unsafe { &*self.inner.get() };
@@ -154,12 +185,48 @@ impl ReadHolder
 
         inner.exhausted
     }
+
+    fn encoding(&self) -> Option<StreamEncoding>
+    {
+        let inner: &Impl = unsafe { &*self.inner.get() };
+
+        inner.encoding
+    }
+
+    /// Drop any retired buffers that only contain data
+    /// below the given stream .bound.
+    ///
+    /// ## Safety
+    ///
+    /// The caller must ensure that no references exist to
+    /// any data read below .bound, as the memory backing
+    /// these references will be deallocated.
+    unsafe fn release_below(&self, bound: usize)
+    {
+        // SAFETY:
+        //
+        // The caller upholds that nothing references the tails
+        // we release, and .head (the only buffer the Scanner
+        // may still be reading from) is never released
+        let inner: &mut Impl = &mut *self.inner.get();
+
+        inner.release_below(bound)
+    }
 }
 
 struct Impl
 {
     head: String,
-    tail: Vec<String>,
+    tail: VecDeque<Retired>,
+
+    /// Stream offset of .head's first byte
+    head_start: usize,
+
+    /// Bytes read from .source which have not yet been
+    /// decoded
+    raw:      Vec<u8>,
+    /// Encoding of .source, once detected
+    encoding: Option<StreamEncoding>,
 
     source:    Box<dyn io::Read + 'static>,
     exhausted: bool,
@@ -175,7 +242,12 @@ impl Impl
 
         Self {
             head: String::new(),
-            tail: Vec::new(),
+            tail: VecDeque::new(),
+
+            head_start: 0,
+
+            raw: Vec::new(),
+            encoding: None,
 
             source,
             exhausted: false,
@@ -189,31 +261,75 @@ impl Impl
 
     fn refresh_buffer(&mut self, copy_from: Option<usize>) -> ReaderResult<()>
     {
-        // Calculate next allocation chunk
-        let cap = (DEFAULT_BUFFER_SIZE * usize::max(self.tail.len(), 1) + copy_from.unwrap_or(0))
-            .next_power_of_two();
-        let mut new = Vec::new();
+        // Calculate next allocation chunk, leaving room for any
+        // unread data we carry over
+        let unread = copy_from.map_or(0, |mark| self.head.len() - mark);
+        let cap =
+            (DEFAULT_BUFFER_SIZE * usize::max(self.tail.len(), 1) + unread).next_power_of_two();
+        let mut new = String::new();
 
         // Copy any data that is marked as unread into the next
         // buffer
         if let Some(mark) = copy_from
         {
-            new.extend_from_slice(&self.head.as_bytes()[mark..]);
+            new.push_str(&self.head[mark..]);
         }
 
-        // Fill the new buffer, checking if .src has been exhausted
-        self.exhausted = read_fill(Take::new(&mut self.source, cap), &mut new)?;
+        // Fill the raw buffer, checking if .src has been exhausted
+        self.exhausted = read_fill(Take::new(&mut self.source, cap), &mut self.raw)?;
+
+        // Detect the stream's encoding from its first bytes,
+        // skipping any byte order mark
+        let encoding = match self.encoding
+        {
+            Some(encoding) => encoding,
+            None =>
+            {
+                let (encoding, bom) = encoding::detect(&self.raw);
+                self.raw.drain(..bom);
+                self.encoding = Some(encoding);
+
+                encoding
+            },
+        };
 
-        // Validate buffer is UTF8
-        let new = String::from_utf8(new).map_err(|e| e.utf8_error())?;
+        // Decode the buffer into UTF8, retaining any incomplete
+        // character for the next refresh
+        let amt = encoding::decode(encoding, &self.raw, &mut new)?;
+        self.raw.drain(..amt);
+
+        if self.exhausted
+        {
+            encoding::incomplete(encoding, &self.raw)?;
+        }
 
         // Swap the new and old heads, pushing the old head into the
         // held tails
+        let retired_at = self.head_start + copy_from.unwrap_or(self.head.len());
         let old = std::mem::replace(&mut self.head, new);
-        self.tail.push(old);
+        self.tail.push_back(Retired {
+            data: old,
+            retired_at,
+        });
+        self.head_start = retired_at;
 
         Ok(())
     }
+
+    /// Release any tails which were retired at or below the
+    /// given stream .bound
+    fn release_below(&mut self, bound: usize)
+    {
+        while let Some(Retired { retired_at, .. }) = self.tail.front()
+        {
+            if *retired_at > bound
+            {
+                break;
+            }
+
+            self.tail.pop_front();
+        }
+    }
 }
 
 impl fmt::Debug for Impl
@@ -223,12 +339,28 @@ impl fmt::Debug for Impl
         f.debug_struct("Impl")
             .field("head", &self.head)
             .field("tail", &self.tail)
+            .field("head_start", &self.head_start)
+            .field("raw", &self.raw)
+            .field("encoding", &self.encoding)
             .field("source", &"dyn <std::io::Read>")
             .field("exhausted", &self.exhausted)
             .finish()
     }
 }
 
+/// A buffer which has been superseded by a newer head.
+///
+/// Any data at or above .retired_at (as a stream offset)
+/// was copied into the next buffer, so once nothing
+/// references data below .retired_at, this buffer may be
+/// dropped.
+#[derive(Debug)]
+struct Retired
+{
+    data:       String,
+    retired_at: usize,
+}
+
 fn read_fill<T>(mut src: T, buf: &mut Vec<u8>) -> io::Result<bool>
 where
     T: io::Read,
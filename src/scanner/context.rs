@@ -21,9 +21,12 @@ pub(in crate::scanner) const STARTING_INDENT: Indent = Indent(None);
 /// but you cannot have a Block context nested inside a Flow
 /// context, and this structure will ignore attempts to
 /// start a Block context while inside a Flow context.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub(in crate::scanner) struct Context
 {
+    // Maximum combined flow and block depth
+    max_depth: usize,
+
     // Flow context fields
     flow: usize,
 
@@ -39,10 +42,19 @@ impl Context
 
     /// Instantiate a new Context
     pub fn new() -> Self
+    {
+        Self::with_depth(usize::MAX)
+    }
+
+    /// Instantiate a new Context which errors if the
+    /// combined depth of flow and block collections would
+    /// exceed .max_depth
+    pub fn with_depth(max_depth: usize) -> Self
     {
         Self {
-            flow:    0,
-            indent:  0,
+            max_depth,
+            flow: 0,
+            indent: 0,
             indents: Vec::new(),
             started: false,
         }
@@ -72,6 +84,8 @@ impl Context
 
     pub fn flow_increment(&mut self) -> Result<usize>
     {
+        self.check_depth()?;
+
         let new = self.flow.checked_add(1).ok_or(ScanError::IntOverflow)?;
         self.flow = new;
 
@@ -118,6 +132,8 @@ impl Context
     /// .column > current_indent and .is_block returns true
     pub fn indent_increment(&mut self, column: usize, line: usize, map: bool) -> Result<Indent>
     {
+        self.check_depth()?;
+
         self.started = true;
         self.indents.push(IndentEntry::new(self.indent, line, map));
 
@@ -126,6 +142,12 @@ impl Context
         Ok(self.indent.into())
     }
 
+    /// Combined depth of flow and block collections
+    pub fn depth(&self) -> usize
+    {
+        self.flow + self.indents.len()
+    }
+
     /// Decrement the indent level calling .f for every
     /// level until .column > current_indent,
     /// returning the number of levels decremented
@@ -168,6 +190,17 @@ impl Context
             None => Ok(false),
         }
     }
+
+    /// Check that another level of nesting would not
+    /// exceed .max_depth
+    fn check_depth(&self) -> Result<()>
+    {
+        match self.depth() < self.max_depth
+        {
+            true => Ok(()),
+            false => Err(ScanError::DepthLimit),
+        }
+    }
 }
 
 /// Stack entry for tracking indentation levels, and
@@ -7,6 +7,7 @@
 use std::cmp::Ordering;
 
 use crate::{
+    event::types::Span,
     scanner::{
         error::ScanResult as Result,
         scalar::{block, flow, plain},
@@ -15,7 +16,8 @@ use crate::{
 };
 
 /// A wrapper around a token containing a custom Ord impl
-/// based on the token's position in the buffer.
+/// based on the token's position in the buffer, alongside
+/// the [`Span`] of the buffer the token was read from.
 ///
 /// Note that this wrapper *does not* compare tokens, so if
 /// you desire that ensure that you compare them directly
@@ -23,24 +25,31 @@ use crate::{
 pub(crate) struct TokenEntry<'de>
 {
     pub(crate) wrap: MaybeToken<'de>,
-    read_at:         usize,
+    span:            Span,
 }
 
 impl<'de> TokenEntry<'de>
 {
-    pub(crate) fn new<T>(token: T, read_at: usize) -> Self
+    pub(crate) fn new<T>(token: T, span: Span) -> Self
     where
         T: Into<MaybeToken<'de>>,
     {
         Self {
             wrap: token.into(),
-            read_at,
+            span,
         }
     }
 
+    /// The byte offset the token's read finished at, which
+    /// determines the token's position in the queue
     pub(crate) fn read_at(&self) -> usize
     {
-        self.read_at
+        self.span.end.offset
+    }
+
+    pub(crate) fn span(&self) -> Span
+    {
+        self.span
     }
 
     pub(crate) fn marker(&self) -> Marker
@@ -63,7 +72,7 @@ impl<'de> PartialEq for TokenEntry<'de>
 {
     fn eq(&self, other: &Self) -> bool
     {
-        self.read_at.eq(&other.read_at)
+        self.read_at().eq(&other.read_at())
     }
 }
 
@@ -81,7 +90,7 @@ impl<'de> Ord for TokenEntry<'de>
 {
     fn cmp(&self, other: &Self) -> Ordering
     {
-        self.read_at.cmp(&other.read_at)
+        self.read_at().cmp(&other.read_at())
     }
 }
 
@@ -148,6 +157,11 @@ impl<'de> Lazy<'de>
     {
         self.inner.into_token()
     }
+
+    pub fn is_plain(&self) -> bool
+    {
+        matches!(self.inner, LazyImpl::Plain(_))
+    }
 }
 impl<'de> From<flow::Deferred<'de>> for Lazy<'de>
 {
@@ -81,6 +81,18 @@ pub(crate) enum ScanError
     /// An integer overflowed
     IntOverflow,
 
+    /// Collections were nested more deeply than permitted
+    DepthLimit,
+
+    /// A scalar was longer than permitted
+    ScalarLimit,
+
+    /// An anchor or alias name was longer than permitted
+    AnchorLimit,
+
+    /// A tag or tag directive was longer than permitted
+    TagLimit,
+
     /// The underlying buffer should be extended before
     /// calling the Scanner again
     Extend,
@@ -125,6 +137,10 @@ impl From<ScanError> for ErrorCode
             ScanError::UnknownDelimiter => UnknownDelimiter,
             ScanError::UnexpectedEOF => UnexpectedEOF,
             ScanError::IntOverflow => IntOverflow,
+            ScanError::DepthLimit => DepthLimit,
+            ScanError::ScalarLimit => ScalarLimit,
+            ScanError::AnchorLimit => AnchorLimit,
+            ScanError::TagLimit => TagLimit,
             // If the extend is returned but isn't caught we assume that the calling code could
             // not extend the buffer after all, and hence was an EOF
             ScanError::Extend => UnexpectedEOF,
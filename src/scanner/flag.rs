@@ -29,6 +29,18 @@ pub const O_EXTENDABLE: Flags = Flags::EXTENDABLE;
 /// errors_ in the scalar itself, which will not be caught
 /// until the caller requests the token!
 pub const O_LAZY: Flags = Flags::LAZY;
+/// Sets the Scanner to use YAML 1.1 rules when scanning.
+///
+/// In particular, double quoted scalars may only contain
+/// the escapes defined by the YAML 1.1 spec, which lacks
+/// the '\/' escape added in 1.2.
+///
+/// Note that a document's `%YAML` directive always takes
+/// precedence over this flag.
+pub const O_YAML11: Flags = Flags::YAML11;
+/// Sets the Scanner to produce tokens for any comments it
+/// encounters, rather than discarding them.
+pub const O_COMMENTS: Flags = Flags::COMMENTS;
 
 bitflags! {
     /// Directives controlling various behaviors of the Scanner,
@@ -37,5 +49,7 @@ bitflags! {
     pub struct Flags: u32 {
         const EXTENDABLE    = 0b00000001;
         const LAZY          = 0b00000010;
+        const YAML11        = 0b00000100;
+        const COMMENTS      = 0b00001000;
     }
 }
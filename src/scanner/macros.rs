@@ -233,20 +233,29 @@ macro_rules! check {
     };
 }
 
-/// Pushes a token into the token queue, updating the tokens
-/// read
+/// Pushes a token into the token queue, spanning the
+/// buffer between the given .start and .end stats, or at
+/// the given .mark for tokens with no width
 ///
 /// Variants
-///     /1 .token, :.stats => .tokens
-///     /2 .token, .read => .tokens
+///     /1 .token, :.start, :.end => .tokens
+///     /2 .token, :.stats => .tokens
+///             := /3 .token, .stats.mark() => .tokens
+///     /3 .token, .mark => .tokens
 macro_rules! enqueue {
+    ($token:expr, :$start:expr, :$end:expr => $tokens:expr) => {
+        $tokens.push(crate::scanner::entry::TokenEntry::new(
+            $token,
+            crate::event::types::Span::new($start.mark(), $end.mark()),
+        ))
+    };
     ($token:expr, :$stats:expr => $tokens:expr) => {
-        enqueue!($token, $stats.read => $tokens)
+        enqueue!($token, $stats.mark() => $tokens)
     };
-    ($token:expr, $read:expr => $tokens:expr) => {
+    ($token:expr, $mark:expr => $tokens:expr) => {
         $tokens.push(crate::scanner::entry::TokenEntry::new(
             $token,
-            $read,
+            crate::event::types::Span::at($mark),
         ))
     };
 }
@@ -22,6 +22,7 @@ mod stats;
 mod tag;
 
 use crate::{
+    event::{limit::Limits, types::Mark},
     queue::Queue,
     scanner::{
         anchor::{scan_anchor, AnchorKind},
@@ -35,7 +36,7 @@ use crate::{
         stats::MStats,
         tag::scan_node_tag,
     },
-    token::{Marker, StreamEncoding, Token},
+    token::{CommentPlacement, Marker, StreamEncoding, Token},
 };
 
 type Tokens<'de> = Queue<TokenEntry<'de>>;
@@ -53,6 +54,23 @@ pub(crate) struct Scanner
     /// at the current position?
     simple_key_allowed: bool,
 
+    /// Does the current document's `%YAML` directive (if
+    /// any) require YAML 1.1 rules?
+    yaml11: Option<bool>,
+
+    /// Have we seen any directives since the last document
+    /// marker?
+    directives: bool,
+
+    /// Resource limits applied to scanned tokens
+    limits: Limits,
+
+    /// Encoding of the underlying byte stream
+    encoding: StreamEncoding,
+
+    /// Has a token been scanned on the current line?
+    inline: bool,
+
     // Subsystems
     stats:   MStats,
     key:     Key,
@@ -61,16 +79,30 @@ pub(crate) struct Scanner
 
 impl Scanner
 {
+    /// Instantiate a new Scanner, without any resource
+    /// limits
     #[allow(clippy::new_without_default)]
     pub fn new() -> Self
+    {
+        Self::with_limits(Limits::unlimited())
+    }
+
+    /// Instantiate a new Scanner which errors if the given
+    /// .limits are exceeded
+    pub fn with_limits(limits: Limits) -> Self
     {
         Self {
-            offset:             0,
+            offset: 0,
             simple_key_allowed: false,
-            stats:              MStats::new(),
-            state:              StreamState::Start,
-            key:                Key::default(),
-            context:            Context::new(),
+            yaml11: None,
+            directives: false,
+            limits,
+            encoding: StreamEncoding::UTF8,
+            inline: false,
+            stats: MStats::new(),
+            state: StreamState::Start,
+            key: Key::default(),
+            context: Context::with_depth(limits.max_depth),
         }
     }
 
@@ -98,6 +130,13 @@ impl Scanner
                     self.offset = base.len() - buffer.len();
                 }
 
+                // Refuse to extend the buffer for a token which
+                // could never be within our limits
+                if run == Err(ScanError::Extend)
+                {
+                    self.check_pending(&base[self.offset..])?;
+                }
+
                 run?;
 
                 num_tokens = tokens.len() - starting_tokens;
@@ -107,11 +146,26 @@ impl Scanner
         Ok(num_tokens)
     }
 
+    /// Set the encoding reported in the stream's start
+    /// token. This has no effect once the stream has
+    /// started.
+    pub fn set_encoding(&mut self, encoding: StreamEncoding)
+    {
+        self.encoding = encoding;
+    }
+
     pub fn offset(&self) -> usize
     {
         self.offset
     }
 
+    /// The current position of the Scanner in the byte
+    /// stream
+    pub fn mark(&self) -> Mark
+    {
+        self.stats.mark()
+    }
+
     pub fn reset_offset(&mut self)
     {
         self.offset = 0;
@@ -124,6 +178,14 @@ impl Scanner
         tokens: &mut Tokens<'de>,
     ) -> Result<()>
     {
+        // Apply any document specific rules
+        let opts = match self.yaml11
+        {
+            Some(true) => opts | O_YAML11,
+            Some(false) => opts - O_YAML11,
+            None => opts,
+        };
+
         // Is it the beginning of the stream?
         if self.state == StreamState::Start
         {
@@ -131,8 +193,16 @@ impl Scanner
             return Ok(());
         }
 
-        // Eat whitespace to the next delimiter
-        self.eat_whitespace(opts, base, COMMENTS)?;
+        // Eat whitespace to the next delimiter, producing tokens
+        // for any comments if requested
+        if opts.contains(O_COMMENTS)
+        {
+            self.fetch_comments(opts, base, tokens)?;
+        }
+        else
+        {
+            self.eat_whitespace(opts, base, COMMENTS)?;
+        }
 
         // Remove any saved key positions that cannot contain keys
         // anymore
@@ -140,12 +210,12 @@ impl Scanner
 
         // Handle indentation unrolling
         self.unroll_indent(tokens, self.stats.column)?;
-        self.pop_zero_indent_sequence(*base, tokens)?;
+        self.pop_zero_indent_sequence(base, tokens)?;
 
         // Is it the end of a stream?
         if base.is_empty() || self.state == StreamState::Done
         {
-            return self.fetch_stream_end(*base, tokens);
+            return self.fetch_stream_end(base, tokens);
         }
 
         // 4 characters is the longest token we can encounter, one
@@ -154,6 +224,9 @@ impl Scanner
         //  - '... '
         cache!(~base, 4, opts)?;
 
+        // Any comment after this token will trail it
+        self.inline = true;
+
         // Fetch the next token(s)
         match base.as_bytes()
         {
@@ -216,13 +289,31 @@ impl Scanner
             [SINGLE, ..] | [DOUBLE, ..] => self.fetch_flow_scalar(opts, base, tokens),
 
             // Is it a plain scalar?
-            _ if self.is_plain_scalar(*base) => self.fetch_plain_scalar(opts, base, tokens),
+            _ if self.is_plain_scalar(base) => self.fetch_plain_scalar(opts, base, tokens),
 
             // Otherwise its an error
             _ => Err(ScanError::UnknownDelimiter),
         }
     }
 
+    /// Check that the .pending bytes of a token the Scanner
+    /// is waiting to complete remain within our limits.
+    ///
+    /// Some lookahead past the end of the token is
+    /// tolerated, as it may be needed to complete the
+    /// token.
+    fn check_pending(&self, pending: &str) -> Result<()>
+    {
+        let (max, err) = match pending.as_bytes().first()
+        {
+            Some(&ALIAS) | Some(&ANCHOR) => (self.limits.max_anchor_len, ScanError::AnchorLimit),
+            Some(&TAG) | Some(&DIRECTIVE) => (self.limits.max_tag_len, ScanError::TagLimit),
+            _ => (self.limits.max_scalar_len, ScanError::ScalarLimit),
+        };
+
+        check_limit(pending.len(), max.saturating_add(PENDING_LOOKAHEAD), err)
+    }
+
     fn fetch_stream_start(&mut self, tokens: &mut Tokens)
     {
         if self.state == StreamState::Start
@@ -232,7 +323,7 @@ impl Scanner
 
             self.state = StreamState::Stream;
 
-            let token = Token::StreamStart(StreamEncoding::UTF8);
+            let token = Token::StreamStart(self.encoding);
 
             enqueue!(token, :self.stats => tokens);
         }
@@ -286,9 +377,19 @@ impl Scanner
         // A key cannot follow a document marker
         self.simple_key_allowed = false;
 
+        // Any directives only apply to the document they
+        // precede, so reset the document rules unless this is
+        // the start of that document
+        if !(start && self.directives)
+        {
+            self.yaml11 = None;
+        }
+        self.directives = false;
+
+        let start = self.stats.clone();
         advance!(*buffer, :self.stats, 3);
 
-        enqueue!(token, :self.stats => tokens);
+        enqueue!(token, :start, :self.stats => tokens);
 
         Ok(())
     }
@@ -325,6 +426,15 @@ impl Scanner
         // Scan the directive token from the .buffer
         let token = scan_directive(opts, &mut buffer, &mut stats, &kind)?;
 
+        if let Token::TagDirective(ref handle, ref prefix) = token
+        {
+            check_limit(
+                handle.len() + prefix.len(),
+                self.limits.max_tag_len,
+                ScanError::TagLimit,
+            )?;
+        }
+
         // Reset indent to starting level
         self.unroll_indent(tokens, STARTING_INDENT)?;
 
@@ -334,13 +444,22 @@ impl Scanner
         // A key cannot follow a directive (a newline is required)
         self.simple_key_allowed = false;
 
+        // Apply the version's rules to the document this
+        // directive precedes
+        self.directives = true;
+        if let Token::VersionDirective(major, minor) = token
+        {
+            self.yaml11 = Some(major < 1 || (major == 1 && minor <= 1));
+        }
+
         // %YAML 1.1 # some comment\n
         //          ^^^^^^^^^^^^^^^^^ buffer
         // ^^^^^^^^^ base.len - buffer.len
         advance!(*base, base.len() - buffer.len());
+        let start = self.stats.clone();
         self.stats += stats;
 
-        enqueue!(token, :self.stats => tokens);
+        enqueue!(token, :start, :self.stats => tokens);
 
         Ok(())
     }
@@ -363,6 +482,15 @@ impl Scanner
         let (token, amt) = scan_node_tag(opts, buffer, &mut stats)?;
         advance!(buffer, amt);
 
+        if let Token::Tag(ref handle, ref suffix) = token
+        {
+            check_limit(
+                handle.len() + suffix.len(),
+                self.limits.max_tag_len,
+                ScanError::TagLimit,
+            )?;
+        }
+
         self.save_key(!REQUIRED)?;
 
         // A key may not start after a tag (only before)
@@ -372,9 +500,10 @@ impl Scanner
         //                       ^^^^^^^^^^^^^^^^^^ buffer
         // ^^^^^^^^^^^^^^^^^^^^^^ self.buffer.len - buffer.len
         advance!(*base, base.len() - buffer.len());
+        let start = self.stats.clone();
         self.stats += stats;
 
-        enqueue!(token, :self.stats => tokens);
+        enqueue!(token, :start, :self.stats => tokens);
 
         Ok(())
     }
@@ -403,6 +532,15 @@ impl Scanner
         // Scan the token from the .buffer
         let token = scan_anchor(opts, &mut buffer, &mut stats, &kind)?;
 
+        if let Token::Alias(ref name) | Token::Anchor(ref name) = token
+        {
+            check_limit(
+                name.len(),
+                self.limits.max_anchor_len,
+                ScanError::AnchorLimit,
+            )?;
+        }
+
         // An anchor / alias may start a simple key
         self.save_key(!REQUIRED)?;
 
@@ -413,9 +551,10 @@ impl Scanner
         //        ^^^^^^^^^^^^^^^^^^^ buffer.len
         // ^^^^^^^ base.len - buffer.len
         advance!(*base, base.len() - buffer.len());
+        let start = self.stats.clone();
         self.stats += stats;
 
-        enqueue!(token, :self.stats => tokens);
+        enqueue!(token, :start, :self.stats => tokens);
 
         Ok(())
     }
@@ -438,6 +577,8 @@ impl Scanner
 
         let (token, amt) = scan_flow_scalar(opts, buffer, &mut stats, single)?;
 
+        check_limit(amt, self.limits.max_scalar_len, ScanError::ScalarLimit)?;
+
         self.save_key(!REQUIRED)?;
 
         // A key cannot follow a flow scalar, as we're either
@@ -447,9 +588,10 @@ impl Scanner
         self.simple_key_allowed = false;
 
         advance!(*base, amt);
+        let start = self.stats.clone();
         self.stats += stats;
 
-        enqueue!(token, :self.stats => tokens);
+        enqueue!(token, :start, :self.stats => tokens);
 
         Ok(())
     }
@@ -466,6 +608,8 @@ impl Scanner
 
         let (token, amt) = scan_plain_scalar(opts, buffer, &mut stats, &self.context)?;
 
+        check_limit(amt, self.limits.max_scalar_len, ScanError::ScalarLimit)?;
+
         self.save_key(!REQUIRED)?;
 
         // A simple key cannot follow a plain scalar, there must be
@@ -474,9 +618,10 @@ impl Scanner
         self.simple_key_allowed = false;
 
         advance!(*base, amt);
+        let start = self.stats.clone();
         self.stats = stats;
 
-        enqueue!(token, :self.stats => tokens);
+        enqueue!(token, :start, :self.stats => tokens);
 
         Ok(())
     }
@@ -501,10 +646,82 @@ impl Scanner
 
         let (token, amt) = scan_block_scalar(opts, buffer, &mut stats, &self.context, fold)?;
 
+        check_limit(amt, self.limits.max_scalar_len, ScanError::ScalarLimit)?;
+
+        // A block scalar always finishes on a new line
+        self.inline = false;
+
         advance!(*base, amt);
+        let start = self.stats.clone();
         self.stats = stats;
 
-        enqueue!(token, :self.stats => tokens);
+        enqueue!(token, :start, :self.stats => tokens);
+
+        Ok(())
+    }
+
+    /// Eat whitespace to the next delimiter, producing a
+    /// token for each comment found along the way
+    fn fetch_comments<'de>(
+        &mut self,
+        opts: Flags,
+        base: &mut &'de str,
+        tokens: &mut Tokens<'de>,
+    ) -> Result<()>
+    {
+        loop
+        {
+            self.eat_whitespace(opts, base, !COMMENTS)?;
+
+            if !check!(~base => [COMMENT, ..])
+            {
+                return Ok(());
+            }
+
+            self.fetch_comment(opts, base, tokens)?;
+        }
+    }
+
+    fn fetch_comment<'de>(
+        &mut self,
+        opts: Flags,
+        base: &mut &'de str,
+        tokens: &mut Tokens<'de>,
+    ) -> Result<()>
+    {
+        let origin: &'de str = base;
+        let mut buffer = origin;
+        let mut stats = MStats::new();
+
+        // Eat the '#'
+        advance!(buffer, :stats, 1);
+
+        // Eat everything until EOL or EOF
+        loop
+        {
+            cache!(~buffer, 1, opts)?;
+
+            if isBreakZ!(~buffer)
+            {
+                break;
+            }
+
+            advance!(buffer, :stats, widthOf!(~buffer));
+        }
+
+        let amt = origin.len() - buffer.len();
+        let placement = match self.inline
+        {
+            true => CommentPlacement::Trailing,
+            false => CommentPlacement::OwnLine,
+        };
+        let token = Token::Comment(cow!(&origin[1..amt]), placement);
+
+        advance!(*base, amt);
+        let start = self.stats.clone();
+        self.stats += stats;
+
+        enqueue!(token, :start, :self.stats => tokens);
 
         Ok(())
     }
@@ -569,9 +786,10 @@ impl Scanner
          */
         self.simple_key_allowed = block_context;
 
+        let start = self.stats.clone();
         advance!(*base, :self.stats, 1);
 
-        enqueue!(Token::Key, :self.stats => tokens);
+        enqueue!(Token::Key, :start, :self.stats => tokens);
 
         Ok(())
     }
@@ -637,9 +855,10 @@ impl Scanner
             },
         }
 
+        let start = self.stats.clone();
         advance!(*base, :self.stats, 1);
 
-        enqueue!(Token::Value, :self.stats => tokens);
+        enqueue!(Token::Value, :start, :self.stats => tokens);
 
         Ok(())
     }
@@ -659,9 +878,10 @@ impl Scanner
 
         self.context.flow_increment()?;
 
+        let start = self.stats.clone();
         advance!(*base, :self.stats, 1);
 
-        enqueue!(token, :self.stats => tokens);
+        enqueue!(token, :start, :self.stats => tokens);
 
         self.save_key(!REQUIRED)?;
 
@@ -693,9 +913,10 @@ impl Scanner
         // A simple key is not allowed after a ']' or '}'
         self.simple_key_allowed = false;
 
+        let start = self.stats.clone();
         advance!(*base, :self.stats, 1);
 
-        enqueue!(token, :self.stats => tokens);
+        enqueue!(token, :start, :self.stats => tokens);
 
         Ok(())
     }
@@ -712,11 +933,12 @@ impl Scanner
         // A simple key can start after a ','
         self.simple_key_allowed = true;
 
+        let start = self.stats.clone();
         advance!(*base, :self.stats, 1);
 
         let token = Token::FlowEntry;
 
-        enqueue!(token, :self.stats => tokens);
+        enqueue!(token, :start, :self.stats => tokens);
 
         Ok(())
     }
@@ -764,10 +986,11 @@ impl Scanner
         // A key is possible after a '-'
         self.simple_key_allowed = true;
 
+        let start = self.stats.clone();
         advance!(*base, :self.stats, 1);
 
         let token = Token::BlockEntry;
-        enqueue!(token, :self.stats => tokens);
+        enqueue!(token, :start, :self.stats => tokens);
 
         Ok(())
     }
@@ -776,7 +999,7 @@ impl Scanner
     where
         T: Into<Indent>,
     {
-        unroll_indent(&mut self.context, self.stats.read, tokens, column)
+        unroll_indent(&mut self.context, self.stats.mark(), tokens, column)
     }
 
     /// Check if the current saved key (if it exists) has
@@ -836,10 +1059,10 @@ impl Scanner
                 && entry.kind == Marker::BlockSequenceStart
                 && (!check!(~base => b'-'))
             {
-                let read = self.stats.read;
+                let mark = self.stats.mark();
 
                 self.context.pop_indent(|_| {
-                    enqueue!(Token::BlockEnd, read => tokens);
+                    enqueue!(Token::BlockEnd, mark => tokens);
                     Ok(())
                 })?;
             }
@@ -932,7 +1155,7 @@ impl Scanner
     {
         let mut stats = MStats::new();
 
-        let amt = eat_whitespace(opts, *buffer, &mut stats, comments)?;
+        let amt = eat_whitespace(opts, buffer, &mut stats, comments)?;
 
         // A new line may start a key in the block context
         //
@@ -941,6 +1164,7 @@ impl Scanner
         if stats.lines != 0
         {
             self.simple_key_allowed = true;
+            self.inline = false;
         }
 
         advance!(*buffer, amt);
@@ -1007,17 +1231,28 @@ fn eat_whitespace(opts: Flags, base: &str, stats: &mut MStats, comments: bool) -
     Ok(base.len() - buffer.len())
 }
 
+/// Error with .err if .len exceeds the given .max
+fn check_limit(len: usize, max: usize, err: ScanError) -> Result<()>
+{
+    match len > max
+    {
+        true => Err(err),
+        false => Ok(()),
+    }
+}
+
 /// Roll the indentation level and push a block collection
 /// indent token to the indent stack if required
 fn roll_indent<'de>(
     context: &mut Context,
     tokens: &mut Tokens<'de>,
-    mark: usize,
+    read: usize,
     line: usize,
     column: usize,
     map: bool,
 ) -> Result<()>
 {
+    let mark = Mark::new(read, line, column);
     let token = match map
     {
         true => Token::BlockMappingStart,
@@ -1064,7 +1299,7 @@ fn roll_indent<'de>(
 /// indent level
 fn unroll_indent<'de, T>(
     context: &mut Context,
-    mark: usize,
+    mark: Mark,
     tokens: &mut Tokens<'de>,
     column: T,
 ) -> Result<()>
@@ -1122,6 +1357,10 @@ const COMMENTS: bool = true;
 const REQUIRED: bool = true;
 const BLOCK_MAP: bool = true;
 
+/// Bytes of lookahead tolerated past a token's length limit
+/// while waiting for the token to complete
+const PENDING_LOOKAHEAD: usize = 4 * 1024;
+
 #[cfg(test)]
 mod tests
 {
@@ -1206,7 +1445,7 @@ mod tests
                     .scan_tokens(self.opts, self.data.read(), &mut self.tokens)
                 {
                     Ok(count) => break count,
-                    Err(e) if e == ScanError::Extend =>
+                    Err(ScanError::Extend) =>
                     {
                         self.data.expand(str_reader::StrReader::BUF_EXTEND);
 
@@ -114,9 +114,6 @@ pub(in crate::scanner) fn scan_block_scalar_eager<'de>(
     // reconciled
     let mut lines: usize = 0;
 
-    // The indentation level of this scalar
-    let indent: usize;
-
     // Scalar style mapping
     let style = match fold
     {
@@ -145,20 +142,55 @@ pub(in crate::scanner) fn scan_block_scalar_eager<'de>(
 
     // Set the indent explicitly if defined, otherwise detect
     // from the indentation level
-    match explicit.map(NonZeroU8::get)
+    let indent = match explicit.map(NonZeroU8::get)
     {
-        Some(explicit) => indent = base_indent + explicit as usize,
-        None =>
+        Some(explicit) =>
         {
-            indent = detect_indent_level(
+            let indent = base_indent + explicit as usize;
+
+            // Chomp any preceding lines and the first line's
+            // indentation
+            scan_indent(
                 opts,
                 &mut buffer,
                 &mut local_stats,
-                base_indent,
                 &mut lines,
                 &mut can_borrow,
-            )?
+                indent,
+            )?;
+
+            if lines > 0
+            {
+                can_borrow = false
+            }
+
+            indent
         },
+        None => detect_indent_level(
+            opts,
+            &mut buffer,
+            &mut local_stats,
+            base_indent,
+            &mut lines,
+            &mut can_borrow,
+        )?,
+    };
+
+    // A scalar without any content lines consists solely of
+    // trailing line breaks, which are only kept when chomping
+    // is set to Keep
+    if local_stats.column != indent || buffer.is_empty()
+    {
+        let scalar = match chomp
+        {
+            ChompStyle::Keep => cow!("\n".repeat(lines)),
+            _ => cow!(""),
+        };
+
+        *stats = local_stats;
+        let advance = base.len() - buffer.len();
+
+        return Ok((Token::Scalar(scalar, style), advance));
     }
 
     // Add any preceding lines to the tracked borrow or scratch
@@ -177,6 +209,10 @@ pub(in crate::scanner) fn scan_block_scalar_eager<'de>(
 
     lines = 0;
 
+    // Tracks if the previous line was more indented than the
+    // scalar, when folding
+    let mut previous_indented = false;
+
     // Loop over scalar line by line until we reach a less
     // indented line or EOF
     while local_stats.column == indent && (!buffer.is_empty())
@@ -194,7 +230,7 @@ pub(in crate::scanner) fn scan_block_scalar_eager<'de>(
         //    I can be borrowed
         if can_borrow && lines > 0
         {
-            scratch.extend_from_slice(base[content_start..content_end].as_bytes());
+            scratch.extend_from_slice(&base.as_bytes()[content_start..content_end]);
 
             can_borrow = false
         }
@@ -202,12 +238,26 @@ pub(in crate::scanner) fn scan_block_scalar_eager<'de>(
         // If its a folding ('>') block scalar
         if fold
         {
+            // Lines starting with whitespace are more indented than
+            // the scalar, and are never folded
+            cache!(~buffer, 1, opts)?;
+            let more_indented = isBlank!(~buffer);
+
             // Handle line joins as needed
             match lines
             {
                 // No join needed, we're done
                 0 =>
                 {},
+                // Either this line or the previous was more indented, append the collected
+                // newlines literally
+                n if more_indented || previous_indented =>
+                {
+                    for _ in 0..n
+                    {
+                        scratch.push(NEWLINE)
+                    }
+                },
                 // If a single line was recorded, we _cannot_ have seen a line wholly made of
                 // whitespace, therefore join via a space
                 1 =>
@@ -224,6 +274,8 @@ pub(in crate::scanner) fn scan_block_scalar_eager<'de>(
                     }
                 },
             }
+
+            previous_indented = more_indented;
         }
         // Otherwise simply append the collected newlines literally ('|')
         else
@@ -312,9 +364,6 @@ pub(in crate::scanner) fn scan_block_scalar_lazy<'de>(
     let mut buffer = base;
     let mut local_stats = stats.clone();
 
-    // The indentation level of this scalar
-    let indent: usize;
-
     // Eat the '|' or '>'
     cache!(~buffer, 1, opts)?;
     advance!(buffer, :local_stats, 1);
@@ -336,21 +385,34 @@ pub(in crate::scanner) fn scan_block_scalar_lazy<'de>(
 
     // Set the indent explicitly if defined, otherwise detect
     // from the indentation level
-    match explicit.map(NonZeroU8::get)
+    let indent = match explicit.map(NonZeroU8::get)
     {
-        Some(explicit) => indent = base_indent + explicit as usize,
-        None =>
+        Some(explicit) =>
         {
-            indent = detect_indent_level(
+            let indent = base_indent + explicit as usize;
+
+            // Chomp any preceding lines and the first line's
+            // indentation
+            scan_indent(
                 opts,
                 &mut buffer,
                 &mut local_stats,
-                base_indent,
                 &mut 0,
                 &mut false,
-            )?
+                indent,
+            )?;
+
+            indent
         },
-    }
+        None => detect_indent_level(
+            opts,
+            &mut buffer,
+            &mut local_stats,
+            base_indent,
+            &mut 0,
+            &mut false,
+        )?,
+    };
 
     while local_stats.column == indent && (!buffer.is_empty())
     {
@@ -517,7 +579,7 @@ fn scan_chomp<'de>(
                 // include the first
                 if lines > 0
                 {
-                    end += widthOf!(~base[end..], 1);
+                    end += widthOf!(~base[end..]);
                 }
 
                 scalar = cow!(&base[start..end])
@@ -540,7 +602,7 @@ fn scan_chomp<'de>(
                 // when .style == Keep
                 _ =>
                 {
-                    scratch.extend_from_slice(base[start..end].as_bytes());
+                    scratch.extend_from_slice(&base.as_bytes()[start..end]);
 
                     while lines > 0
                     {
@@ -845,6 +907,22 @@ mod tests
         Ok(())
     }
 
+    #[test]
+    fn literal_clip_eof() -> TestResult
+    {
+        let data = "|\n  a single line\n";
+        let mut stats = MStats::new();
+        let cxt = cxt!(block -> [0]);
+        let expected = Token::Scalar(cow!("a single line\n"), Literal);
+
+        let (token, _amt) =
+            scan_block_scalar(TEST_FLAGS, data, &mut stats, &cxt, LITERAL).and_then(normalize)?;
+
+        assert_eq!(token, expected);
+
+        Ok(())
+    }
+
     #[test]
     fn literal_strip() -> TestResult
     {
@@ -1001,6 +1079,54 @@ some.other.key: value";
         Ok(())
     }
 
+    #[test]
+    fn literal_explicit_indent() -> TestResult
+    {
+        let data = "|2-\n\n    leading spaces\n  text\n";
+        let mut stats = MStats::new();
+        let cxt = cxt!(block -> [0]);
+        let expected = Token::Scalar(cow!("\n  leading spaces\ntext"), Literal);
+
+        let (token, _amt) =
+            scan_block_scalar(TEST_FLAGS, data, &mut stats, &cxt, LITERAL).and_then(normalize)?;
+
+        assert_eq!(token, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn literal_empty_keep() -> TestResult
+    {
+        let data = "|+\n\n\nkey: value";
+        let mut stats = MStats::new();
+        let cxt = cxt!(block -> [0]);
+        let expected = Token::Scalar(cow!("\n\n"), Literal);
+
+        let (token, _amt) =
+            scan_block_scalar(TEST_FLAGS, data, &mut stats, &cxt, LITERAL).and_then(normalize)?;
+
+        assert_eq!(token, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn literal_empty_clip() -> TestResult
+    {
+        let data = "|\n\n\nkey: value";
+        let mut stats = MStats::new();
+        let cxt = cxt!(block -> [0]);
+        let expected = Token::Scalar(cow!(""), Literal);
+
+        let (token, _amt) =
+            scan_block_scalar(TEST_FLAGS, data, &mut stats, &cxt, LITERAL).and_then(normalize)?;
+
+        assert_eq!(token, expected);
+
+        Ok(())
+    }
+
     /* === FOLDED STYLE === */
 
     #[test]
@@ -1175,6 +1301,28 @@ some.other.key: value";
         Ok(())
     }
 
+    #[test]
+    fn folded_more_indented() -> TestResult
+    {
+        let data = ">-
+  folded
+  text
+
+    more indented
+  back
+";
+        let mut stats = MStats::new();
+        let cxt = cxt!(block -> [0]);
+        let expected = Token::Scalar(cow!("folded text\n\n  more indented\nback"), Folded);
+
+        let (token, _amt) =
+            scan_block_scalar(TEST_FLAGS, data, &mut stats, &cxt, !LITERAL).and_then(normalize)?;
+
+        assert_eq!(token, expected);
+
+        Ok(())
+    }
+
     #[test]
     fn folded_comment() -> TestResult
     {
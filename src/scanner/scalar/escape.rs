@@ -9,7 +9,7 @@
 
 use crate::scanner::{
     error::{ScanError, ScanResult as Result},
-    flag::Flags,
+    flag::{Flags, O_YAML11},
 };
 
 /// Unescape a given YAML escape sequence as defined in
@@ -59,7 +59,7 @@ pub(in crate::scanner) fn flow_unescape(
         [b' ', ..] => scratch.push(b'\x20'),
         [b'"', ..] => scratch.push(b'"'),
         // Forward slashes are not supported in the 1.1 spec
-        [b'/', ..] => scratch.push(b'/'),
+        [b'/', ..] if !opts.contains(O_YAML11) => scratch.push(b'/'),
         [b'\\', ..] => scratch.push(b'\\'),
         [b'N', ..] => scratch.extend_from_slice(&NEL),
         [b'_', ..] => scratch.extend_from_slice(&NBS),
@@ -261,24 +261,24 @@ mod tests
             r#"\e"#, r#"\ "#, r#"\""#, r#"\/"#, r#"\\"#, r#"\N"#, r#"\_"#, r#"\L"#, r#"\P"#,
         ];
         let expected: &[&[u8]] = &[
-            &[b'\0'],   // 0
-            &[b'\x07'], // 1
-            &[b'\x08'], // 2
-            &[b'\x09'], // 3
-            &[b'\x09'], // 4
-            &[b'\x0A'], // 5
-            &[b'\x0B'], // 6
-            &[b'\x0C'], // 7
-            &[b'\x0D'], // 8
-            &[b'\x1B'], // 9
-            &[b'\x20'], // 10
-            &[b'"'],    // 11
-            &[b'/'],    // 12
-            &[b'\\'],   // 13
-            &NEL,       // 14
-            &NBS,       // 15
-            &LS,        // 16
-            &PS,        // 17
+            b"\0",   // 0
+            b"\x07", // 1
+            b"\x08", // 2
+            b"\x09", // 3
+            b"\x09", // 4
+            b"\x0A", // 5
+            b"\x0B", // 6
+            b"\x0C", // 7
+            b"\x0D", // 8
+            b"\x1B", // 9
+            b"\x20", // 10
+            b"\"",   // 11
+            b"/",    // 12
+            b"\\",   // 13
+            &NEL,    // 14
+            &NBS,    // 15
+            &LS,     // 16
+            &PS,     // 17
         ];
 
         assert_eq!(
@@ -287,7 +287,7 @@ mod tests
             "test data length != expected length"
         );
 
-        for (i, (&t, &ex)) in data.into_iter().zip(expected).enumerate()
+        for (i, (&t, &ex)) in data.iter().zip(expected).enumerate()
         {
             scratch.clear();
             flow_unescape(O_ZEROED, t, scratch)
@@ -299,6 +299,21 @@ mod tests
         Ok(())
     }
 
+    #[test]
+    fn flow_escape_yaml11() -> TestResult
+    {
+        let mut scratch = Vec::new();
+
+        flow_unescape(O_YAML11, r#"\N"#, &mut scratch)?;
+        assert_eq!(scratch, NEL);
+
+        match flow_unescape(O_YAML11, r#"\/"#, &mut scratch)
+        {
+            Err(ScanError::UnknownEscape) => Ok(()),
+            res => bail!("expected an unknown escape error, got: {:?}", res),
+        }
+    }
+
     #[test]
     fn flow_escape_hex() -> TestResult
     {
@@ -346,7 +361,7 @@ mod tests
             "test data length != expected length"
         );
 
-        for (i, (&t, &ex)) in data.into_iter().zip(expected).enumerate()
+        for (i, (&t, &ex)) in data.iter().zip(expected).enumerate()
         {
             let mut c: [u8; 4] = [0; 4];
             scratch.clear();
@@ -388,7 +403,7 @@ mod tests
             r#"\U000111E1"#, // 10
         ];
 
-        for (i, &t) in data.into_iter().enumerate()
+        for (i, &t) in data.iter().enumerate()
         {
             scratch.clear();
 
@@ -435,7 +450,7 @@ mod tests
             "test data and expected data are not the same length"
         );
 
-        for (i, (&t, &e)) in data.into_iter().zip(expected).enumerate()
+        for (i, (&t, &e)) in data.iter().zip(expected).enumerate()
         {
             scratch.clear();
 
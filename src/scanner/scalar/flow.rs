@@ -129,12 +129,14 @@ pub(in crate::scanner) fn scan_flow_scalar_eager<'de>(
             // Its a non blank character, add it
             else
             {
+                // Safety: !isBlankZ guarantees the slice is not empty
+                let width = widthOf!(~buffer);
+
                 if !can_borrow
                 {
-                    // Safety: !isBlankZ guarantees the slice is not empty
-                    scratch.push(buffer.as_bytes()[0])
+                    scratch.extend_from_slice(&buffer.as_bytes()[..width])
                 }
-                advance!(buffer, :stats, 1);
+                advance!(buffer, :stats, width);
             }
         }
 
@@ -329,7 +331,7 @@ fn set_no_borrow(can_borrow: &mut bool, base: &str, buffer: &str, scratch: &mut
     {
         // Note we start from 1 here to account for the quote
         // character
-        scratch.extend_from_slice(base[1..base.len() - buffer.len()].as_bytes());
+        scratch.extend_from_slice(&base.as_bytes()[1..base.len() - buffer.len()]);
     }
 
     *can_borrow = false
@@ -503,7 +505,7 @@ fourth'"#;
         let expected = ScanError::InvalidFlowScalar;
         let mut stats;
 
-        for (i, &t) in (&data).into_iter().enumerate()
+        for (i, &t) in data.iter().enumerate()
         {
             stats = MStats::new();
 
@@ -529,7 +531,7 @@ fourth'"#;
         let expected = ScanError::UnexpectedEOF;
         let mut stats;
 
-        for (i, &t) in (&data).into_iter().enumerate()
+        for (i, &t) in data.iter().enumerate()
         {
             stats = MStats::new();
 
@@ -128,6 +128,40 @@ pub(in crate::scanner) fn scan_plain_scalar_eager<'de>(
             break 'scalar;
         }
 
+        // Handle line joins as needed. Note that we only do this
+        // once we know the scalar continues, so that trailing line
+        // breaks do not force an allocation
+        if lines > 0
+        {
+            match can_borrow
+            {
+                // Copy everything up to the last word read
+                true =>
+                {
+                    let advance = scalar_stats.read - stats.read;
+
+                    scratch.extend_from_slice(&base.as_bytes()[..advance]);
+                    can_borrow = false;
+                },
+                false => scratch.truncate(scratch.len() - whitespace),
+            }
+
+            match lines
+            {
+                // If a single line was recorded, we _cannot_ have seen a line wholly made of
+                // whitespace, therefore join via a space
+                1 => scratch.push(SPACE),
+                // Else we need to append (n - 1) newlines, as we skip the origin line's break
+                _ =>
+                {
+                    for _ in 1..lines
+                    {
+                        scratch.push(NEWLINE)
+                    }
+                },
+            }
+        }
+
         // Reset whitespace counters for next char / whitespace
         // sequence. We do this here after all possible terminations
         // that could leave trailing whitespace, so we can
@@ -147,11 +181,13 @@ pub(in crate::scanner) fn scan_plain_scalar_eager<'de>(
                 break;
             }
 
+            let width = widthOf!(~buffer);
+
             if !can_borrow
             {
-                scratch.push(buffer.as_bytes()[0])
+                scratch.extend_from_slice(&buffer.as_bytes()[..width])
             }
-            advance!(buffer, :local_stats, 1);
+            advance!(buffer, :local_stats, width);
         }
         // Save last non whitespace character position
         scalar_stats = local_stats.clone();
@@ -178,8 +214,6 @@ pub(in crate::scanner) fn scan_plain_scalar_eager<'de>(
                 // Handle line breaks
                 (false, _) =>
                 {
-                    set_no_borrow(&mut can_borrow, base, buffer, &mut scratch);
-
                     lines += 1;
                     advance!(buffer, :local_stats, @line);
                 },
@@ -189,49 +223,13 @@ pub(in crate::scanner) fn scan_plain_scalar_eager<'de>(
         // If the whitespace ended at a lower indent, then we're
         // done, and should exit on the next loop
         outdent = block_context && local_stats.column < indent;
-
-        // Handle line joins as needed
-        match lines
-        {
-            // No join needed, we're done
-            0 =>
-            {},
-            // If a single line was recorded, we _cannot_ have seen a line wholly made of
-            // whitespace, therefore join via a space
-            1 =>
-            {
-                // Note that we reset whitespace to zero here, so that the
-                // post loop truncate doesn't remove characters we've
-                // already removed here
-                scratch.truncate(scratch.len() - whitespace);
-                whitespace = 0;
-
-                scratch.push(SPACE);
-            },
-            // Else we need to append (n - 1) newlines, as we skip the origin line's break
-            _ =>
-            {
-                // Similarly, we reset whitespace here, but we _also_ set
-                // lines to the amount of lines we actually add to the
-                // scratch space.
-                scratch.truncate(scratch.len() - whitespace);
-                whitespace = 0;
-                lines -= 1;
-
-                // Safety: we can only reach this branch if lines > 1
-                for _ in 0..lines
-                {
-                    scratch.push(NEWLINE)
-                }
-            },
-        }
     }
 
     // Trim any trailing whitespace that might be left after
     // exiting the loop
     if !can_borrow
     {
-        scratch.truncate(scratch.len() - (whitespace + lines));
+        scratch.truncate(scratch.len() - whitespace);
     }
     // Note we use the stats which point at the last word read
     let advance = scalar_stats.read - stats.read;
@@ -337,7 +335,7 @@ pub(in crate::scanner) fn scan_plain_scalar_lazy<'de>(
                 break 'scalar;
             }
 
-            advance!(buffer, :local_stats, 1);
+            advance!(buffer, :local_stats, widthOf!(~buffer));
         }
         // Save last non whitespace character position
         scalar_stats = local_stats.clone();
@@ -406,17 +404,6 @@ fn flow_indicator(buffer: &str, offset: usize) -> bool
     check!(~buffer, offset => b',' | b'[' | b']' | b'{' | b'}')
 }
 
-/// Handles the trap door from borrowing to copying
-fn set_no_borrow(can_borrow: &mut bool, base: &str, buffer: &str, scratch: &mut Vec<u8>)
-{
-    if *can_borrow
-    {
-        scratch.extend_from_slice(base[0..base.len() - buffer.len()].as_bytes());
-    }
-
-    *can_borrow = false
-}
-
 #[derive(Debug, Clone)]
 pub(in crate::scanner) struct Deferred<'de>
 {
@@ -560,6 +547,77 @@ mod tests
         Ok(())
     }
 
+    #[test]
+    fn trailing_break_borrows() -> TestResult
+    {
+        let data = "hello\n";
+        let mut stats = MStats::new();
+        let cxt = cxt!(block -> [0]);
+
+        let (token, amt) =
+            scan_plain_scalar(TEST_FLAGS, data, &mut stats, &cxt).and_then(normalize)?;
+
+        assert!(
+            matches!(
+                token,
+                Token::Scalar(std::borrow::Cow::Borrowed("hello"), Plain)
+            ),
+            "expected a borrowed scalar, got: {:?}",
+            token
+        );
+
+        assert_eq!(amt, 5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn trailing_whitespace_borrows() -> TestResult
+    {
+        let tests = ["hello  \n", "hello\t\n\n  \n"];
+        let mut stats = MStats::new();
+        let cxt = cxt!(block -> [0]);
+
+        for (i, &data) in tests.iter().enumerate()
+        {
+            let (token, amt) = scan_plain_scalar(TEST_FLAGS, data, &mut stats, &cxt)
+                .and_then(normalize)
+                .map_err(|e| anyhow!("iteration {}: {}", i, e))?;
+
+            assert!(
+                matches!(
+                    token,
+                    Token::Scalar(std::borrow::Cow::Borrowed("hello"), Plain)
+                ),
+                "expected a borrowed scalar on iteration {}, got: {:?}",
+                i,
+                token
+            );
+
+            assert_eq!(amt, 5, "on iteration {}", i);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn trailing_break_after_join() -> TestResult
+    {
+        let data = "hello\n world  \n\n\n";
+        let mut stats = MStats::new();
+        let cxt = cxt!(block -> [0]);
+        let expected = Token::Scalar(cow!("hello world"), Plain);
+
+        let (token, amt) =
+            scan_plain_scalar(TEST_FLAGS, data, &mut stats, &cxt).and_then(normalize)?;
+
+        assert_eq!(token, expected);
+
+        assert_eq!(amt, 12);
+
+        Ok(())
+    }
+
     /* === BLOCK CONTEXT === */
 
     #[test]
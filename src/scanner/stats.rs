@@ -13,6 +13,8 @@
 
 use std::ops::{Add, AddAssign};
 
+use crate::event::types::Mark;
+
 /// Vessel for tracking various stats about the underlying
 /// buffer that are required for correct parsing of certain
 /// elements, and when contextualizing an error.
@@ -48,6 +50,13 @@ impl MStats
             _ => self.column = column,
         }
     }
+
+    /// Retrieve the current position in the buffer as a
+    /// [`Mark`]
+    pub fn mark(&self) -> Mark
+    {
+        Mark::new(self.read, self.lines, self.column)
+    }
 }
 
 impl Add for MStats
@@ -180,7 +180,7 @@ pub(in crate::scanner) fn scan_node_tag<'de>(
     // If its a verbatim tag scan it
     let (token, amt) = if check!(~buffer => [b'!', b'<', ..])
     {
-        advance!(buffer, 2);
+        advance!(buffer, :stats, 2);
 
         // !<global:verbatim:tag:> "node"
         //   ^^^^^^^^^^^^^^^^^^^^
@@ -188,8 +188,9 @@ pub(in crate::scanner) fn scan_node_tag<'de>(
 
         // !<global:verbatim:tag:> "node"
         //                       ^
-        cache!(~buffer, @amt + 1, 1, opts)?;
-        check!(~buffer, amt + 1 => b'>', else ScanError::InvalidTagSuffix)?;
+        cache!(~buffer, @amt, 1, opts)?;
+        check!(~buffer, amt => b'>', else ScanError::InvalidTagSuffix)?;
+        stats.update(1, 0, 1);
 
         let token = assemble_tag(&buffer[0..0], verbatim, can_borrow);
 
@@ -358,7 +359,7 @@ pub(in crate::scanner) fn scan_tag_handle<'b>(
     // !handle!tag
     //  ^^^^^^
     // Safety: we just proved above we have >= 1 byte ('!')
-    let name = take_while(opts, buffer[1..].as_bytes(), u8::is_ascii_alphanumeric)?;
+    let name = take_while(opts, &buffer.as_bytes()[1..], u8::is_ascii_alphanumeric)?;
     let mut offset = 1 + name.len();
 
     cache!(~buffer, @offset, 1, opts)?;
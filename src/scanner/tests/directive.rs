@@ -133,3 +133,35 @@ fn directive_tag_percent_encoding()
 
     assert_eq!(s.scan.stats, stats_of(data));
 }
+
+#[test]
+fn directive_version_yaml11_escapes()
+{
+    let data = "%YAML 1.1\n--- \"\\/\"\n";
+    let mut s = ScanIter::new(data);
+
+    tokens!(s =>
+        | StreamStart(StreamEncoding::UTF8)             => "expected start of stream",
+        | VersionDirective(1, 1)                        => "expected version directive (1, 1)",
+        | DocumentStart                                 => "expected a document start",
+        > Result::<Token>::Err(ScanError::UnknownEscape) => "expected an unknown escape error"
+    );
+}
+
+#[test]
+fn directive_version_yaml11_document_scope()
+{
+    let data = "%YAML 1.1\n--- \"a\"\n--- \"\\/\"\n";
+    let mut s = ScanIter::new(data);
+
+    tokens!(s =>
+        | StreamStart(StreamEncoding::UTF8)             => "expected start of stream",
+        | VersionDirective(1, 1)                        => "expected version directive (1, 1)",
+        | DocumentStart                                 => "expected a document start",
+        | Scalar(cow!("a"), DoubleQuote)                => "expected a scalar",
+        | DocumentStart                                 => "expected a document start",
+        | Scalar(cow!("/"), DoubleQuote)                => "expected a 1.2 escape",
+        | StreamEnd                                     => "expected end of stream",
+        @ None                                          => "expected stream to be finished"
+    );
+}
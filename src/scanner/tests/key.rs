@@ -140,7 +140,7 @@ fn stale_after_newline()
  key': 'a value'
 ";
 
-    let mut s = ScanIter::new(&data);
+    let mut s = ScanIter::new(data);
 
     tokens!(s =>
         | StreamStart(StreamEncoding::UTF8),
@@ -57,6 +57,47 @@ mod plain
 
         assert_eq!(s.scan.stats, stats_of(data));
     }
+
+    #[test]
+    fn scalar_multibyte()
+    {
+        let data = "värde 🦀\n  över två rader";
+        let mut s = ScanIter::new(data);
+
+        tokens!(s =>
+            | StreamStart(StreamEncoding::UTF8)                  => "expected start of stream",
+            | Scalar(cow!("värde 🦀 över två rader"), Plain)     => "expected a plain scalar",
+            | StreamEnd                                          => "expected end of stream",
+            @ None                                               => "expected stream to be finished"
+        );
+
+        assert_eq!(s.scan.stats, stats_of(data));
+    }
+
+    #[test]
+    fn scalar_trailing_breaks()
+    {
+        let data = "a: one\n  two\n\nb: three  \n\n";
+        let mut s = ScanIter::new(data);
+
+        tokens!(s =>
+            | StreamStart(StreamEncoding::UTF8)  => "expected start of stream",
+            | BlockMappingStart                  => "expected the start of a block mapping",
+            | Key                                => "expected an implicit key",
+            | Scalar(cow!("a"), Plain)           => "expected a plain scalar",
+            | Value                              => "expected a value",
+            | Scalar(cow!("one two"), Plain)     => "expected a multi line plain scalar",
+            | Key                                => "expected an implicit key",
+            | Scalar(cow!("b"), Plain)           => "expected a plain scalar",
+            | Value                              => "expected a value",
+            | Scalar(cow!("three"), Plain)       => "expected a plain scalar",
+            | BlockEnd                           => "expected the end of a block mapping",
+            | StreamEnd                          => "expected end of stream",
+            @ None                               => "expected stream to be finished"
+        );
+
+        assert_eq!(s.scan.stats, stats_of(data));
+    }
 }
 
 mod flow
@@ -117,6 +158,22 @@ mod flow
         assert_eq!(s.scan.stats, stats_of(data));
     }
 
+    #[test]
+    fn scalar_double_multibyte()
+    {
+        let data = "\"värde 🦀\n  över två rader\"";
+        let mut s = ScanIter::new(data);
+
+        tokens!(s =>
+            | StreamStart(StreamEncoding::UTF8)                      => "expected start of stream",
+            | Scalar(cow!("värde 🦀 över två rader"), DoubleQuote)   => "expected a flow scalar (double)",
+            | StreamEnd                                              => "expected end of stream",
+            @ None                                                   => "expected stream to be finished"
+        );
+
+        assert_eq!(s.scan.stats, stats_of(data));
+    }
+
     #[test]
     fn scalar_double_complex()
     {
@@ -35,7 +35,7 @@ impl<'de> StrReader<'de>
 
     pub fn new(s: &'de str, size: usize) -> Self
     {
-        let size = std::cmp::min(s.len(), size);
+        let size = char_boundary(s, std::cmp::min(s.len(), size));
 
         Self { s, size }
     }
@@ -51,7 +51,7 @@ impl<'de> StrReader<'de>
 
         match self.s.len() > new
         {
-            true => self.size = new,
+            true => self.size = char_boundary(self.s, new),
             false => self.size = self.s.len(),
         }
     }
@@ -62,6 +62,18 @@ impl<'de> StrReader<'de>
     }
 }
 
+/// Round .size up to the next character boundary in .s,
+/// as we cannot split a str mid character
+fn char_boundary(s: &str, mut size: usize) -> usize
+{
+    while !s.is_char_boundary(size)
+    {
+        size += 1;
+    }
+
+    size
+}
+
 impl std::fmt::Display for StrReader<'_>
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
@@ -89,3 +89,19 @@ fn non_resolving()
 
     assert_eq!(s.scan.stats, (2, 0, 2));
 }
+
+#[test]
+fn verbatim()
+{
+    let data = "!<tag:yaml.org,2002:str> ";
+    let mut s = ScanIter::new(data);
+
+    tokens!(s =>
+        | StreamStart(StreamEncoding::UTF8)             => "expected start of stream",
+        | Tag(cow!(""), cow!("tag:yaml.org,2002:str"))  => "expected a verbatim tag ('', 'tag:yaml.org,2002:str')",
+        | StreamEnd                                     => "expected end of stream",
+        @ None                                          => "expected stream to be finished"
+    );
+
+    assert_eq!(s.scan.stats, stats_of(data));
+}
@@ -66,3 +66,30 @@ fn eat_comments()
 
     assert_eq!(s.scan.stats, stats_of(data));
 }
+
+#[test]
+fn scan_comments()
+{
+    use crate::token::CommentPlacement::*;
+
+    let data = "# own line\nkey: value # trailing\n  #indented\n";
+    let mut s = ScanIter::new(data);
+    s.opts.insert(O_COMMENTS);
+
+    tokens!(s =>
+        | StreamStart(StreamEncoding::UTF8)     => "expected start of stream",
+        | Comment(cow!(" own line"), OwnLine)   => "expected an own line comment",
+        | BlockMappingStart                     => "expected start of block mapping",
+        | Key                                   => "expected an explicit key",
+        | Scalar(cow!("key"), Plain)            => "expected a flow scalar (key)",
+        | Value                                 => "expected an explicit value",
+        | Scalar(cow!("value"), Plain)          => "expected a flow scalar (value)",
+        | Comment(cow!(" trailing"), Trailing)  => "expected a trailing comment",
+        | Comment(cow!("indented"), OwnLine)    => "expected an own line comment",
+        | BlockEnd                              => "expected end of block mapping",
+        | StreamEnd                             => "expected end of stream",
+        @ None                                  => "expected stream to be finished"
+    );
+
+    assert_eq!(s.scan.stats, stats_of(data));
+}
@@ -0,0 +1,1058 @@
+/*
+ * This Source Code Form is subject to the terms of the
+ * Mozilla Public License, v. 2.0. If a copy of the MPL
+ * was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+//! This module exposes the YAML schemas used to resolve the
+//! type of a scalar node.
+//!
+//! [`Event`]s only carry a scalar's content and style, it
+//! is the job of a [`Schema`] to decide whether a given
+//! scalar is a null, bool, int, float or string. Three
+//! schemas are provided, matching those defined in the YAML
+//! 1.2 spec:
+//!
+//! - [`Failsafe`] Every scalar is a string
+//! - [`Json`] Only JSON compatible literals are recognized,
+//!   and any other plain scalar is an error
+//! - [`Core`] A superset of JSON, accepting the common
+//!   spellings of each type, e.g `~`, `True`, `0x1F`,
+//!   `.inf`
+//!
+//! Additionally, the [`Yaml11`] schema provides the (much
+//! looser) resolution rules of the YAML 1.1 spec. Use
+//! [`for_version`] to select the correct schema for a
+//! document's `%YAML` version.
+//!
+//! Callers may implement [`Schema`] themselves to provide
+//! different resolution rules.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use yary::{
+//!     event::types::{ResolvedTag, Scalar, ScalarStyle},
+//!     schema::{Core, Failsafe, Resolved, Schema},
+//! };
+//!
+//! let hex = Scalar::new("0x1F".into(), ScalarStyle::Plain);
+//! let quoted = Scalar::new("0x1F".into(), ScalarStyle::SingleQuote);
+//!
+//! assert_eq!(
+//!     Core.resolve(&ResolvedTag::Question, &hex)?,
+//!     Resolved::Int(31)
+//! );
+//! assert_eq!(
+//!     Core.resolve(&ResolvedTag::Exclamation, &quoted)?,
+//!     Resolved::Str
+//! );
+//! assert_eq!(
+//!     Failsafe.resolve(&ResolvedTag::Question, &hex)?,
+//!     Resolved::Str
+//! );
+//!
+//! // Explicit tags in the YAML namespace, e.g !!str, are respected
+//! let tag = ResolvedTag::Specific("tag:yaml.org,2002:str".into());
+//! assert_eq!(Core.resolve(&tag, &hex)?, Resolved::Str);
+//! # Ok::<(), yary::Error>(())
+//! ```
+//!
+//! [`Event`]: crate::event::types::Event
+
+use crate::{
+    error::{internal::ErrorCode, mkError, Result},
+    event::types::{ResolvedTag, Scalar, ScalarStyle, VersionDirective},
+};
+
+/// The prefix of every tag in the YAML namespace, which
+/// `!!` expands to by default
+const YAML_PREFIX: &str = "tag:yaml.org,2002:";
+
+/// Resolve a scalar's type using the YAML 1.2 [`Core`]
+/// schema.
+///
+/// See [`Schema::resolve`] for more information.
+pub fn resolve(tag: &ResolvedTag<'_>, scalar: &Scalar<'_>) -> Result<Resolved>
+{
+    Core.resolve(tag, scalar)
+}
+
+/// Select the default schema for documents of the given
+/// .version, that is [`Yaml11`] for YAML 1.1 (or older)
+/// documents, and [`Core`] otherwise.
+///
+/// ## Examples
+///
+/// ```rust
+/// use yary::{
+///     event::types::{ResolvedTag, Scalar, ScalarStyle, DEFAULT_VERSION, YAML11_VERSION},
+///     schema::{for_version, Resolved},
+/// };
+///
+/// let yes = Scalar::new("yes".into(), ScalarStyle::Plain);
+///
+/// assert_eq!(
+///     for_version(DEFAULT_VERSION).resolve(&ResolvedTag::Question, &yes)?,
+///     Resolved::Str
+/// );
+/// assert_eq!(
+///     for_version(YAML11_VERSION).resolve(&ResolvedTag::Question, &yes)?,
+///     Resolved::Bool(true)
+/// );
+/// # Ok::<(), yary::Error>(())
+/// ```
+pub fn for_version(version: VersionDirective) -> &'static dyn Schema
+{
+    match version
+    {
+        VersionDirective { major: 0, .. }
+        | VersionDirective {
+            major: 1,
+            minor: 0..=1,
+        } => &Yaml11,
+        _ => &Core,
+    }
+}
+
+/// A scalar's resolved type, and its value if the type is
+/// not a string.
+///
+/// Strings carry no value, as the content of the [`Scalar`]
+/// they were resolved from can be used as is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Resolved
+{
+    /// `tag:yaml.org,2002:null`
+    Null,
+    /// `tag:yaml.org,2002:bool`
+    Bool(bool),
+    /// `tag:yaml.org,2002:int`, that fits in an [`i64`]
+    Int(i64),
+    /// `tag:yaml.org,2002:int`, that is larger than
+    /// [`i64::MAX`] but fits in an [`u64`]
+    UInt(u64),
+    /// `tag:yaml.org,2002:float`
+    Float(f64),
+    /// `tag:yaml.org,2002:str`
+    Str,
+}
+
+/// A set of rules for resolving the type of a scalar.
+///
+/// Implementors must provide rules for untagged plain
+/// scalars, and scalars tagged in the YAML
+/// (`tag:yaml.org,2002:`) namespace.
+/// [`resolve`](Schema::resolve) then applies these rules to
+/// a scalar and its tag.
+pub trait Schema
+{
+    /// Resolve an untagged plain scalar.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if the scalar is an integer that
+    /// does not fit in an [`i64`] or [`u64`], or if the
+    /// schema does not allow the scalar.
+    fn resolve_plain(&self, scalar: &str) -> Result<Resolved>;
+
+    /// Resolve a scalar explicitly tagged with a type from
+    /// the YAML namespace, e.g `!!int`. The .suffix is the
+    /// tag without its `tag:yaml.org,2002:` prefix, e.g
+    /// `int`.
+    ///
+    /// Types unknown to the schema should resolve to
+    /// [`Resolved::Str`], allowing the caller to handle
+    /// them.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if the scalar cannot be represented
+    /// as the tagged type.
+    fn resolve_tagged(&self, suffix: &str, scalar: &str) -> Result<Resolved>;
+
+    /// Resolve the given .scalar by its resolved .tag, as
+    /// found in [`Node::resolved`].
+    ///
+    /// - Scalars whose tag is in the YAML namespace,
+    ///   however it was written, are resolved with
+    ///   [`resolve_tagged`](Schema::resolve_tagged)
+    /// - Scalars with any other specific tag are strings
+    /// - Plain scalars with the `?` non-specific tag are
+    ///   resolved with
+    ///   [`resolve_plain`](Schema::resolve_plain)
+    /// - Any other scalar is a string
+    ///
+    /// ## Errors
+    ///
+    /// Forwards any error from the above methods.
+    ///
+    /// [`Node::resolved`]: crate::event::types::Node::resolved
+    fn resolve(&self, tag: &ResolvedTag<'_>, scalar: &Scalar<'_>) -> Result<Resolved>
+    {
+        match tag
+        {
+            ResolvedTag::Specific(uri) => match uri.strip_prefix(YAML_PREFIX)
+            {
+                Some(suffix) => self.resolve_tagged(suffix, scalar),
+                None => Ok(Resolved::Str),
+            },
+            ResolvedTag::Question if scalar.style() == ScalarStyle::Plain =>
+            {
+                self.resolve_plain(scalar)
+            },
+            _ => Ok(Resolved::Str),
+        }
+    }
+}
+
+/// The YAML 1.2 failsafe schema, which resolves every
+/// scalar as a string.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Failsafe;
+
+impl Schema for Failsafe
+{
+    fn resolve_plain(&self, _: &str) -> Result<Resolved>
+    {
+        Ok(Resolved::Str)
+    }
+
+    fn resolve_tagged(&self, _: &str, _: &str) -> Result<Resolved>
+    {
+        Ok(Resolved::Str)
+    }
+}
+
+/// The YAML 1.2 JSON schema, which only accepts the
+/// literals JSON allows.
+///
+/// Note that under this schema, any plain scalar that is
+/// not a null, bool, int or float is an error. Strings must
+/// be quoted.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Json;
+
+impl Schema for Json
+{
+    fn resolve_plain(&self, scalar: &str) -> Result<Resolved>
+    {
+        json_schema::null(scalar)
+            .or_else(|| json_schema::bool(scalar))
+            .map(Ok)
+            .or_else(|| json_schema::int(scalar).transpose())
+            .transpose()?
+            .or_else(|| json_schema::float(scalar))
+            .ok_or_else(|| mkError!(ErrorCode::InvalidScalar, CODE))
+    }
+
+    fn resolve_tagged(&self, suffix: &str, scalar: &str) -> Result<Resolved>
+    {
+        let resolved = match suffix
+        {
+            "null" => json_schema::null(scalar),
+            "bool" => json_schema::bool(scalar),
+            "int" => json_schema::int(scalar)?,
+            "float" => json_schema::float(scalar),
+            _ => Some(Resolved::Str),
+        };
+
+        resolved.ok_or_else(|| mkError!(ErrorCode::InvalidScalar, CODE))
+    }
+}
+
+/// The YAML 1.2 core schema, and the recommended default.
+///
+/// Extends the [`Json`] schema with additional spellings
+/// of each type, and resolves any other plain scalar as a
+/// string.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Core;
+
+impl Schema for Core
+{
+    fn resolve_plain(&self, scalar: &str) -> Result<Resolved>
+    {
+        let resolved = core_schema::null(scalar)
+            .or_else(|| core_schema::bool(scalar))
+            .map(Ok)
+            .or_else(|| core_schema::int(scalar).transpose())
+            .transpose()?
+            .or_else(|| core_schema::float(scalar))
+            .unwrap_or(Resolved::Str);
+
+        Ok(resolved)
+    }
+
+    fn resolve_tagged(&self, suffix: &str, scalar: &str) -> Result<Resolved>
+    {
+        let resolved = match suffix
+        {
+            "null" => core_schema::null(scalar),
+            "bool" => core_schema::bool(scalar),
+            "int" => core_schema::int(scalar)?,
+            // Allow any int representation to be read as a float
+            "float" => core_schema::float(scalar).or_else(|| match core_schema::int(scalar)
+            {
+                Ok(Some(Resolved::Int(i))) => Some(Resolved::Float(i as f64)),
+                Ok(Some(Resolved::UInt(u))) => Some(Resolved::Float(u as f64)),
+                _ => None,
+            }),
+            _ => Some(Resolved::Str),
+        };
+
+        resolved.ok_or_else(|| mkError!(ErrorCode::InvalidScalar, CODE))
+    }
+}
+
+/// The YAML 1.1 types, as used by the 1.1 spec.
+///
+/// This schema accepts many more spellings than [`Core`],
+/// notably:
+///
+/// - Booleans may be `yes`, `no`, `on`, `off`, `y` or `n`
+/// - Integers may be binary (`0b101`), octal with a leading
+///   zero (`0755`), or sexagesimal (`1:30`)
+/// - Numbers may contain `_` separators, e.g `1_000`
+///
+/// Any other plain scalar is a string. Note that merge
+/// keys (`<<`) are not resolved by this schema.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Yaml11;
+
+impl Schema for Yaml11
+{
+    fn resolve_plain(&self, scalar: &str) -> Result<Resolved>
+    {
+        let resolved = yaml11_schema::null(scalar)
+            .or_else(|| yaml11_schema::bool(scalar))
+            .map(Ok)
+            .or_else(|| yaml11_schema::int(scalar).transpose())
+            .transpose()?
+            .or_else(|| yaml11_schema::float(scalar))
+            .unwrap_or(Resolved::Str);
+
+        Ok(resolved)
+    }
+
+    fn resolve_tagged(&self, suffix: &str, scalar: &str) -> Result<Resolved>
+    {
+        let resolved = match suffix
+        {
+            "null" => yaml11_schema::null(scalar),
+            "bool" => yaml11_schema::bool(scalar),
+            "int" => yaml11_schema::int(scalar)?,
+            // Allow any int representation to be read as a float
+            "float" => yaml11_schema::float(scalar).or_else(|| match yaml11_schema::int(scalar)
+            {
+                Ok(Some(Resolved::Int(i))) => Some(Resolved::Float(i as f64)),
+                Ok(Some(Resolved::UInt(u))) => Some(Resolved::Float(u as f64)),
+                _ => None,
+            }),
+            _ => Some(Resolved::Str),
+        };
+
+        resolved.ok_or_else(|| mkError!(ErrorCode::InvalidScalar, CODE))
+    }
+}
+
+/// Resolution rules for the JSON schema
+mod json_schema
+{
+    use super::*;
+
+    pub(super) fn null(s: &str) -> Option<Resolved>
+    {
+        match s
+        {
+            "null" => Some(Resolved::Null),
+            _ => None,
+        }
+    }
+
+    pub(super) fn bool(s: &str) -> Option<Resolved>
+    {
+        match s
+        {
+            "true" => Some(Resolved::Bool(true)),
+            "false" => Some(Resolved::Bool(false)),
+            _ => None,
+        }
+    }
+
+    /// -? ( 0 | [1-9] [0-9]* )
+    pub(super) fn int(s: &str) -> Result<Option<Resolved>>
+    {
+        let unsigned = s.strip_prefix('-').unwrap_or(s);
+
+        if !is_json_int(unsigned)
+        {
+            return Ok(None);
+        }
+
+        from_digits(s, unsigned, 10).map(Some)
+    }
+
+    /// -? ( 0 | [1-9] [0-9]* ) ( \. [0-9]* )? ( [eE] [-+]?
+    /// [0-9]+ )?
+    pub(super) fn float(s: &str) -> Option<Resolved>
+    {
+        let unsigned = s.strip_prefix('-').unwrap_or(s);
+
+        let (mantissa, exponent) = split_exponent(unsigned);
+        let (whole, fraction) = match mantissa.find('.')
+        {
+            Some(idx) => (&mantissa[..idx], &mantissa[idx + 1..]),
+            None => (mantissa, ""),
+        };
+
+        let valid = is_json_int(whole)
+            && is_digits(fraction)
+            && exponent.map_or(true, |e| {
+                let e = e.strip_prefix(|c| c == '-' || c == '+').unwrap_or(e);
+
+                !e.is_empty() && is_digits(e)
+            });
+
+        match valid
+        {
+            true => s.parse().ok().map(Resolved::Float),
+            false => None,
+        }
+    }
+
+    fn is_json_int(s: &str) -> bool
+    {
+        match s.as_bytes()
+        {
+            [b'0'] => true,
+            [b'1'..=b'9', ..] => is_digits(s),
+            _ => false,
+        }
+    }
+}
+
+/// Resolution rules for the core schema
+mod core_schema
+{
+    use super::*;
+
+    pub(super) fn null(s: &str) -> Option<Resolved>
+    {
+        match s
+        {
+            "" | "~" | "null" | "Null" | "NULL" => Some(Resolved::Null),
+            _ => None,
+        }
+    }
+
+    pub(super) fn bool(s: &str) -> Option<Resolved>
+    {
+        match s
+        {
+            "true" | "True" | "TRUE" => Some(Resolved::Bool(true)),
+            "false" | "False" | "FALSE" => Some(Resolved::Bool(false)),
+            _ => None,
+        }
+    }
+
+    /// [-+]? [0-9]+ | 0o [0-7]+ | 0x [0-9a-fA-F]+
+    pub(super) fn int(s: &str) -> Result<Option<Resolved>>
+    {
+        let (digits, radix) = if let Some(hex) = s.strip_prefix("0x")
+        {
+            (hex, 16)
+        }
+        else if let Some(oct) = s.strip_prefix("0o")
+        {
+            (oct, 8)
+        }
+        else
+        {
+            (s, 10)
+        };
+
+        let unsigned = match (radix, digits.as_bytes().first())
+        {
+            (10, Some(b'-')) | (10, Some(b'+')) => &digits[1..],
+            _ => digits,
+        };
+
+        if unsigned.is_empty() || !unsigned.chars().all(|c| c.is_digit(radix))
+        {
+            return Ok(None);
+        }
+
+        from_digits(digits, unsigned, radix).map(Some)
+    }
+
+    /// [-+]? ( \. [0-9]+ | [0-9]+ ( \. [0-9]* )? ) ( [eE]
+    /// [-+]? [0-9]+ )? | [-+]? \. ( inf | Inf | INF ) | \.
+    /// ( nan | NaN | NAN )
+    pub(super) fn float(s: &str) -> Option<Resolved>
+    {
+        let unsigned = s.trim_start_matches(|c| c == '-' || c == '+');
+        let negative = s.starts_with('-');
+
+        // At most one sign is permitted
+        if s.len() - unsigned.len() > 1
+        {
+            return None;
+        }
+
+        match unsigned
+        {
+            ".inf" | ".Inf" | ".INF" if negative =>
+            {
+                return Some(Resolved::Float(f64::NEG_INFINITY))
+            },
+            ".inf" | ".Inf" | ".INF" => return Some(Resolved::Float(f64::INFINITY)),
+            ".nan" | ".NaN" | ".NAN" if s == unsigned => return Some(Resolved::Float(f64::NAN)),
+            _ =>
+            {},
+        }
+
+        let (mantissa, exponent) = split_exponent(unsigned);
+
+        let mut parts = mantissa.splitn(2, '.');
+        let whole = parts.next().unwrap_or("");
+        let fraction = parts.next();
+
+        let valid_mantissa = match fraction
+        {
+            Some(fraction) =>
+            {
+                (!whole.is_empty() || !fraction.is_empty())
+                    && is_digits(whole)
+                    && is_digits(fraction)
+            },
+            None => !whole.is_empty() && is_digits(whole),
+        };
+        let valid_exponent = match exponent
+        {
+            Some(e) =>
+            {
+                let e = e.strip_prefix(|c| c == '-' || c == '+').unwrap_or(e);
+
+                !e.is_empty() && is_digits(e)
+            },
+            None => true,
+        };
+
+        if !valid_mantissa || !valid_exponent
+        {
+            return None;
+        }
+
+        s.parse().ok().map(Resolved::Float)
+    }
+}
+
+/// Resolution rules for the YAML 1.1 types
+mod yaml11_schema
+{
+    use super::*;
+
+    pub(super) fn null(s: &str) -> Option<Resolved>
+    {
+        core_schema::null(s)
+    }
+
+    pub(super) fn bool(s: &str) -> Option<Resolved>
+    {
+        match s
+        {
+            "y" | "Y" | "yes" | "Yes" | "YES" | "true" | "True" | "TRUE" | "on" | "On" | "ON" =>
+            {
+                Some(Resolved::Bool(true))
+            },
+            "n" | "N" | "no" | "No" | "NO" | "false" | "False" | "FALSE" | "off" | "Off"
+            | "OFF" => Some(Resolved::Bool(false)),
+            _ => None,
+        }
+    }
+
+    /// [-+]? 0b [0-1_]+
+    /// | [-+]? 0 [0-7_]+
+    /// | [-+]? ( 0 | [1-9] [0-9_]* )
+    /// | [-+]? 0x [0-9a-fA-F_]+
+    /// | [-+]? [1-9] [0-9_]* ( : [0-5]? [0-9] )+
+    pub(super) fn int(s: &str) -> Result<Option<Resolved>>
+    {
+        let (negative, unsigned) = split_sign(s);
+
+        let magnitude = if let Some(bin) = unsigned.strip_prefix("0b")
+        {
+            digits(bin, 2)?
+        }
+        else if let Some(hex) = unsigned.strip_prefix("0x")
+        {
+            digits(hex, 16)?
+        }
+        else if let Some(oct) = unsigned.strip_prefix('0').filter(|oct| !oct.is_empty())
+        {
+            digits(oct, 8)?
+        }
+        else if unsigned.contains(':')
+        {
+            sexagesimal(unsigned)?
+        }
+        else if unsigned == "0" || unsigned.starts_with(|c: char| c.is_ascii_digit())
+        {
+            digits(unsigned, 10)?
+        }
+        else
+        {
+            None
+        };
+
+        magnitude.map(|m| signed(negative, m)).transpose()
+    }
+
+    /// [-+]? ( [0-9] [0-9_]* )? \. [0-9_]* ( [eE] [-+]
+    /// [0-9]+ )? | [-+]? [0-9] [0-9_]* ( : [0-5]? [0-9]
+    /// )+ \. [0-9_]* | [-+]? \. ( inf | Inf | INF )
+    /// | \. ( nan | NaN | NAN )
+    pub(super) fn float(s: &str) -> Option<Resolved>
+    {
+        let (negative, unsigned) = split_sign(s);
+        let sign = if negative { -1.0 } else { 1.0 };
+
+        match unsigned
+        {
+            ".inf" | ".Inf" | ".INF" => return Some(Resolved::Float(sign * f64::INFINITY)),
+            ".nan" | ".NaN" | ".NAN" if s == unsigned => return Some(Resolved::Float(f64::NAN)),
+            _ =>
+            {},
+        }
+
+        let (mantissa, exponent) = split_exponent(unsigned);
+        let (whole, fraction) = match mantissa.find('.')
+        {
+            Some(idx) => (&mantissa[..idx], &mantissa[idx + 1..]),
+            None => return None,
+        };
+
+        let is_separated = |s: &str| s.chars().all(|c| c.is_ascii_digit() || c == '_');
+        let has_digits = |s: &str| s.chars().any(|c| c.is_ascii_digit());
+
+        let valid_exponent = exponent.map_or(true, |e| {
+            e.strip_prefix(|c| c == '-' || c == '+')
+                .map_or(false, |e| !e.is_empty() && is_digits(e))
+        });
+
+        if !valid_exponent
+            || !is_separated(fraction)
+            || !(has_digits(whole) || has_digits(fraction))
+        {
+            return None;
+        }
+
+        let strip = |s: &str| s.chars().filter(|&c| c != '_').collect::<String>();
+        let fraction = strip(fraction);
+
+        let value: f64 = match whole.contains(':')
+        {
+            true if exponent.is_none() =>
+            {
+                let whole = sexagesimal(whole).ok()?? as f64;
+                let fraction: f64 = format!("0.{}0", fraction).parse().ok()?;
+
+                whole + fraction
+            },
+            false if whole.is_empty() || whole.starts_with(|c: char| c.is_ascii_digit()) =>
+            {
+                if !is_separated(whole)
+                {
+                    return None;
+                }
+
+                format!(
+                    "0{}.{}0e{}",
+                    strip(whole),
+                    fraction,
+                    exponent.unwrap_or("0")
+                )
+                .parse()
+                .ok()?
+            },
+            _ => return None,
+        };
+
+        Some(Resolved::Float(sign * value))
+    }
+
+    /// Split any leading sign from .s, returning whether
+    /// the sign was negative and the remaining unsigned
+    /// .s
+    fn split_sign(s: &str) -> (bool, &str)
+    {
+        match s.as_bytes().first()
+        {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        }
+    }
+
+    /// Parse the given .s in .radix, ignoring any '_'
+    /// separators, returning None if .s is not a valid
+    /// number
+    fn digits(s: &str, radix: u32) -> Result<Option<u64>>
+    {
+        let mut digits = s.chars().filter(|&c| c != '_').peekable();
+
+        if digits.peek().is_none() || !s.chars().all(|c| c == '_' || c.is_digit(radix))
+        {
+            return Ok(None);
+        }
+
+        digits
+            .try_fold(0u64, |acc, c| {
+                let digit = c.to_digit(radix).expect("digits were checked above") as u64;
+
+                acc.checked_mul(radix as u64)?.checked_add(digit)
+            })
+            .map(Some)
+            .ok_or_else(|| mkError!(ErrorCode::IntOverflow, CODE))
+    }
+
+    /// Parse a base 60 number, e.g `1:30:00`, returning
+    /// None if .s is not a valid number
+    fn sexagesimal(s: &str) -> Result<Option<u64>>
+    {
+        let mut parts = s.split(':');
+        let first = parts.next().unwrap_or("");
+
+        if !first.starts_with(|c: char| matches!(c, '1'..='9'))
+        {
+            return Ok(None);
+        }
+
+        let mut value = match digits(first, 10)?
+        {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+
+        for part in parts
+        {
+            let valid = matches!(part.as_bytes(), [b'0'..=b'9'] | [b'0'..=b'5', b'0'..=b'9']);
+
+            if !valid
+            {
+                return Ok(None);
+            }
+
+            let digit: u64 = part.parse().expect("part was checked above");
+
+            value = value
+                .checked_mul(60)
+                .and_then(|v| v.checked_add(digit))
+                .ok_or_else(|| mkError!(ErrorCode::IntOverflow, CODE))?;
+        }
+
+        Ok(Some(value))
+    }
+
+    /// Apply the given sign to .magnitude, erroring if the
+    /// resulting int does not fit in an i64 or u64
+    fn signed(negative: bool, magnitude: u64) -> Result<Resolved>
+    {
+        const MIN: u64 = i64::MAX as u64 + 1;
+
+        match (negative, magnitude)
+        {
+            (true, MIN) => Ok(Resolved::Int(i64::MIN)),
+            (true, m) if m < MIN => Ok(Resolved::Int(-(m as i64))),
+            (true, _) => Err(mkError!(ErrorCode::IntOverflow, CODE)),
+            (false, m) if m < MIN => Ok(Resolved::Int(m as i64)),
+            (false, m) => Ok(Resolved::UInt(m)),
+        }
+    }
+}
+
+/// Convert the already validated .digits (which may be
+/// signed) into an int, falling back to an unsigned int if
+/// it does not fit in an i64.
+fn from_digits(digits: &str, unsigned: &str, radix: u32) -> Result<Resolved>
+{
+    if let Ok(i) = i64::from_str_radix(digits, radix)
+    {
+        return Ok(Resolved::Int(i));
+    }
+
+    match digits.as_bytes().first()
+    {
+        Some(b'-') => Err(mkError!(ErrorCode::IntOverflow, CODE)),
+        _ => u64::from_str_radix(unsigned, radix)
+            .map(Resolved::UInt)
+            .map_err(|_| mkError!(ErrorCode::IntOverflow, CODE)),
+    }
+}
+
+/// Split a float into its mantissa and (optional) exponent
+fn split_exponent(s: &str) -> (&str, Option<&str>)
+{
+    match s.find(|c| c == 'e' || c == 'E')
+    {
+        Some(idx) => (&s[..idx], Some(&s[idx + 1..])),
+        None => (s, None),
+    }
+}
+
+fn is_digits(s: &str) -> bool
+{
+    s.chars().all(|c| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests
+{
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::error::Category;
+
+    fn plain(s: &str) -> Scalar<'_>
+    {
+        Scalar::new(s.into(), ScalarStyle::Plain)
+    }
+
+    fn tagged(suffix: &str) -> ResolvedTag<'static>
+    {
+        ResolvedTag::Specific(format!("{}{}", YAML_PREFIX, suffix).into())
+    }
+
+    #[test]
+    fn core_plain()
+    {
+        let cases = vec![
+            ("", Resolved::Null),
+            ("~", Resolved::Null),
+            ("NULL", Resolved::Null),
+            ("True", Resolved::Bool(true)),
+            ("false", Resolved::Bool(false)),
+            ("0", Resolved::Int(0)),
+            ("-17", Resolved::Int(-17)),
+            ("+17", Resolved::Int(17)),
+            ("0o17", Resolved::Int(15)),
+            ("0x1F", Resolved::Int(31)),
+            ("18446744073709551615", Resolved::UInt(u64::MAX)),
+            ("1.5", Resolved::Float(1.5)),
+            ("-.5e3", Resolved::Float(-500.0)),
+            ("1.", Resolved::Float(1.0)),
+            (".inf", Resolved::Float(f64::INFINITY)),
+            ("-.Inf", Resolved::Float(f64::NEG_INFINITY)),
+            ("yes", Resolved::Str),
+            ("0x", Resolved::Str),
+            ("1_000", Resolved::Str),
+            (".", Resolved::Str),
+            ("1e", Resolved::Str),
+            ("--1", Resolved::Str),
+        ];
+
+        for (s, expected) in cases
+        {
+            assert_eq!(
+                Core.resolve(&ResolvedTag::Question, &plain(s)).unwrap(),
+                expected,
+                "{:?}",
+                s
+            );
+        }
+
+        let nan = Core
+            .resolve(&ResolvedTag::Question, &plain(".NaN"))
+            .unwrap();
+        assert!(matches!(nan, Resolved::Float(f) if f.is_nan()));
+    }
+
+    #[test]
+    fn json_plain()
+    {
+        let cases = vec![
+            ("null", Resolved::Null),
+            ("true", Resolved::Bool(true)),
+            ("0", Resolved::Int(0)),
+            ("-17", Resolved::Int(-17)),
+            ("1.5", Resolved::Float(1.5)),
+            ("-0.5e+3", Resolved::Float(-500.0)),
+            ("1.", Resolved::Float(1.0)),
+        ];
+
+        for (s, expected) in cases
+        {
+            assert_eq!(
+                Json.resolve(&ResolvedTag::Question, &plain(s)).unwrap(),
+                expected,
+                "{:?}",
+                s
+            );
+        }
+
+        for s in &["", "~", "True", "+17", "01", "0x1F", ".5", ".inf", "string"]
+        {
+            let err = Json.resolve(&ResolvedTag::Question, &plain(s)).unwrap_err();
+
+            assert_eq!(err.classify(), Category::Data, "{:?}", s);
+        }
+    }
+
+    #[test]
+    fn yaml11_plain()
+    {
+        let cases = vec![
+            ("~", Resolved::Null),
+            ("y", Resolved::Bool(true)),
+            ("Yes", Resolved::Bool(true)),
+            ("ON", Resolved::Bool(true)),
+            ("n", Resolved::Bool(false)),
+            ("off", Resolved::Bool(false)),
+            ("0", Resolved::Int(0)),
+            ("-17", Resolved::Int(-17)),
+            ("1_000", Resolved::Int(1000)),
+            ("0755", Resolved::Int(0o755)),
+            ("-0b1010", Resolved::Int(-10)),
+            ("0x_1F", Resolved::Int(31)),
+            ("1:30", Resolved::Int(90)),
+            ("-190:20:30", Resolved::Int(-685230)),
+            ("1.5", Resolved::Float(1.5)),
+            ("1_000.5", Resolved::Float(1000.5)),
+            (".5", Resolved::Float(0.5)),
+            ("1.", Resolved::Float(1.0)),
+            ("6.8523015e+5", Resolved::Float(685230.15)),
+            ("190:20:30.15", Resolved::Float(685230.15)),
+            ("-.inf", Resolved::Float(f64::NEG_INFINITY)),
+            ("0o17", Resolved::Str),
+            ("09", Resolved::Str),
+            ("1e3", Resolved::Str),
+            ("1.5e3", Resolved::Str),
+            ("1:60", Resolved::Str),
+            ("0:30", Resolved::Str),
+            (".", Resolved::Str),
+            ("_", Resolved::Str),
+            ("0b", Resolved::Str),
+            ("<<", Resolved::Str),
+        ];
+
+        for (s, expected) in cases
+        {
+            assert_eq!(
+                Yaml11.resolve(&ResolvedTag::Question, &plain(s)).unwrap(),
+                expected,
+                "{:?}",
+                s
+            );
+        }
+
+        let err = Yaml11
+            .resolve(&ResolvedTag::Question, &plain("0x1_0000_0000_0000_0000"))
+            .unwrap_err();
+        assert_eq!(err.classify(), Category::Data);
+    }
+
+    #[test]
+    fn version_schema()
+    {
+        use crate::event::types::{DEFAULT_VERSION, YAML11_VERSION};
+
+        let yes = plain("yes");
+
+        assert_eq!(
+            for_version(DEFAULT_VERSION)
+                .resolve(&ResolvedTag::Question, &yes)
+                .unwrap(),
+            Resolved::Str
+        );
+        assert_eq!(
+            for_version(YAML11_VERSION)
+                .resolve(&ResolvedTag::Question, &yes)
+                .unwrap(),
+            Resolved::Bool(true)
+        );
+    }
+
+    #[test]
+    fn failsafe()
+    {
+        for s in &["", "~", "true", "17", "1.5"]
+        {
+            assert_eq!(
+                Failsafe.resolve(&ResolvedTag::Question, &plain(s)).unwrap(),
+                Resolved::Str
+            );
+        }
+
+        let tag = tagged("int");
+        assert_eq!(Failsafe.resolve(&tag, &plain("17")).unwrap(), Resolved::Str);
+    }
+
+    #[test]
+    fn non_plain()
+    {
+        let quoted = Scalar::new("17".into(), ScalarStyle::DoubleQuote);
+
+        for tag in &[ResolvedTag::Question, ResolvedTag::Exclamation]
+        {
+            assert_eq!(Core.resolve(tag, &quoted).unwrap(), Resolved::Str);
+            assert_eq!(Json.resolve(tag, &quoted).unwrap(), Resolved::Str);
+        }
+    }
+
+    #[test]
+    fn explicit_tags()
+    {
+        let quoted = Scalar::new("17".into(), ScalarStyle::SingleQuote);
+
+        let int = tagged("int");
+        assert_eq!(Core.resolve(&int, &quoted).unwrap(), Resolved::Int(17));
+
+        let float = tagged("float");
+        assert_eq!(
+            Core.resolve(&float, &plain("0x10")).unwrap(),
+            Resolved::Float(16.0)
+        );
+
+        let str = tagged("str");
+        assert_eq!(Core.resolve(&str, &plain("17")).unwrap(), Resolved::Str);
+
+        let local = ResolvedTag::Specific("!int".into());
+        assert_eq!(Core.resolve(&local, &plain("17")).unwrap(), Resolved::Str);
+
+        let other = ResolvedTag::Specific("tag:example.com,2000:int".into());
+        assert_eq!(Core.resolve(&other, &plain("17")).unwrap(), Resolved::Str);
+
+        let unknown = tagged("timestamp");
+        assert_eq!(Core.resolve(&unknown, &plain("17")).unwrap(), Resolved::Str);
+
+        let err = Core.resolve(&int, &plain("seventeen")).unwrap_err();
+        assert_eq!(err.classify(), Category::Data);
+    }
+
+    #[test]
+    fn int_overflow()
+    {
+        for s in &[
+            "18446744073709551616",
+            "-9223372036854775809",
+            "0x10000000000000000",
+        ]
+        {
+            let err = Core.resolve(&ResolvedTag::Question, &plain(s)).unwrap_err();
+
+            assert_eq!(err.classify(), Category::Data, "{:?}", s);
+        }
+
+        let err = Json
+            .resolve(&ResolvedTag::Question, &plain("-9223372036854775809"))
+            .unwrap_err();
+        assert_eq!(err.classify(), Category::Data);
+
+        assert_eq!(
+            Core.resolve(&ResolvedTag::Question, &plain("-9223372036854775808"))
+                .unwrap(),
+            Resolved::Int(i64::MIN)
+        );
+    }
+}
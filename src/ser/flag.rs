@@ -0,0 +1,124 @@
+/*
+ * This Source Code Form is subject to the terms of the
+ * Mozilla Public License, v. 2.0. If a copy of the MPL
+ * was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+//! Configuration flags used to control aspects of the
+//! Serializer's output.
+//!
+//! Read the documentation on each flag's `O_*` constant for
+//! an explanation of it's purpose.
+//!
+//! ```
+//! # use yary::ser::flag::*;
+//! // You can use bitwise operators
+//! let bitwise = O_NIL | O_FLOW | O_MARKERS;
+//!
+//! // Or if you prefer, method chaining
+//! let chaining = Flags::new().flow().markers();
+//!
+//! assert_eq!(bitwise, chaining);
+//! ```
+
+use bitflags::bitflags;
+
+use crate::emit;
+
+/// An empty, zeroed flag set. This is the default set,
+/// with all other flags disabled.
+pub const O_NIL: Flags = Flags::empty();
+
+/// Set to write collections in flow style.
+///
+/// By default, non empty collections are written in block
+/// style:
+///
+/// ```yaml
+/// key:
+///   - a
+///   - b
+/// ```
+///
+/// With this flag set, they will instead be written in
+/// flow style:
+///
+/// ```yaml
+/// {key: [a, b]}
+/// ```
+pub const O_FLOW: Flags = Flags::FLOW;
+
+/// Set to write explicit document markers.
+///
+/// By default, a document start marker (`---`) is only
+/// written when required to separate documents. With this
+/// flag set, every document will be started with `---`
+/// and terminated with `...`.
+pub const O_MARKERS: Flags = Flags::MARKERS;
+
+impl Flags
+{
+    /// Instantiates a new, empty flag set
+    pub const fn new() -> Self
+    {
+        O_NIL
+    }
+
+    /// Nulls the flag set, resetting it to empty
+    pub const fn nil(self) -> Self
+    {
+        O_NIL
+    }
+
+    /// Applies [`O_FLOW`] to this flag set
+    pub const fn flow(self) -> Self
+    {
+        self.union(O_FLOW)
+    }
+
+    /// Removes [`O_FLOW`] from this flag set
+    pub const fn no_flow(self) -> Self
+    {
+        self.difference(O_FLOW)
+    }
+
+    /// Applies [`O_MARKERS`] to this flag set
+    pub const fn markers(self) -> Self
+    {
+        self.union(O_MARKERS)
+    }
+
+    /// Removes [`O_MARKERS`] from this flag set
+    pub const fn no_markers(self) -> Self
+    {
+        self.difference(O_MARKERS)
+    }
+}
+
+/// Convert from Serializer flags to Emitter specific flags
+pub(crate) const fn as_emitter(f: Flags) -> emit::flag::Flags
+{
+    use emit::flag;
+
+    let mut flags = flag::O_NIL;
+
+    if f.contains(O_FLOW)
+    {
+        flags = flags.union(flag::O_FLOW);
+    }
+
+    flags
+}
+
+bitflags! {
+    /// Controls aspects of [Serializer] behaviors, read each flag for more information.
+    ///
+    /// [Serializer]: super::Serializer
+    #[derive(Default)]
+    pub struct Flags: u32 {
+        /// See [`O_FLOW`]
+        const FLOW          = 0b00000001;
+        /// See [`O_MARKERS`]
+        const MARKERS       = 0b00000010;
+    }
+}
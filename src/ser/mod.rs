@@ -0,0 +1,999 @@
+/*
+ * This Source Code Form is subject to the terms of the
+ * Mozilla Public License, v. 2.0. If a copy of the MPL
+ * was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+//! This module exposes a [`serde`] Serializer which writes
+//! YAML documents, available with the `serde` feature.
+//!
+//! Output is written in block style, with each nested
+//! collection indented by two spaces. Strings which would
+//! otherwise be read back as a null, boolean or number are
+//! quoted, as are any strings that cannot be represented as
+//! a plain scalar. Strings containing line breaks are
+//! written as literal block scalars.
+//!
+//! Enums are written as a plain scalar for unit variants,
+//! and as a single key mapping (`Variant: value`)
+//! otherwise, matching the representations accepted by
+//! [`de`](crate::de).
+//!
+//! See [`flag`] for the available output options.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use serde::Serialize;
+//! use yary::ser;
+//!
+//! #[derive(Serialize)]
+//! struct Config
+//! {
+//!     name:    &'static str,
+//!     version: &'static str,
+//!     tags:    Vec<&'static str>,
+//! }
+//!
+//! let config = Config {
+//!     name:    "web",
+//!     version: "1.0",
+//!     tags:    vec!["yes", "http"],
+//! };
+//!
+//! let yaml = ser::to_string(&config)?;
+//!
+//! assert_eq!(
+//!     yaml,
+//!     "name: web\nversion: \"1.0\"\ntags:\n  - \"yes\"\n  - http\n"
+//! );
+//! # Ok::<(), yary::Error>(())
+//! ```
+
+use std::{fmt::Debug, io::Write};
+
+use serde::ser::{self, Serialize};
+
+use crate::{
+    emit::Emitter,
+    error::{mkError, Error, Result},
+    event::types::{
+        Directives, DocumentEnd, DocumentStart, EventData, Mapping, Node, NodeKind, ResolvedTag,
+        ScalarLike, ScalarStyle, Sequence, Slice, StreamEncoding, StreamStart,
+    },
+    schema::{Core, Resolved, Schema},
+    ser::flag::{as_emitter, Flags, O_MARKERS},
+};
+
+pub mod flag;
+
+/// Serialize the given value as a YAML document, writing it
+/// to the given `writer`.
+///
+/// ## Examples
+///
+/// ```rust
+/// use yary::ser;
+///
+/// let mut output = Vec::new();
+/// ser::to_writer(&mut output, &vec![1, 2, 3])?;
+///
+/// assert_eq!(output, b"- 1\n- 2\n- 3\n");
+/// # Ok::<(), yary::Error>(())
+/// ```
+pub fn to_writer<W, T>(writer: W, value: &T) -> Result<()>
+where
+    W: Write,
+    T: ?Sized + Serialize,
+{
+    to_writer_with(writer, value, Flags::default())
+}
+
+/// Serialize the given value as a YAML document, writing it
+/// to the given `writer`, configured by the given flags.
+///
+/// See [`flag`] for the available options.
+///
+/// ## Examples
+///
+/// ```rust
+/// use std::collections::BTreeMap;
+///
+/// use yary::ser::{self, flag::O_FLOW};
+///
+/// let mut map = BTreeMap::new();
+/// map.insert("list", vec![1, 2]);
+///
+/// let mut output = Vec::new();
+/// ser::to_writer_with(&mut output, &map, O_FLOW)?;
+///
+/// assert_eq!(output, b"{list: [1, 2]}\n");
+/// # Ok::<(), yary::Error>(())
+/// ```
+pub fn to_writer_with<W, T>(writer: W, value: &T, f: Flags) -> Result<()>
+where
+    W: Write,
+    T: ?Sized + Serialize,
+{
+    let mut ser = Serializer::with_flags(writer, f);
+
+    value.serialize(&mut ser)?;
+    ser.into_inner()?;
+
+    Ok(())
+}
+
+/// Serialize the given value as a YAML document, returning
+/// it as a String.
+///
+/// See [`to_writer`] for more information.
+pub fn to_string<T>(value: &T) -> Result<String>
+where
+    T: ?Sized + Serialize,
+{
+    to_string_with(value, Flags::default())
+}
+
+/// Serialize the given value as a YAML document, returning
+/// it as a String, configured by the given flags.
+///
+/// See [`to_writer_with`] for more information.
+pub fn to_string_with<T>(value: &T, f: Flags) -> Result<String>
+where
+    T: ?Sized + Serialize,
+{
+    let mut output = Vec::new();
+
+    to_writer_with(&mut output, value, f)?;
+
+    String::from_utf8(output).map_err(|e| mkError!(e.utf8_error(), SOURCE))
+}
+
+/// A YAML Serializer, writing each value it is given as a
+/// separate document in a single YAML stream.
+///
+/// The stream is finished by calling
+/// [`into_inner`](Self::into_inner).
+///
+/// ## Examples
+///
+/// ```rust
+/// use serde::Serialize;
+/// use yary::ser::{flag::O_MARKERS, Serializer};
+///
+/// let mut ser = Serializer::with_flags(Vec::new(), O_MARKERS);
+///
+/// "first".serialize(&mut ser)?;
+/// ["second"].serialize(&mut ser)?;
+///
+/// let output = ser.into_inner()?;
+///
+/// assert_eq!(output, b"--- first\n...\n---\n- second\n...\n");
+/// # Ok::<(), yary::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct Serializer<W>
+{
+    emitter: Emitter<W>,
+    flags:   Flags,
+    levels:  Vec<Level>,
+    started: bool,
+}
+
+impl<W> Serializer<W>
+where
+    W: Write,
+{
+    /// Instantiate a new [`Serializer`] writing to the
+    /// given `writer`.
+    pub fn new(writer: W) -> Self
+    {
+        Self::with_flags(writer, Flags::default())
+    }
+
+    /// Instantiate a new [`Serializer`] writing to the
+    /// given `writer`, configured by the given flags.
+    pub fn with_flags(writer: W, flags: Flags) -> Self
+    {
+        Self {
+            emitter: Emitter::with_flags(writer, as_emitter(flags)),
+            flags,
+            levels: Vec::new(),
+            started: false,
+        }
+    }
+
+    /// Finish the YAML stream, returning the underlying
+    /// writer.
+    pub fn into_inner(mut self) -> Result<W>
+    {
+        self.stream_start()?;
+        self.emitter.emit(EventData::StreamEnd)?;
+
+        Ok(self.emitter.into_inner())
+    }
+
+    fn stream_start(&mut self) -> Result<()>
+    {
+        if !self.started
+        {
+            self.started = true;
+
+            self.emitter.emit(EventData::StreamStart(StreamStart {
+                encoding: StreamEncoding::UTF8,
+            }))?;
+        }
+
+        Ok(())
+    }
+
+    /// Start a new node, returning its kind and starting a
+    /// new document if required
+    fn node_start(&mut self) -> Result<NodeKind>
+    {
+        let kind = match self.levels.last_mut()
+        {
+            Some(Level::Sequence) => NodeKind::Entry,
+            Some(Level::Mapping { key }) =>
+            {
+                let kind = if *key { NodeKind::Key } else { NodeKind::Value };
+                *key = !*key;
+
+                kind
+            },
+            None =>
+            {
+                self.stream_start()?;
+                self.emitter.emit(EventData::DocumentStart(DocumentStart {
+                    directives: Directives::empty(),
+                    implicit:   !self.flags.contains(O_MARKERS),
+                }))?;
+
+                NodeKind::Root
+            },
+        };
+
+        Ok(kind)
+    }
+
+    /// Finish a node, finishing the document if it was the
+    /// root node
+    fn node_end(&mut self) -> Result<()>
+    {
+        if self.levels.is_empty()
+        {
+            self.emitter.emit(EventData::DocumentEnd(DocumentEnd {
+                implicit: !self.flags.contains(O_MARKERS),
+            }))?;
+        }
+
+        Ok(())
+    }
+
+    fn scalar(&mut self, data: &str, style: ScalarStyle) -> Result<()>
+    {
+        let kind = self.node_start()?;
+
+        self.emitter.emit(EventData::Scalar(Node {
+            anchor: None,
+            tag: None,
+            content: ScalarLike::eager(Slice::Borrowed(data), style),
+            kind,
+            resolved: match style
+            {
+                ScalarStyle::Plain => ResolvedTag::Question,
+                _ => ResolvedTag::Exclamation,
+            },
+        }))?;
+
+        self.node_end()
+    }
+
+    fn sequence_start(&mut self) -> Result<()>
+    {
+        let kind = self.node_start()?;
+
+        self.emitter.emit(EventData::SequenceStart(Node {
+            anchor: None,
+            tag: None,
+            content: Sequence,
+            kind,
+            resolved: ResolvedTag::Question,
+        }))?;
+        self.levels.push(Level::Sequence);
+
+        Ok(())
+    }
+
+    fn sequence_end(&mut self) -> Result<()>
+    {
+        self.levels.pop();
+        self.emitter.emit(EventData::SequenceEnd)?;
+
+        self.node_end()
+    }
+
+    fn mapping_start(&mut self) -> Result<()>
+    {
+        let kind = self.node_start()?;
+
+        self.emitter.emit(EventData::MappingStart(Node {
+            anchor: None,
+            tag: None,
+            content: Mapping,
+            kind,
+            resolved: ResolvedTag::Question,
+        }))?;
+        self.levels.push(Level::Mapping { key: true });
+
+        Ok(())
+    }
+
+    fn mapping_end(&mut self) -> Result<()>
+    {
+        self.levels.pop();
+        self.emitter.emit(EventData::MappingEnd)?;
+
+        self.node_end()
+    }
+
+    fn float<F>(&mut self, f: f64, repr: F) -> Result<()>
+    where
+        F: Debug,
+    {
+        let data = match f
+        {
+            f if f.is_nan() => ".nan".into(),
+            f if f.is_infinite() && f.is_sign_positive() => ".inf".into(),
+            f if f.is_infinite() => "-.inf".into(),
+            // Debug formatting always includes a '.' or
+            // exponent, so the scalar is never read back as an
+            // integer
+            _ => format!("{:?}", repr),
+        };
+
+        self.scalar(&data, ScalarStyle::Plain)
+    }
+}
+
+impl<'a, W> ser::Serializer for &'a mut Serializer<W>
+where
+    W: Write,
+{
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Variant<'a, W>;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Variant<'a, W>;
+
+    fn serialize_bool(self, v: bool) -> Result<()>
+    {
+        self.scalar(if v { "true" } else { "false" }, ScalarStyle::Plain)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<()>
+    {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<()>
+    {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<()>
+    {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<()>
+    {
+        self.scalar(&v.to_string(), ScalarStyle::Plain)
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<()>
+    {
+        self.scalar(&v.to_string(), ScalarStyle::Plain)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<()>
+    {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<()>
+    {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<()>
+    {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<()>
+    {
+        self.scalar(&v.to_string(), ScalarStyle::Plain)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<()>
+    {
+        self.scalar(&v.to_string(), ScalarStyle::Plain)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<()>
+    {
+        self.float(v.into(), v)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<()>
+    {
+        self.float(v, v)
+    }
+
+    fn serialize_char(self, v: char) -> Result<()>
+    {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<()>
+    {
+        self.scalar(v, string_style(v))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<()>
+    {
+        use ser::SerializeSeq;
+
+        let mut seq = self.serialize_seq(Some(v.len()))?;
+        for byte in v
+        {
+            seq.serialize_element(byte)?;
+        }
+
+        seq.end()
+    }
+
+    fn serialize_none(self) -> Result<()>
+    {
+        self.serialize_unit()
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<()>
+    {
+        self.scalar("null", ScalarStyle::Plain)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<()>
+    {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<()>
+    {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.mapping_start()?;
+        self.serialize_str(variant)?;
+        value.serialize(&mut *self)?;
+
+        self.mapping_end()
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq>
+    {
+        self.sequence_start()?;
+
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple>
+    {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct>
+    {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant>
+    {
+        self.mapping_start()?;
+        self.serialize_str(variant)?;
+        self.sequence_start()?;
+
+        Ok(Variant { ser: self })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap>
+    {
+        self.mapping_start()?;
+
+        Ok(self)
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeStruct>
+    {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant>
+    {
+        self.mapping_start()?;
+        self.serialize_str(variant)?;
+        self.mapping_start()?;
+
+        Ok(Variant { ser: self })
+    }
+}
+
+impl<W> ser::SerializeSeq for &mut Serializer<W>
+where
+    W: Write,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()>
+    {
+        self.sequence_end()
+    }
+}
+
+impl<W> ser::SerializeTuple for &mut Serializer<W>
+where
+    W: Write,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()>
+    {
+        self.sequence_end()
+    }
+}
+
+impl<W> ser::SerializeTupleStruct for &mut Serializer<W>
+where
+    W: Write,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()>
+    {
+        self.sequence_end()
+    }
+}
+
+impl<W> ser::SerializeMap for &mut Serializer<W>
+where
+    W: Write,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        key.serialize(&mut **self)
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()>
+    {
+        self.mapping_end()
+    }
+}
+
+impl<W> ser::SerializeStruct for &mut Serializer<W>
+where
+    W: Write,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::Serializer::serialize_str(&mut **self, key)?;
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()>
+    {
+        self.mapping_end()
+    }
+}
+
+/// Serializer for tuple and struct enum variants, which are
+/// wrapped in a single key mapping
+#[derive(Debug)]
+pub struct Variant<'a, W>
+{
+    ser: &'a mut Serializer<W>,
+}
+
+impl<'a, W> ser::SerializeTupleVariant for Variant<'a, W>
+where
+    W: Write,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(&mut *self.ser)
+    }
+
+    fn end(self) -> Result<()>
+    {
+        self.ser.sequence_end()?;
+        self.ser.mapping_end()
+    }
+}
+
+impl<'a, W> ser::SerializeStructVariant for Variant<'a, W>
+where
+    W: Write,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::Serializer::serialize_str(&mut *self.ser, key)?;
+        value.serialize(&mut *self.ser)
+    }
+
+    fn end(self) -> Result<()>
+    {
+        self.ser.mapping_end()?;
+        self.ser.mapping_end()
+    }
+}
+
+/// An open collection
+#[derive(Debug, Clone, Copy)]
+enum Level
+{
+    Sequence,
+    /// .key is set if the next node is a mapping key
+    Mapping
+    {
+        key: bool,
+    },
+}
+
+/// Pick the style a string should be written in
+fn string_style(s: &str) -> ScalarStyle
+{
+    if s.contains('\n')
+    {
+        ScalarStyle::Literal
+    }
+    else if is_ambiguous(s)
+    {
+        ScalarStyle::DoubleQuote
+    }
+    else
+    {
+        ScalarStyle::Plain
+    }
+}
+
+/// Would the given string be read back as something other
+/// than a string if written as a plain scalar?
+///
+/// This includes the YAML 1.1 booleans, as many parsers
+/// still follow the older schema.
+fn is_ambiguous(s: &str) -> bool
+{
+    let yaml11_bool = matches!(
+        s,
+        "y" | "Y"
+            | "yes"
+            | "Yes"
+            | "YES"
+            | "n"
+            | "N"
+            | "no"
+            | "No"
+            | "NO"
+            | "on"
+            | "On"
+            | "ON"
+            | "off"
+            | "Off"
+            | "OFF"
+    );
+
+    yaml11_bool || !matches!(Core.resolve_plain(s), Ok(Resolved::Str))
+}
+
+#[cfg(test)]
+mod tests
+{
+    use std::collections::BTreeMap;
+
+    use pretty_assertions::assert_eq;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::ser::flag::O_FLOW;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Config
+    {
+        name:    String,
+        port:    u16,
+        ratio:   f64,
+        enabled: bool,
+        tags:    Vec<String>,
+        limits:  BTreeMap<String, u32>,
+        parent:  Option<String>,
+    }
+
+    fn config() -> Config
+    {
+        let mut limits = BTreeMap::new();
+        limits.insert("cpu".into(), 2);
+        limits.insert("memory".into(), 512);
+
+        Config {
+            name: "web".into(),
+            port: 8080,
+            ratio: 1.0,
+            enabled: true,
+            tags: vec!["a".into(), "b".into()],
+            limits,
+            parent: None,
+        }
+    }
+
+    #[test]
+    fn block_output()
+    {
+        let expected = "name: web
+port: 8080
+ratio: 1.0
+enabled: true
+tags:
+  - a
+  - b
+limits:
+  cpu: 2
+  memory: 512
+parent: null
+";
+
+        assert_eq!(to_string(&config()).unwrap(), expected);
+    }
+
+    #[test]
+    fn flow_output()
+    {
+        let expected = "{name: web, port: 8080, ratio: 1.0, enabled: true, tags: [a, b], limits: \
+                        {cpu: 2, memory: 512}, parent: null}\n";
+
+        assert_eq!(to_string_with(&config(), O_FLOW).unwrap(), expected);
+    }
+
+    #[test]
+    fn document_markers()
+    {
+        assert_eq!(to_string_with(&1, O_MARKERS).unwrap(), "--- 1\n...\n");
+        assert_eq!(
+            to_string_with(&[1], O_MARKERS | O_FLOW).unwrap(),
+            "--- [1]\n...\n"
+        );
+    }
+
+    #[test]
+    fn round_trip()
+    {
+        let yaml = to_string(&config()).unwrap();
+
+        assert_eq!(crate::de::from_str::<Config>(&yaml).unwrap(), config());
+
+        let yaml = to_string_with(&config(), O_FLOW | O_MARKERS).unwrap();
+
+        assert_eq!(crate::de::from_str::<Config>(&yaml).unwrap(), config());
+    }
+
+    #[test]
+    fn ambiguous_strings()
+    {
+        let strings = vec![
+            "yes", "No", "on", "1.0", "~", "", "null", "true", "42", "0x1F", ".inf", "-1e3",
+        ];
+
+        let expected = "- \"yes\"\n- \"No\"\n- \"on\"\n- \"1.0\"\n- \"~\"\n- \"\"\n- \"null\"\n- \
+                        \"true\"\n- \"42\"\n- \"0x1F\"\n- \".inf\"\n- \"-1e3\"\n";
+
+        assert_eq!(to_string(&strings).unwrap(), expected);
+        assert_eq!(
+            crate::de::from_str::<Vec<String>>(expected).unwrap(),
+            strings
+        );
+    }
+
+    #[test]
+    fn special_strings()
+    {
+        let strings = vec!["a: b", "- x", "multi\nline\n", "#hash", "plain text"];
+        let yaml = to_string(&strings).unwrap();
+
+        assert_eq!(
+            yaml,
+            "- 'a: b'\n- '- x'\n- |\n  multi\n  line\n- '#hash'\n- plain text\n"
+        );
+        assert_eq!(crate::de::from_str::<Vec<String>>(&yaml).unwrap(), strings);
+    }
+
+    #[test]
+    fn floats()
+    {
+        let floats = vec![1.0, -0.5, 1e100, f64::INFINITY, f64::NEG_INFINITY];
+
+        assert_eq!(
+            to_string(&floats).unwrap(),
+            "- 1.0\n- -0.5\n- 1e100\n- .inf\n- -.inf\n"
+        );
+        assert_eq!(to_string(&f64::NAN).unwrap(), ".nan\n");
+        assert_eq!(to_string(&0.1f32).unwrap(), "0.1\n");
+        assert_eq!(
+            crate::de::from_str::<Vec<f64>>("- 1e100\n").unwrap(),
+            [1e100]
+        );
+    }
+
+    #[test]
+    fn enums()
+    {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        enum Shape
+        {
+            Empty,
+            Circle(f64),
+            Point(i32, i32),
+            Rect
+            {
+                w: u32,
+                h: u32,
+            },
+        }
+
+        let shapes = vec![
+            Shape::Empty,
+            Shape::Circle(1.5),
+            Shape::Point(1, 2),
+            Shape::Rect { w: 3, h: 4 },
+        ];
+
+        let yaml = to_string(&shapes).unwrap();
+
+        assert_eq!(
+            yaml,
+            "- Empty\n- Circle: 1.5\n- Point:\n    - 1\n    - 2\n- Rect:\n    w: 3\n    h: 4\n"
+        );
+        assert_eq!(crate::de::from_str::<Vec<Shape>>(&yaml).unwrap(), shapes);
+    }
+
+    #[test]
+    fn complex_keys()
+    {
+        let mut map = BTreeMap::new();
+        map.insert(vec![1, 2], "a");
+
+        assert_eq!(to_string(&map).unwrap(), "? - 1\n  - 2\n: a\n");
+        assert_eq!(to_string_with(&map, O_FLOW).unwrap(), "{? [1, 2] : a}\n");
+    }
+
+    #[test]
+    fn multiple_documents()
+    {
+        let mut ser = Serializer::new(Vec::new());
+
+        1.serialize(&mut ser).unwrap();
+        vec!["a"].serialize(&mut ser).unwrap();
+        "b".serialize(&mut ser).unwrap();
+
+        let output = ser.into_inner().unwrap();
+
+        assert_eq!(String::from_utf8(output).unwrap(), "1\n---\n- a\n--- b\n");
+    }
+
+    #[test]
+    fn empty_stream()
+    {
+        let output = Serializer::new(Vec::new()).into_inner().unwrap();
+
+        assert!(output.is_empty());
+    }
+}
@@ -4,7 +4,7 @@
  * was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-pub use crate::event::types::{ScalarStyle, Slice, StreamEncoding};
+pub use crate::event::types::{CommentPlacement, ScalarStyle, Slice, StreamEncoding};
 
 /// Tokens that may be emitted by a YAML scanner
 #[derive(Debug, PartialEq)]
@@ -53,6 +53,9 @@ pub enum Token<'a>
     Tag(Slice<'a>, Slice<'a>),
     /// A scalar (value, style)
     Scalar(Slice<'a>, ScalarStyle),
+    /// A comment (text, placement), only produced if
+    /// requested
+    Comment(Slice<'a>, CommentPlacement),
 }
 
 impl<'a> Token<'a>
@@ -91,6 +94,10 @@ impl<'a> Token<'a>
             {
                 Token::Scalar(Slice::Owned(contents.into_owned()), kind)
             },
+            Token::Comment(text, placement) =>
+            {
+                Token::Comment(Slice::Owned(text.into_owned()), placement)
+            },
         }
     }
 }
@@ -141,6 +148,8 @@ pub enum Marker
     Tag,
     /// A scalar (value, style)
     Scalar,
+    /// A comment (text, placement)
+    Comment,
 }
 
 impl Marker
@@ -172,6 +181,7 @@ impl Marker
             Anchor(_) => Self::Anchor,
             Tag(_, _) => Self::Tag,
             Scalar(_, _) => Self::Scalar,
+            Comment(_, _) => Self::Comment,
         }
     }
 }
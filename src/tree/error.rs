@@ -0,0 +1,107 @@
+/*
+ * This Source Code Form is subject to the terms of the
+ * Mozilla Public License, v. 2.0. If a copy of the MPL
+ * was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+//! This module contains the errors that may surface while
+//! constructing a YAML document tree.
+
+use std::{
+    error::Error as StdError,
+    fmt::{self, Debug},
+};
+
+use crate::{
+    error::internal::{ErrorCode, ErrorKind, SourceError},
+    scanner::error::ScanError,
+};
+
+/// Result type returned by [`yary::tree`](super)
+pub(crate) type TreeResult<T> = std::result::Result<T, TreeError>;
+
+/// Possible errors that can be encountered while building
+/// a YAML document tree.
+#[derive(Debug)]
+pub(crate) enum TreeError
+{
+    /// An alias referred to an anchor that has not been
+    /// defined in the current document, or whose node has
+    /// not yet been completed.
+    ///
+    /// ## Examples
+    ///
+    /// ```yaml
+    /// key: *missing
+    /// #    ^^^^^^^^ UndefinedAlias
+    /// ```
+    UndefinedAlias,
+
+    /// A node's tag had no registered constructor, and the
+    /// registry rejects unknown tags.
+    ///
+    /// ## Examples
+    ///
+    /// ```yaml
+    /// key: !unregistered value
+    /// #    ^^^^^^^^^^^^^ UnknownTag
+    /// ```
+    UnknownTag,
+
+    /// A registered tag constructor rejected its node.
+    Constructor(Box<dyn StdError + Send + Sync>),
+
+    /// A lazy scalar could not be evaluated.
+    Scanner(ScanError),
+}
+
+impl From<ScanError> for TreeError
+{
+    fn from(e: ScanError) -> Self
+    {
+        Self::Scanner(e)
+    }
+}
+
+impl fmt::Display for TreeError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        Debug::fmt(self, f)
+    }
+}
+
+impl StdError for TreeError
+{
+    fn source(&self) -> Option<&(dyn StdError + 'static)>
+    {
+        match self
+        {
+            Self::Scanner(e) => Some(e),
+            Self::Constructor(e) => Some(&**e),
+            _ => None,
+        }
+    }
+}
+
+impl From<TreeError> for ErrorKind
+{
+    fn from(err: TreeError) -> Self
+    {
+        match err
+        {
+            TreeError::UndefinedAlias => ErrorCode::UndefinedAlias.into(),
+            TreeError::UnknownTag => ErrorCode::UnknownTag.into(),
+            TreeError::Constructor(e) => SourceError::Constructor(e).into(),
+            TreeError::Scanner(e) => ErrorCode::from(e).into(),
+        }
+    }
+}
+
+impl From<TreeError> for crate::error::Error
+{
+    fn from(err: TreeError) -> Self
+    {
+        crate::error::mkError!(err, KIND)
+    }
+}
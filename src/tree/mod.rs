@@ -0,0 +1,1064 @@
+/*
+ * This Source Code Form is subject to the terms of the
+ * Mozilla Public License, v. 2.0. If a copy of the MPL
+ * was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+//! This module exposes an in-memory tree representation of
+//! YAML documents, built from an [`Events`] stream.
+//!
+//! Each document in the stream is represented by its root
+//! [`Node`], which is a cheaply cloneable handle to the
+//! node's properties and its [`Yaml`] value.
+//!
+//! Aliases are resolved while the tree is built: an alias
+//! produces a [`Node`] that _shares_ the anchored node it
+//! refers to, rather than copying it. You can check whether
+//! two nodes are shared with [`Node::ptr_eq`].
+//!
+//! Scalar content is borrowed from the underlying read
+//! source where possible, and nodes carry the source's
+//! `'de` lifetime.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use yary::{reader::borrow::BorrowReader, tree};
+//!
+//! let yaml = BorrowReader::new(
+//!     "
+//! defaults: &defaults
+//!   retries: 3
+//! services:
+//!   - name: web
+//!     config: *defaults
+//! ",
+//! );
+//!
+//! let docs = tree::from_reader(&yaml)?;
+//! let root = &docs[0];
+//!
+//! assert_eq!(root["services"][0]["name"].as_str(), Some("web"));
+//! assert_eq!(root["services"][0]["config"]["retries"].as_str(), Some("3"));
+//!
+//! // Aliases share the anchored node
+//! assert!(tree::Node::ptr_eq(
+//!     &root["defaults"],
+//!     &root["services"][0]["config"]
+//! ));
+//! # Ok::<(), yary::Error>(())
+//! ```
+//!
+//! [`Events`]: crate::event::Events
+
+use std::{any::Any, collections::HashMap, fmt, ops, rc::Rc};
+
+use crate::{
+    error::Result,
+    event::{
+        from_reader as events_from_reader,
+        types::{EventData, ResolvedTag, Scalar, ScalarStyle, Slice},
+    },
+    reader::Read,
+    tree::{
+        error::{TreeError as Error, TreeResult},
+        tag::{Action, Registry},
+    },
+};
+
+pub(crate) mod error;
+pub mod tag;
+
+/// Build a tree for every document in the given read
+/// source, returning each document's root [`Node`] in
+/// stream order.
+///
+/// Documents without any content are represented by an
+/// empty plain scalar.
+///
+/// ## Examples
+///
+/// ```rust
+/// use yary::{reader::borrow::BorrowReader, tree};
+///
+/// let yaml = BorrowReader::new("--- [a, b]\n--- {c: d}\n");
+/// let docs = tree::from_reader(&yaml)?;
+///
+/// assert_eq!(docs.len(), 2);
+/// assert_eq!(docs[0][1].as_str(), Some("b"));
+/// assert_eq!(docs[1]["c"].as_str(), Some("d"));
+/// # Ok::<(), yary::Error>(())
+/// ```
+pub fn from_reader<'de, R>(src: &'de R) -> Result<Vec<Node<'de>>>
+where
+    R: Read,
+{
+    from_events(events_from_reader(src))
+}
+
+/// Build a tree for every document in the given stream of
+/// events, returning each document's root [`Node`] in
+/// stream order.
+///
+/// See [`from_reader`] for more information.
+pub fn from_events<'de, I, E>(events: I) -> Result<Vec<Node<'de>>>
+where
+    I: IntoIterator<Item = Result<E>>,
+    E: Into<EventData<'de>>,
+{
+    build(Builder::new(), events)
+}
+
+/// Build a tree for every document in the given read
+/// source, passing tagged nodes through the constructors of
+/// the given [`Registry`].
+///
+/// See the [`tag`] module for more information.
+pub fn from_reader_with<'de, R>(src: &'de R, registry: &Registry) -> Result<Vec<Node<'de>>>
+where
+    R: Read,
+{
+    from_events_with(events_from_reader(src), registry)
+}
+
+/// Build a tree for every document in the given stream of
+/// events, passing tagged nodes through the constructors of
+/// the given [`Registry`].
+///
+/// See the [`tag`] module for more information.
+pub fn from_events_with<'de, I, E>(events: I, registry: &Registry) -> Result<Vec<Node<'de>>>
+where
+    I: IntoIterator<Item = Result<E>>,
+    E: Into<EventData<'de>>,
+{
+    build(Builder::with_registry(registry.clone()), events)
+}
+
+fn build<'de, I, E>(mut builder: Builder<'de>, events: I) -> Result<Vec<Node<'de>>>
+where
+    I: IntoIterator<Item = Result<E>>,
+    E: Into<EventData<'de>>,
+{
+    let mut documents = Vec::new();
+
+    for event in events
+    {
+        if let Some(root) = builder.process(event?.into())?
+        {
+            documents.push(root);
+        }
+    }
+
+    Ok(documents)
+}
+
+/// A node in a YAML document tree.
+///
+/// This is a reference counted handle, so cloning it is
+/// cheap, and any clones will refer to the same underlying
+/// node. A [`Node`] dereferences to its [`Yaml`] value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node<'de>
+{
+    inner: Rc<NodeData<'de>>,
+}
+
+impl<'de> Node<'de>
+{
+    /// Instantiate a new [`Node`] from the given value and
+    /// properties.
+    pub fn new(value: Yaml<'de>, anchor: Option<Slice<'de>>, tag: Option<Tag<'de>>) -> Self
+    {
+        let inner = Rc::new(NodeData { anchor, tag, value });
+
+        Self { inner }
+    }
+
+    /// The anchor applied to this node, if any
+    pub fn anchor(&self) -> Option<&Slice<'de>>
+    {
+        self.inner.anchor.as_ref()
+    }
+
+    /// The tag applied to this node, if any, as a (handle,
+    /// suffix) pair
+    pub fn tag(&self) -> Option<&Tag<'de>>
+    {
+        self.inner.tag.as_ref()
+    }
+
+    /// This node's value
+    pub fn value(&self) -> &Yaml<'de>
+    {
+        &self.inner.value
+    }
+
+    /// Check if the two nodes are the _same_ node, e.g if
+    /// one is an alias of the other.
+    ///
+    /// Note that this is distinct from equality, which
+    /// compares nodes structurally.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool
+    {
+        Rc::ptr_eq(&this.inner, &other.inner)
+    }
+}
+
+impl<'de> ops::Deref for Node<'de>
+{
+    type Target = Yaml<'de>;
+
+    fn deref(&self) -> &Self::Target
+    {
+        self.value()
+    }
+}
+
+impl<'de, I> ops::Index<I> for Node<'de>
+where
+    I: Index,
+{
+    type Output = Node<'de>;
+
+    fn index(&self, index: I) -> &Self::Output
+    {
+        self.value().index(index)
+    }
+}
+
+/// A tag, as a (handle, suffix) pair
+pub type Tag<'de> = (Slice<'de>, Slice<'de>);
+
+/// The value of a YAML [`Node`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Yaml<'de>
+{
+    /// A scalar leaf value
+    Scalar(Scalar<'de>),
+    /// An ordered sequence of nodes
+    Sequence(Sequence<'de>),
+    /// A mapping of nodes, in insertion order
+    Mapping(Mapping<'de>),
+    /// A value produced by a tag constructor
+    Custom(Custom),
+}
+
+impl<'de> Yaml<'de>
+{
+    /// Returns the [`Scalar`] if this is a scalar value
+    pub fn as_scalar(&self) -> Option<&Scalar<'de>>
+    {
+        match self
+        {
+            Self::Scalar(scalar) => Some(scalar),
+            _ => None,
+        }
+    }
+
+    /// Returns the scalar's content if this is a scalar
+    /// value
+    pub fn as_str(&self) -> Option<&str>
+    {
+        self.as_scalar().map(|scalar| scalar.as_ref())
+    }
+
+    /// Returns the [`Sequence`] if this is a sequence value
+    pub fn as_sequence(&self) -> Option<&Sequence<'de>>
+    {
+        match self
+        {
+            Self::Sequence(seq) => Some(seq),
+            _ => None,
+        }
+    }
+
+    /// Returns the [`Mapping`] if this is a mapping value
+    pub fn as_mapping(&self) -> Option<&Mapping<'de>>
+    {
+        match self
+        {
+            Self::Mapping(map) => Some(map),
+            _ => None,
+        }
+    }
+
+    /// Returns the [`Custom`] value if this is a custom
+    /// value of type `T`
+    pub fn as_custom<T>(&self) -> Option<&T>
+    where
+        T: Any,
+    {
+        match self
+        {
+            Self::Custom(custom) => custom.downcast_ref(),
+            _ => None,
+        }
+    }
+
+    /// Is this a scalar value?
+    pub fn is_scalar(&self) -> bool
+    {
+        matches!(self, Self::Scalar(_))
+    }
+
+    /// Is this a sequence value?
+    pub fn is_sequence(&self) -> bool
+    {
+        matches!(self, Self::Sequence(_))
+    }
+
+    /// Is this a mapping value?
+    pub fn is_mapping(&self) -> bool
+    {
+        matches!(self, Self::Mapping(_))
+    }
+
+    /// Is this a custom value?
+    pub fn is_custom(&self) -> bool
+    {
+        matches!(self, Self::Custom(_))
+    }
+
+    /// Retrieve a child node, either by position in a
+    /// sequence or by key in a mapping.
+    ///
+    /// Returns `None` if no child exists for the given
+    /// index, or this value is not of the correct type.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use yary::{reader::borrow::BorrowReader, tree};
+    ///
+    /// let yaml = BorrowReader::new("{a: [1, 2]}");
+    /// let docs = tree::from_reader(&yaml)?;
+    ///
+    /// assert!(docs[0].get("a").and_then(|a| a.get(1)).is_some());
+    /// assert!(docs[0].get("b").is_none());
+    /// assert!(docs[0].get(0).is_none());
+    /// # Ok::<(), yary::Error>(())
+    /// ```
+    pub fn get<I>(&self, index: I) -> Option<&Node<'de>>
+    where
+        I: Index,
+    {
+        index.index_into(self)
+    }
+}
+
+impl<'de, I> ops::Index<I> for Yaml<'de>
+where
+    I: Index,
+{
+    type Output = Node<'de>;
+
+    /// ## Panics
+    ///
+    /// If no child exists for the given index, see
+    /// [`Yaml::get`] for a non panicking alternative.
+    fn index(&self, index: I) -> &Self::Output
+    {
+        match index.index_into(self)
+        {
+            Some(node) => node,
+            None => panic!("no child node at the given index: {}", index.describe()),
+        }
+    }
+}
+
+/// A YAML sequence
+pub type Sequence<'de> = Vec<Node<'de>>;
+
+/// A YAML mapping, which preserves the order its entries
+/// were inserted in.
+///
+/// Keys may be any [`Node`], though lookups by `&str` will
+/// only ever match scalar keys.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Mapping<'de>
+{
+    entries: Vec<(Node<'de>, Node<'de>)>,
+}
+
+impl<'de> Mapping<'de>
+{
+    /// Instantiate a new, empty [`Mapping`]
+    pub fn new() -> Self
+    {
+        Self::default()
+    }
+
+    /// Append a new entry to the end of the mapping
+    pub fn insert(&mut self, key: Node<'de>, value: Node<'de>)
+    {
+        self.entries.push((key, value))
+    }
+
+    /// Retrieve the value of the first entry whose key is a
+    /// scalar matching .key
+    pub fn get(&self, key: &str) -> Option<&Node<'de>>
+    {
+        self.entries
+            .iter()
+            .find(|(k, _)| k.as_str() == Some(key))
+            .map(|(_, v)| v)
+    }
+
+    /// Check if any scalar key matches .key
+    pub fn contains_key(&self, key: &str) -> bool
+    {
+        self.get(key).is_some()
+    }
+
+    /// The number of entries in the mapping
+    pub fn len(&self) -> usize
+    {
+        self.entries.len()
+    }
+
+    /// Is the mapping empty?
+    pub fn is_empty(&self) -> bool
+    {
+        self.entries.is_empty()
+    }
+
+    /// Iterate over the mapping's entries, in insertion
+    /// order
+    pub fn iter(&self) -> impl Iterator<Item = (&Node<'de>, &Node<'de>)>
+    {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+
+    /// Iterate over the mapping's keys, in insertion order
+    pub fn keys(&self) -> impl Iterator<Item = &Node<'de>>
+    {
+        self.entries.iter().map(|(k, _)| k)
+    }
+
+    /// Iterate over the mapping's values, in insertion
+    /// order
+    pub fn values(&self) -> impl Iterator<Item = &Node<'de>>
+    {
+        self.entries.iter().map(|(_, v)| v)
+    }
+}
+
+impl<'de> IntoIterator for Mapping<'de>
+{
+    type Item = (Node<'de>, Node<'de>);
+
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter
+    {
+        self.entries.into_iter()
+    }
+}
+
+/// An arbitrary value, typically produced by a
+/// [tag constructor](tag).
+///
+/// Like [`Node`] this is a reference counted handle, and
+/// two custom values are only equal if they are the _same_
+/// value.
+#[derive(Clone)]
+pub struct Custom
+{
+    value: Rc<dyn Any>,
+}
+
+impl Custom
+{
+    /// Instantiate a new [`Custom`] wrapping .value
+    pub fn new<T>(value: T) -> Self
+    where
+        T: Any,
+    {
+        Self {
+            value: Rc::new(value),
+        }
+    }
+
+    /// Check if the wrapped value is of type `T`
+    pub fn is<T>(&self) -> bool
+    where
+        T: Any,
+    {
+        self.value.is::<T>()
+    }
+
+    /// Returns the wrapped value if it is of type `T`
+    pub fn downcast_ref<T>(&self) -> Option<&T>
+    where
+        T: Any,
+    {
+        self.value.downcast_ref()
+    }
+}
+
+impl fmt::Debug for Custom
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        f.write_str("Custom(..)")
+    }
+}
+
+impl PartialEq for Custom
+{
+    fn eq(&self, other: &Self) -> bool
+    {
+        Rc::as_ptr(&self.value) as *const u8 == Rc::as_ptr(&other.value) as *const u8
+    }
+}
+
+/// A type that can be used to retrieve a child [`Node`].
+///
+/// Implemented for `usize` (sequence position) and `str`
+/// (mapping key). This trait is sealed, and cannot be
+/// implemented outside of this library.
+pub trait Index: private::Sealed
+{
+    #[doc(hidden)]
+    fn index_into<'a, 'de>(&self, value: &'a Yaml<'de>) -> Option<&'a Node<'de>>;
+
+    #[doc(hidden)]
+    fn describe(&self) -> String;
+}
+
+impl Index for usize
+{
+    fn index_into<'a, 'de>(&self, value: &'a Yaml<'de>) -> Option<&'a Node<'de>>
+    {
+        value.as_sequence().and_then(|seq| seq.get(*self))
+    }
+
+    fn describe(&self) -> String
+    {
+        self.to_string()
+    }
+}
+
+impl Index for str
+{
+    fn index_into<'a, 'de>(&self, value: &'a Yaml<'de>) -> Option<&'a Node<'de>>
+    {
+        value.as_mapping().and_then(|map| map.get(self))
+    }
+
+    fn describe(&self) -> String
+    {
+        format!("{:?}", self)
+    }
+}
+
+impl Index for String
+{
+    fn index_into<'a, 'de>(&self, value: &'a Yaml<'de>) -> Option<&'a Node<'de>>
+    {
+        self.as_str().index_into(value)
+    }
+
+    fn describe(&self) -> String
+    {
+        self.as_str().describe()
+    }
+}
+
+impl<T> Index for &T
+where
+    T: ?Sized + Index,
+{
+    fn index_into<'a, 'de>(&self, value: &'a Yaml<'de>) -> Option<&'a Node<'de>>
+    {
+        (**self).index_into(value)
+    }
+
+    fn describe(&self) -> String
+    {
+        (**self).describe()
+    }
+}
+
+mod private
+{
+    pub trait Sealed {}
+
+    impl Sealed for usize {}
+    impl Sealed for str {}
+    impl Sealed for String {}
+    impl<T> Sealed for &T where T: ?Sized + Sealed {}
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct NodeData<'de>
+{
+    anchor: Option<Slice<'de>>,
+    tag:    Option<Tag<'de>>,
+    value:  Yaml<'de>,
+}
+
+/// Incremental document tree builder, which consumes events
+/// one at a time, returning each document's root once its
+/// DocumentEnd is seen.
+#[derive(Debug, Default)]
+pub(crate) struct Builder<'de>
+{
+    stack:    Vec<Partial<'de>>,
+    anchors:  HashMap<Slice<'de>, Node<'de>>,
+    root:     Option<Node<'de>>,
+    registry: Option<Registry>,
+}
+
+impl<'de> Builder<'de>
+{
+    pub fn new() -> Self
+    {
+        Self::default()
+    }
+
+    /// Instantiate a builder that passes tagged nodes
+    /// through the given .registry
+    pub fn with_registry(registry: Registry) -> Self
+    {
+        Self {
+            registry: Some(registry),
+            ..Self::default()
+        }
+    }
+
+    /// Take the most recently completed root node, if no
+    /// collection remains open.
+    ///
+    /// Useful for building a single node, rather than an
+    /// entire document.
+    #[cfg_attr(not(feature = "serde"), allow(dead_code))]
+    pub fn take_node(&mut self) -> Option<Node<'de>>
+    {
+        match self.stack.is_empty()
+        {
+            true => self.root.take(),
+            false => None,
+        }
+    }
+
+    /// Finish the current document, returning its root
+    /// node, or an empty plain scalar if it had no
+    /// content.
+    pub fn end_document(&mut self) -> Node<'de>
+    {
+        self.anchors.clear();
+
+        self.root.take().unwrap_or_else(|| {
+            Node::new(
+                Yaml::Scalar(Scalar::new(Slice::from(""), ScalarStyle::Plain)),
+                None,
+                None,
+            )
+        })
+    }
+
+    /// Process the given .event, returning a document's
+    /// root node if the event completed a document.
+    pub fn process(&mut self, event: EventData<'de>) -> TreeResult<Option<Node<'de>>>
+    {
+        match event
+        {
+            EventData::StreamStart(_) | EventData::StreamEnd | EventData::Comment(_) =>
+            {},
+            EventData::DocumentStart(_) =>
+            {
+                self.anchors.clear();
+                self.root = None;
+            },
+            EventData::DocumentEnd(_) => return Ok(Some(self.end_document())),
+            EventData::Alias(alias) =>
+            {
+                let node = self
+                    .anchors
+                    .get(&alias.name)
+                    .cloned()
+                    .ok_or(Error::UndefinedAlias)?;
+
+                self.complete(node, false);
+            },
+            EventData::Scalar(node) =>
+            {
+                let scalar = node.content.evaluate_scalar()?;
+                let node =
+                    self.construct(Yaml::Scalar(scalar), node.anchor, node.tag, &node.resolved)?;
+
+                self.complete(node, true);
+            },
+            EventData::SequenceStart(node) => self.stack.push(Partial {
+                anchor:   node.anchor,
+                tag:      node.tag,
+                resolved: node.resolved,
+                kind:     PartialKind::Sequence(Sequence::new()),
+            }),
+            EventData::MappingStart(node) => self.stack.push(Partial {
+                anchor:   node.anchor,
+                tag:      node.tag,
+                resolved: node.resolved,
+                kind:     PartialKind::Mapping(Mapping::new(), None),
+            }),
+            EventData::SequenceEnd | EventData::MappingEnd =>
+            {
+                if let Some(partial) = self.stack.pop()
+                {
+                    let value = match partial.kind
+                    {
+                        PartialKind::Sequence(seq) => Yaml::Sequence(seq),
+                        PartialKind::Mapping(map, _) => Yaml::Mapping(map),
+                    };
+
+                    let node =
+                        self.construct(value, partial.anchor, partial.tag, &partial.resolved)?;
+
+                    self.complete(node, true);
+                }
+            },
+        }
+
+        Ok(None)
+    }
+
+    /// Create a node from the given .value and properties,
+    /// applying the registry's action for its .resolved tag
+    fn construct(
+        &self,
+        value: Yaml<'de>,
+        anchor: Option<Slice<'de>>,
+        tag: Option<Tag<'de>>,
+        resolved: &ResolvedTag<'de>,
+    ) -> TreeResult<Node<'de>>
+    {
+        let action = match self.registry
+        {
+            Some(ref registry) => registry.action(resolved),
+            None => Action::Keep,
+        };
+
+        match action
+        {
+            Action::Keep => Ok(Node::new(value, anchor, tag)),
+            Action::Strip => Ok(Node::new(value, anchor, None)),
+            Action::Reject => Err(Error::UnknownTag),
+            Action::Construct(constructor) =>
+            {
+                let node = Node::new(value, anchor.clone(), tag.clone());
+                let value = constructor(&node).map_err(Error::Constructor)?;
+
+                Ok(Node::new(value, anchor, tag))
+            },
+        }
+    }
+
+    /// Add a finished node to its parent, registering its
+    /// anchor if .register is set
+    fn complete(&mut self, node: Node<'de>, register: bool)
+    {
+        if let Some(anchor) = node.anchor().filter(|_| register)
+        {
+            self.anchors.insert(anchor.clone(), node.clone());
+        }
+
+        match self.stack.last_mut().map(|partial| &mut partial.kind)
+        {
+            Some(PartialKind::Sequence(seq)) => seq.push(node),
+            Some(PartialKind::Mapping(map, key)) => match key.take()
+            {
+                Some(key) => map.insert(key, node),
+                None => *key = Some(node),
+            },
+            None => self.root = Some(node),
+        }
+    }
+}
+
+/// A collection whose end has not yet been seen
+#[derive(Debug)]
+struct Partial<'de>
+{
+    anchor:   Option<Slice<'de>>,
+    tag:      Option<Tag<'de>>,
+    resolved: ResolvedTag<'de>,
+    kind:     PartialKind<'de>,
+}
+
+#[derive(Debug)]
+enum PartialKind<'de>
+{
+    Sequence(Sequence<'de>),
+    /// A mapping, and its pending key (if any)
+    Mapping(Mapping<'de>, Option<Node<'de>>),
+}
+
+#[cfg(test)]
+mod tests
+{
+    use std::borrow::Cow;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        error::{Category, ErrorKind},
+        reader::borrow::BorrowReader,
+    };
+
+    type TestResult = anyhow::Result<()>;
+
+    fn build_err(yaml: &str) -> Category
+    {
+        let reader = BorrowReader::new(yaml);
+
+        from_reader(&reader)
+            .expect_err("an invalid YAML stream")
+            .classify()
+    }
+
+    #[test]
+    fn scalar_root() -> TestResult
+    {
+        let reader = BorrowReader::new("'a scalar'");
+        let docs = from_reader(&reader)?;
+
+        assert_eq!(docs.len(), 1);
+        assert_eq!(docs[0].as_str(), Some("a scalar"));
+        assert_eq!(
+            docs[0].as_scalar().map(Scalar::style),
+            Some(ScalarStyle::SingleQuote)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn sequence_index() -> TestResult
+    {
+        let reader = BorrowReader::new("- a\n- [b, c]\n- {d: e}");
+        let docs = from_reader(&reader)?;
+        let root = &docs[0];
+
+        assert_eq!(root.as_sequence().map(Vec::len), Some(3));
+        assert_eq!(root[0].as_str(), Some("a"));
+        assert_eq!(root[1][1].as_str(), Some("c"));
+        assert_eq!(root[2]["d"].as_str(), Some("e"));
+        assert!(root.get(3).is_none());
+        assert!(root.get("a").is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn mapping_insertion_order() -> TestResult
+    {
+        let reader = BorrowReader::new("z: 1\na: 2\nm: 3\n");
+        let docs = from_reader(&reader)?;
+        let map = docs[0].as_mapping().expect("a mapping");
+
+        let keys: Vec<_> = map.keys().filter_map(|k| k.as_str()).collect();
+        let values: Vec<_> = map.values().filter_map(|v| v.as_str()).collect();
+
+        assert_eq!(keys, vec!["z", "a", "m"]);
+        assert_eq!(values, vec!["1", "2", "3"]);
+        assert!(map.contains_key("m"));
+        assert!(!map.contains_key("b"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn complex_keys() -> TestResult
+    {
+        let reader = BorrowReader::new("? [a, b]\n: value\nkey: other\n");
+        let docs = from_reader(&reader)?;
+        let map = docs[0].as_mapping().expect("a mapping");
+
+        let (key, value) = map.iter().next().expect("an entry");
+
+        assert!(key.is_sequence());
+        assert_eq!(value.as_str(), Some("value"));
+        assert_eq!(docs[0]["key"].as_str(), Some("other"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn alias_shared() -> TestResult
+    {
+        let reader = BorrowReader::new("a: &anchor [1, 2]\nb: *anchor\nc: [1, 2]\n");
+        let docs = from_reader(&reader)?;
+        let root = &docs[0];
+
+        assert!(Node::ptr_eq(&root["a"], &root["b"]));
+        assert!(!Node::ptr_eq(&root["a"], &root["c"]));
+        assert_eq!(root["a"].value(), root["c"].value());
+        assert_eq!(root["b"].anchor().map(AsRef::as_ref), Some("anchor"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn alias_redefined() -> TestResult
+    {
+        let reader = BorrowReader::new("- &a first\n- *a\n- &a second\n- *a\n");
+        let docs = from_reader(&reader)?;
+        let root = &docs[0];
+
+        assert_eq!(root[1].as_str(), Some("first"));
+        assert_eq!(root[3].as_str(), Some("second"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn alias_undefined()
+    {
+        assert_eq!(build_err("a: *missing\n"), Category::Data);
+    }
+
+    #[test]
+    fn alias_scoped_to_document()
+    {
+        assert_eq!(build_err("--- &a value\n--- *a\n"), Category::Data);
+    }
+
+    #[test]
+    fn tags() -> TestResult
+    {
+        let reader = BorrowReader::new("!!map {a: !local b}");
+        let docs = from_reader(&reader)?;
+        let root = &docs[0];
+
+        assert_eq!(root.tag(), Some(&(Cow::from("!!"), Cow::from("map"))));
+        assert_eq!(root["a"].tag(), Some(&(Cow::from("!"), Cow::from("local"))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn multiple_documents() -> TestResult
+    {
+        let reader = BorrowReader::new("--- a\n---\n--- [b]\n");
+        let docs = from_reader(&reader)?;
+
+        assert_eq!(docs.len(), 3);
+        assert_eq!(docs[0].as_str(), Some("a"));
+        assert_eq!(docs[1].as_str(), Some(""));
+        assert_eq!(docs[2][0].as_str(), Some("b"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn borrowed_scalars() -> TestResult
+    {
+        let yaml = String::from("key: value");
+        let reader = BorrowReader::new(&yaml);
+        let docs = from_reader(&reader)?;
+
+        let value = docs[0]["key"].as_scalar().expect("a scalar");
+        assert!(matches!(value.data(), Cow::Borrowed(_)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn tag_constructors() -> TestResult
+    {
+        let mut registry = tag::Registry::new();
+        registry
+            .insert("!secret", |node| {
+                let name = node.as_str().ok_or("expected a secret name")?;
+
+                Ok(Yaml::Scalar(Scalar::new(
+                    format!("<{}>", name).into(),
+                    ScalarStyle::Plain,
+                )))
+            })
+            .insert("tag:example.com:2000:pair", |node| {
+                let seq = node.as_sequence().ok_or("expected a sequence")?;
+                let pair = (seq.len(), seq[0].is_custom());
+
+                Ok(Yaml::Custom(Custom::new(pair)))
+            });
+
+        let reader = BorrowReader::new(
+            "%TAG !e! tag:example.com:2000:
+---
+a: !secret db
+b: !e!pair [!secret x, y]
+",
+        );
+        let docs = from_reader_with(&reader, &registry)?;
+        let root = &docs[0];
+
+        assert_eq!(root["a"].as_str(), Some("<db>"));
+        assert_eq!(
+            root["a"].tag(),
+            Some(&(Cow::from("!"), Cow::from("secret")))
+        );
+        assert_eq!(root["b"].as_custom::<(usize, bool)>(), Some(&(2, false)));
+        assert_eq!(root["b"].as_custom::<usize>(), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn tag_unknown_policy() -> TestResult
+    {
+        let yaml = "a: !unknown {b: !!str c}
+";
+        let reader = BorrowReader::new(yaml);
+        let mut registry = tag::Registry::new();
+
+        let docs = from_reader_with(&reader, &registry)?;
+        assert_eq!(
+            docs[0]["a"].tag(),
+            Some(&(Cow::from("!"), Cow::from("unknown")))
+        );
+
+        registry.set_unknown(tag::Unknown::Strip);
+        let docs = from_reader_with(&reader, &registry)?;
+        assert_eq!(docs[0]["a"].tag(), None);
+        assert_eq!(
+            docs[0]["a"]["b"].tag(),
+            Some(&(Cow::from("!!"), Cow::from("str")))
+        );
+
+        registry.set_unknown(tag::Unknown::Error);
+        let err = from_reader_with(&reader, &registry).expect_err("an unknown tag");
+        assert_eq!(err.kind(), ErrorKind::UnknownTag);
+
+        Ok(())
+    }
+
+    #[test]
+    fn tag_constructor_error()
+    {
+        let mut registry = tag::Registry::new();
+        registry.insert("!int", |node| {
+            let n: i64 = node.as_str().unwrap_or_default().parse()?;
+
+            Ok(Yaml::Custom(Custom::new(n)))
+        });
+
+        let reader = BorrowReader::new("!int nope");
+        let err = from_reader_with(&reader, &registry).expect_err("a constructor error");
+
+        assert_eq!(err.kind(), ErrorKind::Constructor);
+        assert_eq!(err.classify(), Category::Data);
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_missing_panics()
+    {
+        let reader = BorrowReader::new("{a: b}");
+        let docs = from_reader(&reader).unwrap();
+
+        let _ = &docs[0]["c"];
+    }
+}
@@ -0,0 +1,283 @@
+/*
+ * This Source Code Form is subject to the terms of the
+ * Mozilla Public License, v. 2.0. If a copy of the MPL
+ * was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+//! This module exposes a [`Registry`] of tag constructors,
+//! which give application specific tags (e.g `!Ref` or
+//! `!duration`) their meaning.
+//!
+//! A constructor is called with the materialized [`Node`]
+//! of each node carrying its tag, after any of the node's
+//! children have themselves been constructed, and returns
+//! the [`Yaml`] value that replaces it. Constructors that
+//! produce a Rust type rather than YAML data can wrap it in
+//! a [`Custom`] value.
+//!
+//! Tags are matched by their resolved URI, that is with any
+//! `%TAG` prefix applied: `!Ref` for a local tag, and
+//! `tag:yaml.org,2002:str` for `!!str`. Tags in the
+//! `tag:yaml.org,2002:` namespace belong to the YAML
+//! schema, and are never considered unknown. What happens
+//! to any other tag without a constructor is decided by the
+//! registry's [`Unknown`] policy.
+//!
+//! A registry is used by [`tree::from_reader_with`], and by
+//! the `serde` Deserializer via
+//! `de::StreamDeserializer::with_registry`.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use std::time::Duration;
+//!
+//! use yary::{
+//!     reader::borrow::BorrowReader,
+//!     tree::{self, tag::Registry, Custom, Yaml},
+//! };
+//!
+//! let mut registry = Registry::new();
+//! registry.insert("!duration", |node| {
+//!     let secs = node
+//!         .as_str()
+//!         .unwrap_or_default()
+//!         .trim_end_matches('s')
+//!         .parse()?;
+//!
+//!     Ok(Yaml::Custom(Custom::new(Duration::from_secs(secs))))
+//! });
+//!
+//! let yaml = BorrowReader::new("timeout: !duration 30s\n");
+//! let docs = tree::from_reader_with(&yaml, &registry)?;
+//!
+//! assert_eq!(
+//!     docs[0]["timeout"].as_custom::<Duration>(),
+//!     Some(&Duration::from_secs(30))
+//! );
+//! # Ok::<(), yary::Error>(())
+//! ```
+//!
+//! [`tree::from_reader_with`]: super::from_reader_with
+//! [`Custom`]: super::Custom
+
+use std::{collections::HashMap, error::Error as StdError, fmt, rc::Rc};
+
+use crate::{
+    event::types::ResolvedTag,
+    tree::{Node, Yaml},
+};
+
+/// The URI prefix of tags defined by the YAML schemas
+const SCHEMA_PREFIX: &str = "tag:yaml.org,2002:";
+
+/// Result type returned by tag constructors
+pub type ConstructResult = std::result::Result<Yaml<'static>, Box<dyn StdError + Send + Sync>>;
+
+/// A set of tag constructors, and the policy for handling
+/// tags without one.
+///
+/// Cloning a registry is cheap, constructors are shared
+/// between clones.
+#[derive(Clone, Default)]
+pub struct Registry
+{
+    constructors: HashMap<String, Constructor>,
+    unknown:      Unknown,
+}
+
+impl Registry
+{
+    /// Instantiate a new, empty [`Registry`], which keeps
+    /// any unknown tags
+    pub fn new() -> Self
+    {
+        Self::default()
+    }
+
+    /// Register .constructor for nodes whose resolved tag
+    /// is .tag, replacing any existing constructor for the
+    /// tag
+    pub fn insert<T, F>(&mut self, tag: T, constructor: F) -> &mut Self
+    where
+        T: Into<String>,
+        F: Fn(&Node<'_>) -> ConstructResult + 'static,
+    {
+        self.constructors.insert(tag.into(), Rc::new(constructor));
+
+        self
+    }
+
+    /// Remove the constructor for .tag, returning whether
+    /// one was registered
+    pub fn remove(&mut self, tag: &str) -> bool
+    {
+        self.constructors.remove(tag).is_some()
+    }
+
+    /// Check if a constructor is registered for .tag
+    pub fn contains(&self, tag: &str) -> bool
+    {
+        self.constructors.contains_key(tag)
+    }
+
+    /// Retrieve the policy for unknown tags
+    pub fn unknown(&self) -> Unknown
+    {
+        self.unknown
+    }
+
+    /// Set the policy for unknown tags
+    pub fn set_unknown(&mut self, policy: Unknown) -> &mut Self
+    {
+        self.unknown = policy;
+
+        self
+    }
+
+    /// Decide what should happen to a node with the given
+    /// resolved .tag
+    pub(crate) fn action(&self, tag: &ResolvedTag<'_>) -> Action<'_>
+    {
+        let uri = match tag
+        {
+            ResolvedTag::Specific(uri) => uri,
+            _ => return Action::Keep,
+        };
+
+        if let Some(constructor) = self.constructors.get(uri.as_ref())
+        {
+            return Action::Construct(constructor);
+        }
+
+        if uri.starts_with(SCHEMA_PREFIX)
+        {
+            return Action::Keep;
+        }
+
+        match self.unknown
+        {
+            Unknown::Error => Action::Reject,
+            Unknown::Keep => Action::Keep,
+            Unknown::Strip => Action::Strip,
+        }
+    }
+}
+
+impl fmt::Debug for Registry
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        let mut tags: Vec<_> = self.constructors.keys().collect();
+        tags.sort();
+
+        f.debug_struct("Registry")
+            .field("constructors", &tags)
+            .field("unknown", &self.unknown)
+            .finish()
+    }
+}
+
+/// The policy applied to a node whose tag is not
+/// registered, and is not part of the YAML schema
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Unknown
+{
+    /// Return an error
+    Error,
+    /// Keep the node and its tag as is
+    Keep,
+    /// Keep the node, but remove its tag
+    Strip,
+}
+
+impl Default for Unknown
+{
+    fn default() -> Self
+    {
+        Self::Keep
+    }
+}
+
+/// What to do with a tagged node
+pub(crate) enum Action<'r>
+{
+    /// Use the node as is
+    Keep,
+    /// Remove the node's tag
+    Strip,
+    /// Error, the tag is unknown
+    Reject,
+    /// Replace the node with the constructor's output
+    Construct(&'r Constructor),
+}
+
+type Constructor = Rc<dyn Fn(&Node<'_>) -> ConstructResult>;
+
+#[cfg(test)]
+mod tests
+{
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn specific(uri: &str) -> ResolvedTag<'_>
+    {
+        ResolvedTag::Specific(uri.into())
+    }
+
+    #[test]
+    fn action_by_policy()
+    {
+        let mut registry = Registry::new();
+        registry.insert("!Ref", |_| Ok(Yaml::Sequence(Vec::new())));
+
+        for &(policy, expected) in &[
+            (Unknown::Error, "reject"),
+            (Unknown::Keep, "keep"),
+            (Unknown::Strip, "strip"),
+        ]
+        {
+            registry.set_unknown(policy);
+
+            assert_eq!(describe(registry.action(&specific("!Ref"))), "construct");
+            assert_eq!(describe(registry.action(&specific("!Sub"))), expected);
+            assert_eq!(
+                describe(registry.action(&specific("tag:yaml.org,2002:str"))),
+                "keep"
+            );
+            assert_eq!(describe(registry.action(&ResolvedTag::Question)), "keep");
+            assert_eq!(describe(registry.action(&ResolvedTag::Exclamation)), "keep");
+        }
+    }
+
+    #[test]
+    fn registration()
+    {
+        let mut registry = Registry::new();
+        registry
+            .insert("!a", |_| Ok(Yaml::Sequence(Vec::new())))
+            .insert("!b", |_| Ok(Yaml::Sequence(Vec::new())));
+
+        assert!(registry.contains("!a"));
+        assert!(registry.remove("!a"));
+        assert!(!registry.remove("!a"));
+        assert!(!registry.contains("!a"));
+        assert_eq!(registry.unknown(), Unknown::Keep);
+        assert_eq!(
+            format!("{:?}", registry),
+            "Registry { constructors: [\"!b\"], unknown: Keep }"
+        );
+    }
+
+    fn describe(action: Action<'_>) -> &'static str
+    {
+        match action
+        {
+            Action::Keep => "keep",
+            Action::Strip => "strip",
+            Action::Reject => "reject",
+            Action::Construct(_) => "construct",
+        }
+    }
+}